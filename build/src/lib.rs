@@ -0,0 +1,105 @@
+//! `include-sql-build` is [include-sql](https://crates.io/crates/include-sql) for build scripts.
+//! It parses the SQL file like `include_sql!` does and writes the generated items into `OUT_DIR`,
+//! where they can be inspected and from where the crate includes them. Procedural macros are
+//! then not needed, nor do they have to read files during the build.
+//!
+//! `build.rs`
+//! ```rust,no_run
+//! fn main() -> std::io::Result<()> {
+//!     include_sql_build::include_sql("src/crew.sql", r#""$", functions = postgres"#)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! `src/lib.rs`
+//! ```rust,ignore
+//! include!(concat!(env!("OUT_DIR"), "/crew.rs"));
+//! ```
+//!
+//! The arguments are the ones that follow the path in `include_sql!`. The path is relative to
+//! the package root. The generated code uses `include-sql-helper`, thus the package needs it as
+//! a dependency, with the features that the enabled `include-sql` features would enable.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use syn::{Item, LitStr};
+use include_sql_codegen::IncludeSql;
+
+/// Returns the code that `include_sql!` generates for the SQL file and the arguments that
+/// follow its path - `"$", functions = postgres`. Errors in the SQL are returned together as
+/// one `InvalidData` error.
+pub fn generate(path: &str, args: &str) -> io::Result<String> {
+    let input = if args.trim().is_empty() { format!("{:?}", path) } else { format!("{:?}, {}", path, args) };
+    let include : IncludeSql = syn::parse_str(&input).map_err(|err| invalid_data(format!("{}: {}", path, err)))?;
+    let code = include_sql_codegen::include_sql(include);
+    let file : syn::File = syn::parse2(code).map_err(|err| invalid_data(format!("{}: cannot parse the generated code: {}", path, err)))?;
+    let mut errors = Vec::new();
+    collect_errors(&file.items, &mut errors);
+    if !errors.is_empty() {
+        return Err(invalid_data(errors.join("\n")));
+    }
+    Ok(include_sql_codegen::source_text(&file.items))
+}
+
+/// Writes the code that `include_sql!` generates for the SQL file into `OUT_DIR`. The file is
+/// named after the SQL one - `src/crew.sql` is written to `crew.rs`. Returns the path of the
+/// written file.
+///
+/// Cargo is told to rerun the build script when the SQL file changes.
+pub fn include_sql(path: &str, args: &str) -> io::Result<PathBuf> {
+    let out_dir = env::var_os("OUT_DIR").ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "OUT_DIR is not set - include_sql is called outside of a build script"))?;
+    include_sql_into(path, args, Path::new(&out_dir))
+}
+
+/// Writes the code that `include_sql!` generates for the SQL file into the `out_dir` directory,
+/// like `include_sql` does for `OUT_DIR`. Returns the path of the written file.
+pub fn include_sql_into(path: &str, args: &str, out_dir: &Path) -> io::Result<PathBuf> {
+    let code = generate(path, args)?;
+    let module = include_sql_codegen::module_name(path).ok_or_else(|| invalid_data(format!("cannot name the generated file after `{}`", path)))?;
+    let out_path = out_dir.join(format!("{}.rs", module));
+    fs::write(&out_path, code)?;
+    println!("cargo:rerun-if-changed={}", path);
+    Ok(out_path)
+}
+
+/// Regenerates the schema snapshot of the `schema` option from the database that `DATABASE_URL`
+/// points to - the SQL file and the arguments are the ones of `include_sql!`. The macros only
+/// read the snapshot, thus it is refreshed by a build script, or a small binary, that calls
+/// this function. Needs the `verify-postgres` and `schema-snapshot` features.
+///
+/// ```rust,no_run
+/// fn main() -> std::io::Result<()> {
+///     include_sql_build::refresh_schema("src/crew.sql", r#"dialect = postgres, schema = "sql/schema.json""#)
+/// }
+/// ```
+pub fn refresh_schema(path: &str, args: &str) -> io::Result<()> {
+    let input = if args.trim().is_empty() { format!("{:?}", path) } else { format!("{:?}, {}", path, args) };
+    let include : IncludeSql = syn::parse_str(&input).map_err(|err| invalid_data(format!("{}: {}", path, err)))?;
+    include.refresh_schema().map_err(invalid_data)
+}
+
+/// Collects messages of the unconditional `compile_error!` items, which the macros generate
+/// for the errors in the SQL.
+fn collect_errors(items: &[Item], errors: &mut Vec<String>) {
+    for item in items {
+        match item {
+            Item::Macro(item) if item.attrs.is_empty() && item.mac.path.is_ident("compile_error") => {
+                if let Ok( msg ) = syn::parse2::<LitStr>(item.mac.tts.clone()) {
+                    errors.push(msg.value());
+                }
+            }
+            Item::Mod(item) => {
+                if let Some( (_, items) ) = &item.content {
+                    collect_errors(items, errors);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,48 @@
+-- name: select_ship
+SELECT id, name FROM ships WHERE id = :id
+
+-- name: select_ship_info
+SELECT id, info FROM ships WHERE id = :id
+
+-- name: select_crew
+SELECT id, name FROM sailors
+
+-- name: select_crew_kind
+SELECT id, kind FROM sailors
+
+-- name: delete_ship!
+-- idempotent
+DELETE FROM ships WHERE id = :id
+
+-- name: delete_ship_idempotent!
+DELETE FROM ships WHERE id = :id AND retired
+
+-- name: update_ship!
+UPDATE ships SET name = :name WHERE id = :id
+
+-- name: update_ship_idempotent!
+UPDATE ships SET name = :name WHERE id = :id AND NOT retired
+
+-- name: select_port
+SELECT id, name FROM ports
+
+-- name: select_port_fingerprint
+SELECT id, fingerprint FROM ports
+
+-- name: list
+SELECT id, name FROM docks
+
+-- name: list_col_id
+SELECT id FROM docks
+
+-- name: list_columns
+SELECT count(*) FROM docks
+
+-- name: select_dock
+SELECT id, name FROM docks WHERE port_id = :port
+
+-- name: select_dock_param_count
+SELECT count(*) FROM docks WHERE port_id = :port
+
+-- name: select_dock_params
+SELECT port_id FROM docks WHERE id = :dock
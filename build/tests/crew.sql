@@ -0,0 +1,8 @@
+-- name: select_ship_crew
+-- Selects ship crew (sailors of a given ship)
+SELECT id, name, rank
+  FROM sailors
+ WHERE ship_id = :ship
+
+-- name: insert_sailor!
+INSERT INTO sailors (name, rank, ship_id) VALUES (:name, :rank, :ship)
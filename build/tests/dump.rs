@@ -0,0 +1,21 @@
+//! Checks that the generated code is dumped into `INCLUDE_SQL_DUMP`. The variable is read by
+//! every expansion, thus the test has its own test binary, where no other test runs while it
+//! is set.
+
+use include_sql_build::generate;
+
+#[test]
+fn expanded_code_is_dumped() {
+    let dump_dir = std::env::temp_dir().join("include-sql-dump-test");
+    std::env::set_var("INCLUDE_SQL_DUMP", &dump_dir);
+    let code = generate("tests/crew.sql", r#""$""#).unwrap();
+    let dumped = std::fs::read_to_string(dump_dir.join("crew.rs")).unwrap();
+    assert_eq!(code, dumped);
+
+    // the dumped file is not a directory the dump can be written into
+    std::env::set_var("INCLUDE_SQL_DUMP", dump_dir.join("crew.rs"));
+    let code = generate("tests/crew.sql", r#""$""#).unwrap();
+    std::env::remove_var("INCLUDE_SQL_DUMP");
+    assert!(code.contains("UNWRITTEN_DUMP"));
+    assert!(code.contains("include-sql: cannot write"));
+}
@@ -0,0 +1,5 @@
+-- name: select_ship_crew
+SELECT id, name, rank FROM sailors WHERE ship_id = :ship
+
+-- name: select_crew
+SELECT id, name, rank FROM sailors WHERE ship_id = :ship
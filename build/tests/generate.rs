@@ -0,0 +1,90 @@
+//! Checks the code that is generated for build scripts.
+
+use include_sql_build::{generate, include_sql_into, refresh_schema};
+
+#[test]
+fn generated_items_are_the_ones_of_the_macro() {
+    let code = generate("tests/crew.sql", r#""$""#).unwrap();
+    assert!(code.contains("const SELECT_SHIP_CREW : & str = \"SELECT id, name, rank\\n  FROM sailors\\n WHERE ship_id = $1\" ;"));
+    assert!(code.contains("struct InsertSailor"));
+    assert!(code.contains("include_sql_helper :: def_args ! ( $ => using_insert_sailor_args"));
+}
+
+#[test]
+fn options_follow_the_path() {
+    let code = generate("tests/crew.sql", r#""$", module = true, export = crate::crew"#).unwrap();
+    assert!(code.starts_with("# [ macro_use ] pub mod crew {"));
+    assert!(code.contains("pub const SELECT_SHIP_CREW"));
+}
+
+#[test]
+fn errors_in_sql_are_returned() {
+    let err = generate("tests/bad.sql", r#""$""#).unwrap_err();
+    assert_eq!("tests/bad.sql:5: statement `select_ship_crew` is already defined at line 2", err.to_string());
+    let err = generate("tests/crew.sql", r#""$", args = owned"#).unwrap_err();
+    assert!(err.to_string().starts_with("tests/crew.sql: unknown args mode `owned`"));
+}
+
+#[test]
+fn colliding_constants_are_reported() {
+    let err = generate("tests/colliding.sql", r#""$""#).unwrap_err();
+    let msg = err.to_string();
+    let errors : Vec<&str> = msg.lines().collect();
+    assert_eq!(vec![
+        "tests/colliding.sql:5: `select_ship_info`: constant `SELECT_SHIP_INFO` is also generated for `select_ship` at tests/colliding.sql:2 - rename one of the statements",
+        "tests/colliding.sql:11: `select_crew_kind`: constant `SELECT_CREW_KIND` is also generated for `select_crew` at tests/colliding.sql:8 - rename one of the statements",
+        "tests/colliding.sql:18: `delete_ship_idempotent`: constant `DELETE_SHIP_IDEMPOTENT` is also generated for `delete_ship` at tests/colliding.sql:15 - rename one of the statements",
+        "tests/colliding.sql:30: `select_port_fingerprint`: constant `SELECT_PORT_FINGERPRINT` is also generated for `select_port` at tests/colliding.sql:27 - rename one of the statements",
+        "tests/colliding.sql:36: `list_col_id`: constant `LIST_COL_ID` is also generated for `list` at tests/colliding.sql:33 - rename one of the statements",
+        "tests/colliding.sql:39: `list_columns`: constant `LIST_COLUMNS` is also generated for `list` at tests/colliding.sql:33 - rename one of the statements",
+        "tests/colliding.sql:45: `select_dock_param_count`: constant `SELECT_DOCK_PARAM_COUNT` is also generated for `select_dock` at tests/colliding.sql:42 - rename one of the statements",
+        "tests/colliding.sql:48: `select_dock_params`: constant `SELECT_DOCK_PARAMS` is also generated for `select_dock` at tests/colliding.sql:42 - rename one of the statements",
+    ], errors);
+}
+
+#[test]
+fn code_is_written_into_out_dir() {
+    let out_dir = std::env::temp_dir().join("include-sql-build-test");
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let path = include_sql_into("tests/crew.sql", r#""?""#, &out_dir).unwrap();
+    assert_eq!(out_dir.join("crew.rs"), path);
+    let code = std::fs::read_to_string(path).unwrap();
+    assert!(code.contains("WHERE ship_id = ?1"));
+}
+
+#[test]
+fn manifest_lists_statements() {
+    let manifest_path = std::env::temp_dir().join("include-sql-manifest-test").join("crew.json");
+    let args = format!(r#""$", manifest = {:?}"#, manifest_path.to_str().unwrap());
+    generate("tests/crew.sql", &args).unwrap();
+    let manifest : serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    assert_eq!("tests/crew.sql", manifest["file"]);
+    let statements = manifest["statements"].as_array().unwrap();
+    assert_eq!(2, statements.len());
+    assert_eq!("select_ship_crew", statements[0]["name"]);
+    assert_eq!("tests/crew.sql", statements[0]["file"]);
+    assert_eq!(3, statements[0]["line"]);
+    assert_eq!("query", statements[0]["kind"]);
+    assert_eq!(serde_json::json!(["ship"]), statements[0]["params"]);
+    assert_eq!("$1", statements[0]["placeholder"]);
+    assert_eq!("SELECT id, name, rank\n  FROM sailors\n WHERE ship_id = $1", statements[0]["sql"]);
+    assert_eq!(16, statements[0]["fingerprint"].as_str().unwrap().len());
+    assert_eq!("insert_sailor", statements[1]["name"]);
+    assert_eq!("execute", statements[1]["kind"]);
+    assert_eq!(serde_json::json!(["name", "rank", "ship"]), statements[1]["params"]);
+}
+
+#[test]
+fn paths_are_resolved_against_the_root() {
+    let code = generate("tests/crew.sql", r#""$", root = "crate""#).unwrap();
+    assert!(code.contains("struct InsertSailor"));
+    let err = generate("crew.sql", r#""$", root = "file""#).unwrap_err();
+    assert!(err.to_string().ends_with("the file with the macro call is only known to the macro"));
+}
+
+#[test]
+fn schema_refresh_needs_the_snapshot_path() {
+    let err = refresh_schema("tests/crew.sql", r#""$""#).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+    assert_eq!("tests/crew.sql: `schema` option with the path to the snapshot is missing", err.to_string());
+}
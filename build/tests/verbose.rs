@@ -0,0 +1,29 @@
+//! Checks what the expansion reports when `INCLUDE_SQL_VERBOSE` is set. The variable is read by
+//! every expansion, thus the test has its own test binary, where no other test runs while it
+//! is set.
+
+use include_sql_build::generate;
+
+#[test]
+fn statements_are_noted() {
+    let code = generate("tests/duplicates.sql", r#""$""#).unwrap();
+    assert!(!code.contains("DUPLICATE_TEXT"));
+
+    let notes_dir = std::env::temp_dir().join(format!("include-sql-notes-{}", std::process::id()));
+    std::env::set_var("INCLUDE_SQL_VERBOSE", &notes_dir);
+    let code = generate("tests/duplicates.sql", r#""$""#).unwrap();
+    std::env::remove_var("INCLUDE_SQL_VERBOSE");
+    assert!(code.contains("const SELECT_CREW : & str = SELECT_SHIP_CREW ;"));
+    assert!(code.contains("# [ deprecated ( note = \"include-sql: tests/duplicates.sql:5: `select_crew` has the same text as `select_ship_crew`\" ) ] const DUPLICATE_TEXT"));
+
+    let notes = std::fs::read_to_string(notes_dir.join("duplicates.notes")).unwrap();
+    std::fs::remove_dir_all(&notes_dir).unwrap();
+    assert_eq!(
+        "tests/duplicates.sql: 2 statement(s)\n\
+         tests/duplicates.sql:2: `select_ship_crew` - query\n\
+         tests/duplicates.sql:2:   `ship` - scalar, bound as argument 1\n\
+         tests/duplicates.sql:5: `select_crew` - query\n\
+         tests/duplicates.sql:5:   `ship` - scalar, bound as argument 1\n",
+        notes
+    );
+}
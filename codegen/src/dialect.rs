@@ -0,0 +1,116 @@
+/// Describes how the database interface tags positional SQL parameters.
+#[derive(Clone)]
+pub(crate) struct Placeholder {
+    pub(crate) prefix: String,
+    pub(crate) numbering: Numbering,
+    /// Whether a repeated parameter reuses the number of its first occurrence
+    pub(crate) dedup: bool,
+    /// Whether parameters are bound by their names. The SQL is left untouched and the prefix
+    /// is the one the database interface expects in front of the argument names.
+    pub(crate) named: bool
+}
+
+/// Numbering style of positional parameters.
+#[derive(Clone, Copy)]
+pub(crate) enum Numbering {
+    /// Placeholders are numbered starting from the specified number. Unless
+    /// deduplication is turned off, each named parameter is bound once even
+    /// if it is used several times.
+    From(usize),
+    /// Placeholders are not numbered. The argument is bound for each occurrence
+    /// of the parameter.
+    None
+}
+
+impl Placeholder {
+    pub(crate) fn numbered(prefix: &str) -> Self {
+        Placeholder { prefix: prefix.to_string(), numbering: Numbering::From(1), dedup: true, named: false }
+    }
+
+    pub(crate) fn anonymous(prefix: &str) -> Self {
+        Placeholder { prefix: prefix.to_string(), numbering: Numbering::None, dedup: false, named: false }
+    }
+}
+
+/// Database presets with the placeholder style of each backend baked in.
+#[derive(Clone, Copy)]
+pub(crate) enum Dialect {
+    Postgres,
+    Sqlite,
+    MySql,
+    Oracle,
+    MsSql
+}
+
+impl Dialect {
+    pub(crate) const NAMES : &'static str = "postgres, sqlite, mysql, oracle, mssql";
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "postgres" => Some( Dialect::Postgres ),
+            "sqlite"   => Some( Dialect::Sqlite ),
+            "mysql"    => Some( Dialect::MySql ),
+            "oracle"   => Some( Dialect::Oracle ),
+            "mssql"    => Some( Dialect::MsSql ),
+            _ => None
+        }
+    }
+
+    /// Returns the prefix of argument names for the backends that bind arguments by name.
+    pub(crate) fn name_prefix(&self) -> Option<&'static str> {
+        match self {
+            Dialect::Sqlite => Some(":"),
+            Dialect::Oracle => Some(""),
+            _ => None
+        }
+    }
+
+    /// Returns the prefix that turns a statement into the request of its execution plan.
+    pub(crate) fn explain_prefix(&self, analyze: bool) -> Option<&'static str> {
+        match (self, analyze) {
+            (Dialect::Postgres, false) | (Dialect::MySql, false) => Some("EXPLAIN "),
+            (Dialect::Postgres, true)  | (Dialect::MySql, true)  => Some("EXPLAIN ANALYZE "),
+            (Dialect::Sqlite, false) => Some("EXPLAIN QUERY PLAN "),
+            (Dialect::Oracle, false) => Some("EXPLAIN PLAN FOR "),
+            _ => None
+        }
+    }
+
+    /// Returns the clause that limits the rows of a query to a page - `-- paginate`.
+    pub(crate) fn pagination(&self) -> &'static str {
+        match self {
+            Dialect::Oracle | Dialect::MsSql => "OFFSET :offset ROWS FETCH NEXT :limit ROWS ONLY",
+            _ => "LIMIT :limit OFFSET :offset"
+        }
+    }
+
+    /// Returns whether the database interfaces of the backend reject statements that end with
+    /// a semicolon - OCI does.
+    pub(crate) fn strips_semicolons(&self) -> bool {
+        matches!(self, Dialect::Oracle)
+    }
+
+    /// Returns whether the statement cannot be prepared and thus is executed as is. MySQL, for
+    /// instance, loads files only via its text protocol - `LOAD DATA LOCAL INFILE`.
+    pub(crate) fn is_unprepared(&self, stmt_text: &str) -> bool {
+        match self {
+            Dialect::MySql => {
+                let mut words = stmt_text.split_whitespace();
+                let first = words.next().unwrap_or_default();
+                let second = words.next().unwrap_or_default();
+                first.eq_ignore_ascii_case("LOAD") && (second.eq_ignore_ascii_case("DATA") || second.eq_ignore_ascii_case("XML"))
+            }
+            _ => false
+        }
+    }
+
+    pub(crate) fn placeholder(&self) -> Placeholder {
+        match self {
+            Dialect::Postgres => Placeholder::numbered("$"),
+            Dialect::Sqlite   => Placeholder::numbered("?"),
+            Dialect::MySql    => Placeholder::anonymous("?"),
+            Dialect::Oracle   => Placeholder::numbered(":"),
+            Dialect::MsSql    => Placeholder::numbered("@p"),
+        }
+    }
+}
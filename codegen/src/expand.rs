@@ -0,0 +1,3554 @@
+//! Expansion of the macros - parsing of their arguments and generation of the items of the
+//! included statements.
+
+use proc_macro2::{Span, TokenStream};
+use syn::{Token, Lit, LitStr, LitBool, LitInt, Ident, Expr, Error, TypeParamBound, Item, ItemMod, ImplItem, Visibility};
+use syn::punctuated::Punctuated;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
+use syn::ext::IdentExt;
+use quote::quote;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::dialect::{Placeholder, Numbering, Dialect};
+use crate::{sql, validate, verify, schema, usage, manifest};
+
+/// Expands `include_sql!`, whose items are placed into the module named after the SQL file when
+/// it has the `module` option.
+pub fn include_sql(include: IncludeSql) -> TokenStream {
+    let dump_name = module_name(&include.path).map(|name| name.to_string());
+    let code = if include.options.module { include_module(include) } else { expand_exported(include) };
+    dump(dump_name, code)
+}
+
+/// Expands `include_sql!` with the `module` option.
+fn include_module(include: IncludeSql) -> TokenStream {
+    let module = match module_name(&include.path) {
+        Some( module ) => module,
+        None => {
+            let msg = format!("cannot name a module after `{}`", include.path);
+            return Error::new(Span::call_site(), msg).to_compile_error();
+        }
+    };
+    let vis = module_item_visibility(&include.options);
+    let module_vis = if include.options.export.is_some() { quote! { pub } } else { quote! {} };
+    let code = match with_visibility(expand(include), &vis) {
+        Ok( code ) => code,
+        Err( err ) => return err.to_compile_error()
+    };
+    // argument macros stay usable after the module, like they are without it
+    quote! {
+        #[macro_use]
+        #module_vis mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #( #code )*
+        }
+    }
+}
+
+/// Expands `inline_sql!`.
+pub fn inline_sql(include: IncludeSql) -> TokenStream {
+    // inline SQL has no file to name the dump after
+    let dump_name = include.statements.first().map(|stmt| format!("inline_{}", stmt.name));
+    let code = expand_exported(include);
+    dump(dump_name, code)
+}
+
+/// Expands `include_sql_mod` - the items are placed into the annotated module.
+pub fn include_sql_mod(include: IncludeSql, module: ItemMod) -> TokenStream {
+    let dump_name = module_name(&include.path).map(|name| name.to_string());
+    let code = expand_mod(include, module);
+    dump(dump_name, code)
+}
+
+fn expand_mod(include: IncludeSql, module: ItemMod) -> TokenStream {
+    if include.options.module {
+        let msg = "`include_sql_mod` already includes the SQL into a module, thus it cannot have the `module` option";
+        return Error::new(module.ident.span(), msg).to_compile_error();
+    }
+    let ItemMod { attrs, vis, mod_token, ident, content, .. } = module;
+    let items = match content {
+        Some( (_, items) ) => items,
+        None => {
+            let msg = "`include_sql_mod` needs an inline module - `mod crew {}`";
+            return Error::new(ident.span(), msg).to_compile_error();
+        }
+    };
+    let item_vis = module_item_visibility(&include.options);
+    let code = match with_visibility(expand(include), &item_vis) {
+        Ok( code ) => code,
+        Err( err ) => return err.to_compile_error()
+    };
+    quote! {
+        #( #attrs )*
+        #vis #mod_token #ident {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #( #code )*
+            #( #items )*
+        }
+    }
+}
+
+/// Writes the expanded code into the directory that the `INCLUDE_SQL_DUMP` environment variable
+/// names, to the file named after the SQL one - `crew.rs`. A file that cannot be written is
+/// reported as a compiler warning, as the code itself is fine.
+fn dump(name: Option<String>, code: TokenStream) -> TokenStream {
+    let dir = match std::env::var_os("INCLUDE_SQL_DUMP") {
+        Some( dir ) => std::path::PathBuf::from(dir),
+        None => return code
+    };
+    let name = match name {
+        Some( name ) => name,
+        None => return code
+    };
+    let text = match syn::parse2::<syn::File>(code.clone()) {
+        Ok( file ) => source_text(&file.items),
+        Err( _ ) => code.to_string()
+    };
+    let path = dir.join(format!("{}.rs", name));
+    match std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, text)) {
+        Ok( _ ) => code,
+        Err( err ) => {
+            let warning = warning("UNWRITTEN_DUMP", &format!("include-sql: cannot write {}: {}", path.display(), err));
+            quote! { #code #warning }
+        }
+    }
+}
+
+/// Returns the source text of the generated items - an item per line keeps it readable without
+/// a formatter.
+pub fn source_text(items: &[Item]) -> String {
+    let lines : Vec<String> = items.iter().map(|item| quote!(#item).to_string()).collect();
+    lines.join("\n")
+}
+
+/// Returns the name of the module for the items of the SQL file - the file name without the
+/// extension, where characters that cannot be in identifiers are replaced by `_`.
+pub fn module_name(path: &str) -> Option<Ident> {
+    let stem = std::path::Path::new(path).file_stem()?.to_str()?;
+    let name : String = stem.chars().map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        None
+    } else {
+        Some(Ident::new(&name, Span::call_site()))
+    }
+}
+
+/// Allows constants and statics named after verbatim statement names.
+fn allow_lowercase_globals(code: TokenStream) -> TokenStream {
+    let mut file : syn::File = match syn::parse2(code.clone()) {
+        Ok( file ) => file,
+        Err( _ ) => return code
+    };
+    let allow : syn::Attribute = syn::parse_quote!(#[allow(non_upper_case_globals)]);
+    for item in &mut file.items {
+        match item {
+            Item::Const(item) => item.attrs.push(allow.clone()),
+            Item::Static(item) => item.attrs.push(allow.clone()),
+            _ => {}
+        }
+    }
+    let items = file.items;
+    quote! {
+        #( #items )*
+    }
+}
+
+/// Expands the statements, which are public when they are exported to other crates.
+fn expand_exported(include: IncludeSql) -> TokenStream {
+    if include.options.export.is_none() {
+        return expand(include);
+    }
+    match with_visibility(expand(include), &syn::parse_quote!(pub)) {
+        Ok( code ) => quote! { #( #code )* },
+        Err( err ) => err.to_compile_error()
+    }
+}
+
+/// Returns the visibility of the items generated for the module form - they are visible in the
+/// parent module, or everywhere when they are exported to other crates.
+fn module_item_visibility(options: &Options) -> Visibility {
+    if options.export.is_some() {
+        syn::parse_quote!(pub)
+    } else {
+        syn::parse_quote!(pub(super))
+    }
+}
+
+/// Makes generated items visible outside of the module they are in.
+fn with_visibility(code: TokenStream, vis: &Visibility) -> Result<Vec<Item>> {
+    let mut file : syn::File = syn::parse2(code)?;
+    for item in &mut file.items {
+        match item {
+            Item::Const(item) => item.vis = vis.clone(),
+            Item::Static(item) => item.vis = vis.clone(),
+            Item::Fn(item) => item.vis = vis.clone(),
+            Item::Trait(item) => item.vis = vis.clone(),
+            Item::Struct(item) => {
+                item.vis = vis.clone();
+                for field in item.fields.iter_mut() {
+                    field.vis = vis.clone();
+                }
+            }
+            Item::Impl(item) if item.trait_.is_none() => {
+                for impl_item in &mut item.items {
+                    if let ImplItem::Method(method) = impl_item {
+                        method.vis = vis.clone();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(file.items)
+}
+
+/// Expands the statements of each dialect under the `cfg` of its feature. The first dialect is
+/// the default one, which is used when none of the features of the other dialects is enabled,
+/// thus it does not need a feature of its own.
+fn expand_variants(path: &str, variants: Vec<(String, IncludeSql)>) -> TokenStream {
+    // errors are reported whichever dialect is selected, and the ones in the shared statements once
+    let mut errors : Vec<&Error> = Vec::new();
+    for (_, include) in &variants {
+        for err in &include.errors {
+            if !errors.iter().any(|reported| reported.to_string() == err.to_string()) {
+                errors.push(err);
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return errors.iter().map(|err| err.to_compile_error()).collect();
+    }
+    let features : Vec<String> = variants.iter().map(|(name, _)| name.clone()).collect();
+    let mut code = Vec::new();
+    for (idx, first) in features.iter().enumerate().skip(1) {
+        for other in &features[idx + 1..] {
+            let msg = format!("features `{}` and `{}` select different dialects of {}", first, other, path);
+            code.push(quote! {
+                #[cfg(all(feature = #first, feature = #other))]
+                compile_error!(#msg);
+            });
+        }
+    }
+    for (idx, (_, include)) in variants.into_iter().enumerate() {
+        let cfg = if idx == 0 {
+            let others = &features[1..];
+            quote! { #[cfg(not(any( #( feature = #others ),* )))] }
+        } else {
+            let feature = &features[idx];
+            quote! { #[cfg(feature = #feature)] }
+        };
+        code.push(with_attrs(expand(include), &cfg, &[]));
+    }
+    quote! {
+        #( #code )*
+    }
+}
+
+/// Returns the `cfg` attribute of a statement with `-- cfg:` predicates - all of them have to
+/// hold - or nothing.
+fn cfg_attr(predicates: &[syn::NestedMeta]) -> TokenStream {
+    match predicates {
+        [] => quote! {},
+        [predicate] => quote! { #[cfg(#predicate)] },
+        _ => quote! { #[cfg(all( #( #predicates ),* ))] }
+    }
+}
+
+/// Puts the `cfg` attribute on each of the items of the code, and the other attributes on all
+/// but the impl blocks, which do not accept most of them - e.g. `deprecated`. Code that is not
+/// a list of items (e.g. `compile_error!` of the errors) is returned as is.
+fn with_attrs(code: TokenStream, cfg: &TokenStream, attrs: &[syn::Meta]) -> TokenStream {
+    // items of a deprecated statement refer to each other
+    let allow = allow_deprecated(attrs.iter().any(|attr| attr.name() == "deprecated"));
+    match syn::parse2::<syn::File>(code.clone()) {
+        Ok( file ) => file.items.into_iter().map(|item| match item {
+            syn::Item::Impl(_) => quote! { #cfg #allow #item },
+            _ => quote! { #cfg #allow #( #[#attrs] )* #item }
+        }).collect(),
+        Err( _ ) => code
+    }
+}
+
+/// Returns `#[allow(deprecated)]` for the items that refer to deprecated statements.
+fn allow_deprecated(deprecated: bool) -> TokenStream {
+    if deprecated {
+        quote! { #[allow(deprecated)] }
+    } else {
+        quote! {}
+    }
+}
+
+fn expand(include: IncludeSql) -> TokenStream {
+    if !include.errors.is_empty() {
+        return include.errors.iter().map(Error::to_compile_error).collect();
+    }
+    if !include.variants.is_empty() {
+        return expand_variants(&include.path, include.variants);
+    }
+    let IncludeSql { path, statements, placeholder, dialect, options, .. } = include;
+    let mut code = Vec::new();
+    let notes_dir = std::env::var_os("INCLUDE_SQL_VERBOSE");
+    let verbose = notes_dir.is_some();
+    let mut notes = Vec::new();
+    if verbose {
+        statement_notes(&path, &statements, &placeholder, &mut notes);
+    }
+    let notes_name = if path == INLINE_PATH {
+        statements.first().map(|stmt| format!("inline_{}", stmt.name))
+    } else {
+        module_name(&path).map(|name| name.to_string())
+    };
+    add_missing_param_warnings(&path, &statements, &mut code);
+    let mut texts : Vec<(String, String, Ident, String)> = Vec::with_capacity(statements.len());
+    // first code item, `cfg` and other attributes of each statement
+    let mut stmt_starts = Vec::new();
+    let mut deprecated = false;
+    // texts of the SQL files that statements are reloaded from
+    let mut sql_files = HashMap::new();
+    let mut healthchecks = Vec::new();
+    let mut migrations = Vec::new();
+    let mut registry = Vec::new();
+    let sql_item = if options.static_items { quote! { static } } else { quote! { const } };
+    add_param_groups(&path, &statements, &mut code);
+
+    for stmt in statements {
+        let line_num = stmt.lines.first().copied().unwrap_or_default();
+        let sql::Stmt { name, kind, const_name, text, params, cache_ttl, idempotent, generate, healthcheck, diesel, row, columns, column_names, script, migration, file, cfg, attrs, .. } = stmt;
+        // statements of the dialect variant of the file are located in it
+        let stmt_path = file.as_deref().unwrap_or(&path);
+        let cfg = cfg_attr(&cfg);
+        let cfg_key = cfg.to_string();
+        let attrs : Vec<syn::Meta> = options.attrs.iter().cloned().chain(attrs).collect();
+        let stmt_deprecated = attrs.iter().any(|attr| attr.name() == "deprecated");
+        deprecated |= stmt_deprecated;
+        stmt_starts.push((code.len(), cfg.clone(), attrs));
+        // statements with the same text share the literal of the first one, unless it might be
+        // left out or is deprecated
+        if let Some( (_, first_name, first_const, _) ) = texts.iter().find(|(first_text, _, _, first_cfg)| *first_text == text && (first_cfg.is_empty() || *first_cfg == cfg_key)) {
+            if verbose {
+                let msg = format!("include-sql: {}:{}: `{}` has the same text as `{}`", stmt_path, line_num, name, first_name);
+                code.push(warning("DUPLICATE_TEXT", &msg));
+            }
+            code.push(quote! {
+                #sql_item #const_name : &str = #first_const;
+            });
+        } else {
+            code.push(quote! {
+                #sql_item #const_name : &str = #text;
+            });
+            if !stmt_deprecated {
+                texts.push((text.clone(), name.clone(), const_name.clone(), cfg_key));
+            }
+        }
+        if options.registry {
+            registry.push(quote! { #cfg (#name, #const_name) });
+        }
+        add_kind(kind, &const_name, &mut code);
+        add_info(stmt_path, &name, line_num, params.as_ref(), &const_name, &mut code);
+        if idempotent {
+            add_idempotent(&const_name, &mut code);
+        }
+        add_fingerprint(&text, &const_name, &mut code);
+        add_bound_params(params.as_ref(), &placeholder, &const_name, &mut code);
+        if let Some( columns ) = columns {
+            add_columns(columns, &column_names, &const_name, &mut code);
+        }
+        if let Some( script ) = &script {
+            add_script(script, &const_name, &mut code);
+        }
+        if let Some( ttl ) = cache_ttl {
+            add_cache_ttl(ttl, &const_name, &mut code);
+        }
+        for item in generate {
+            add_explain(item, dialect.as_ref(), &const_name, &text, &mut code);
+        }
+        if let Some( time_limit ) = healthcheck {
+            let item = healthcheck_item(&name, &const_name, time_limit);
+            healthchecks.push(quote! { #cfg #item });
+        }
+        if let Some( migration ) = &migration {
+            let item = migration_item(&name, &const_name, migration);
+            migrations.push((migration.version, quote! { #cfg #item }));
+        }
+        if cfg!(feature = "inventory") {
+            add_inventory_item(stmt_path, &name, idempotent, &const_name, &text, &mut code);
+        }
+        if cfg!(feature = "tracing") {
+            add_span_fn(stmt_path, &name, line_num, params.as_ref(), &options, &mut code);
+        }
+        if options.hot_reload {
+            let reload = reload_spec(stmt_path, &name, &text, params.as_ref(), &placeholder, &mut sql_files);
+            if reload.is_none() && verbose {
+                notes.push(format!("{}:{}: `{}` cannot be reloaded, its `_sql()` returns the compiled text", stmt_path, line_num, name));
+            }
+            add_sql_fn(&name, &const_name, reload, &placeholder, &options, &mut code);
+        }
+        match options.functions {
+            // drivers cannot execute the script as one statement, thus it gets no functions
+            _ if script.is_some() => {}
+            // migrations are applied by the migration runner
+            _ if migration.is_some() => {}
+            Some(Functions::Rusqlite) => add_rusqlite_fn(&name, kind, &const_name, params.as_ref(), columns, &options, &mut code),
+            Some(Functions::Postgres) => add_postgres_fn(&name, kind, &const_name, params.as_ref(), columns, &options, &mut code),
+            Some(Functions::TokioPostgres) => add_async_fn(&name, kind, &const_name, params.as_ref(), columns, &options, &mut code),
+            Some(Functions::Executor) => add_executor_fn(&name, kind, &const_name, params.as_ref(), &options, &mut code),
+            None => {}
+        }
+        if let (Some( functions ), Some( _ ), None, None) = (options.functions, cache_ttl, &script, &migration) {
+            add_cached_fn(functions, &name, kind, &const_name, params.as_ref(), &options, &mut code);
+        }
+        if !row.is_empty() {
+            add_row_struct(&name, &row, &options, &mut code);
+        }
+        if let Some( diesel ) = &diesel {
+            add_diesel_query(&name, &const_name, diesel, params.as_ref(), &options, &mut code);
+        }
+        if let Some( params ) = params {
+            if options.debug_args {
+                add_debug_args(&params, &options, &mut code);
+            }
+            if options.serialize_args {
+                add_serialize_args(&params, &options, &mut code);
+            }
+            if options.builder {
+                add_args_builder(&params, &mut code);
+            }
+            if options.args == ArgsMode::Refs {
+                add_args_from_macro(&params, &name, &options, &mut code);
+            }
+            if !params.pieces.is_empty() {
+                // generic arguments and named binding are rejected before statements with blocks get here
+                add_cond_params(&params, &name, &placeholder, &const_name, &mut code);
+                add_into_sql_with_args(&params, &name, idempotent, &placeholder, &const_name, &mut code);
+                continue;
+            }
+            if options.named {
+                add_named_params(&params, &name, &placeholder, &options, &mut code);
+                add_try_from_map(&params, &mut code);
+                add_owned_args(&params, &mut code);
+                continue;
+            }
+            if params.lst_params.is_empty() {
+                match options.args {
+                    ArgsMode::Refs => {
+                        add_pos_params(&params, &name, &placeholder, &const_name, &options, &mut code);
+                        if options.args_trait {
+                            add_args_trait(&params, &name, &mut code);
+                        }
+                    }
+                    ArgsMode::Generic => add_generic_pos_params(&params, &options, &mut code)
+                }
+            } else {
+                add_lst_params(&params, &name, &placeholder, &const_name, &options, &mut code);
+            }
+            if options.args == ArgsMode::Refs {
+                add_into_sql_with_args(&params, &name, idempotent, &placeholder, &const_name, &mut code);
+                add_owned_args(&params, &mut code);
+            }
+        }
+    }
+    // items of a statement are the ones from its first item to the first item of the next one
+    for (idx, (start, cfg, attrs)) in stmt_starts.iter().enumerate().filter(|(_, (_, cfg, attrs))| !cfg.is_empty() || !attrs.is_empty()) {
+        let end = stmt_starts.get(idx + 1).map_or(code.len(), |(next_start, _, _)| *next_start);
+        for item in &mut code[*start..end] {
+            *item = with_attrs(item.clone(), cfg, attrs);
+        }
+    }
+    // the statement lists refer to all statements
+    let lists_start = code.len();
+    if !healthchecks.is_empty() {
+        add_healthchecks(&healthchecks, &options, &mut code);
+    }
+    if !migrations.is_empty() {
+        migrations.sort_by_key(|(version, _)| *version);
+        let migrations : Vec<_> = migrations.into_iter().map(|(_, item)| item).collect();
+        add_migrations(&migrations, &options, &mut code);
+    }
+    if options.registry {
+        add_registry(&registry, &options, &mut code);
+    }
+    if deprecated {
+        let allow = allow_deprecated(true);
+        for item in &mut code[lists_start..] {
+            *item = with_attrs(item.clone(), &allow, &[]);
+        }
+    }
+    if let (Some( dir ), Some( name )) = (notes_dir, notes_name) {
+        write_notes(Path::new(&dir), &name, &notes, &mut code);
+    }
+    let code = quote! {
+        #( #code )*
+    };
+    if options.verbatim_names {
+        allow_lowercase_globals(code)
+    } else {
+        code
+    }
+}
+
+pub struct IncludeSql {
+    path: String,
+    statements: Vec<sql::Stmt>,
+    placeholder: Placeholder,
+    dialect: Option<Dialect>,
+    options: Options,
+    /// Errors in the SQL. `Error` holds only one message, thus they are kept here to be reported
+    /// as separate diagnostics.
+    errors: Vec<Error>,
+    /// Statements of each dialect - `dialects = ["postgres", "sqlite"]` - and the names of the
+    /// dialects, which are the cargo features that select them. The include itself then has
+    /// no statements.
+    variants: Vec<(String, IncludeSql)>
+}
+
+/// Optional `name = value` arguments of the `include_sql!`
+#[derive(Clone, Default)]
+struct Options {
+    /// Prefix of the statement names in the generated macro names
+    macro_prefix: String,
+    /// Path to the schema snapshot
+    schema: Option<String>,
+    /// Types of the argument struct fields
+    args: ArgsMode,
+    /// Additional bounds of the argument trait objects in the `generic` mode
+    arg_bounds: Vec<TypeParamBound>,
+    /// Whether repeated parameters are bound once when placeholders are numbered
+    dedup_params: Option<bool>,
+    /// Whether arguments are bound by parameter names
+    named: bool,
+    /// Numbering of placeholders that overrides the one of the prefix or dialect
+    numbering: Option<Numbering>,
+    /// Database interface for which statement functions are generated
+    functions: Option<Functions>,
+    /// Names and values of the template variables - `{{schema}}`
+    vars: Vec<(String, String)>,
+    /// Whether the `SQL_STATEMENTS` constant with the names and texts of all statements is generated
+    registry: bool,
+    /// Whether the generated items are placed into a module named after the SQL file
+    module: bool,
+    /// Whether statement constants keep the statement names verbatim instead of uppercasing them
+    verbatim_names: bool,
+    /// Prefix of the statement constant names - `SQL_`
+    const_prefix: String,
+    /// Whether statement texts are `static` items rather than constants
+    static_items: bool,
+    /// Trait of the rows from which the generic `from_row` of the row structs reads the columns
+    row_trait: Option<syn::Path>,
+    /// Prefix of the generated struct names that the `namespace` sets - `Crew`
+    struct_prefix: String,
+    /// What happens to the comments in the statement texts
+    comments: sql::Comments,
+    /// Whether statement texts are collapsed into a single line without comments
+    minify: bool,
+    /// Whether semicolons that terminate statements are removed, which overrides the dialect
+    strip_semicolons: Option<bool>,
+    /// Directory with the Rust code that must use each statement
+    unused_in: Option<String>,
+    /// Whether argument structs implement `Debug`
+    debug_args: bool,
+    /// Whether `args = generic` argument structs implement `serde::Serialize`
+    serialize_args: bool,
+    /// Whether argument structs get builders - `SelectShipCrew::builder()`
+    builder: bool,
+    /// Whether each statement gets the trait of its arguments - `SelectShipCrewArgs`
+    args_trait: bool,
+    /// Query tags of all statements - `tag = "service=crew"`
+    tags: Vec<String>,
+    /// Whether generated functions execute statements inside their tracing spans
+    instrument: bool,
+    /// Companion item that every query and data modifying statement gets - `generate = explain`
+    generate: Option<sql::Generate>,
+    /// Attributes of the items of all statements - `attrs = [allow(dead_code)]`
+    attrs: Vec<syn::Meta>,
+    /// Path from the crate root to the module of the generated items, whose argument macros are
+    /// exported to other crates - `export = crate::queries`
+    export: Option<syn::Path>,
+    /// Path to the JSON manifest of the statements
+    manifest: Option<String>,
+    /// Whether the `<statement>_sql()` functions read the statements from the SQL file in debug builds
+    hot_reload: bool,
+    /// Directory that relative paths are resolved against instead of the current directory of
+    /// the compiler - `root = "crate"`
+    root: Option<PathBuf>
+}
+
+/// Database interfaces that can execute statements via generated functions.
+#[derive(Clone, Copy, PartialEq)]
+enum Functions {
+    /// Functions that execute statements on a rusqlite `Connection`
+    Rusqlite,
+    /// Functions that execute statements via the postgres `GenericClient`
+    Postgres,
+    /// Async functions that execute statements via `include_sql_helper::AsyncClient`
+    TokioPostgres,
+    /// Functions that execute statements via `include_sql_helper::Executor`
+    Executor
+}
+
+impl Functions {
+    /// Returns the name of the option value.
+    fn name(self) -> &'static str {
+        match self {
+            Functions::Rusqlite => "rusqlite",
+            Functions::Postgres => "postgres",
+            Functions::TokioPostgres => "tokio_postgres",
+            Functions::Executor => "executor"
+        }
+    }
+
+    /// Returns whether the feature, that the generated functions need, is enabled.
+    fn is_enabled(self) -> bool {
+        match self {
+            Functions::Rusqlite => cfg!(feature = "rusqlite"),
+            Functions::Postgres => cfg!(feature = "postgres"),
+            Functions::TokioPostgres => cfg!(feature = "tokio-postgres"),
+            Functions::Executor => true
+        }
+    }
+}
+
+/// How arguments are stored in the generated argument structs.
+#[derive(Clone, PartialEq, Default)]
+enum ArgsMode {
+    /// Fields are `&dyn ToSql` references
+    #[default]
+    Refs,
+    /// Each field has its own generic type bounded by `ToSql`
+    Generic
+}
+
+
+impl Options {
+    /// Returns the name of the statement text constant.
+    fn const_name(&self, stmt_name: &str) -> Ident {
+        let name = if self.verbatim_names { stmt_name.to_string() } else { stmt_name.to_uppercase() };
+        Ident::new(&format!("{}{}", self.const_prefix, name), Span::call_site())
+    }
+
+    /// Returns the name of the statement structs without their suffixes - `SelectShipCrew`.
+    fn struct_name(&self, stmt_name: &str) -> String {
+        format!("{}{}", self.struct_prefix, sql::to_camel_case(stmt_name))
+    }
+
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut options = Options::default();
+        let mut namespace = None;
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let name: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match name.to_string().as_str() {
+                "macro_prefix" => {
+                    options.macro_prefix = parse_name_fragment(input)?;
+                }
+                "schema" => {
+                    let path: LitStr = input.parse()?;
+                    options.schema = Some(path.value());
+                }
+                "tag" => {
+                    let tags: LitStr = input.parse()?;
+                    options.tags = sql::parse_tags(&tags.value()).map_err(|msg| Error::new(tags.span(), msg))?;
+                }
+                "generate" => {
+                    let name: Ident = input.parse()?;
+                    let item = sql::Generate::from_name(&name.to_string()).ok_or_else(|| {
+                        Error::new(name.span(), format!("unknown generated item `{}`, expected one of: {}", name, sql::Generate::NAMES))
+                    })?;
+                    options.generate = Some(item);
+                }
+                "root" => {
+                    let root: LitStr = input.parse()?;
+                    options.root = Some(root_dir(&root.value()).map_err(|msg| Error::new(root.span(), msg))?);
+                }
+                "manifest" => {
+                    let path: LitStr = input.parse()?;
+                    options.manifest = Some(path.value());
+                }
+                "unused_in" => {
+                    let path: LitStr = input.parse()?;
+                    options.unused_in = Some(path.value());
+                }
+                "args" => {
+                    let mode: Ident = input.parse()?;
+                    options.args = match mode.to_string().as_str() {
+                        "refs"    => ArgsMode::Refs,
+                        "generic" => ArgsMode::Generic,
+                        _ => return Err(Error::new(mode.span(), format!("unknown args mode `{}`, expected one of: refs, generic", mode)))
+                    };
+                }
+                "dedup_params" => {
+                    let dedup: LitBool = input.parse()?;
+                    options.dedup_params = Some(dedup.value);
+                }
+                "module" => {
+                    let module: LitBool = input.parse()?;
+                    options.module = module.value;
+                }
+                "const_names" => {
+                    let case: Ident = input.parse()?;
+                    options.verbatim_names = match case.to_string().as_str() {
+                        "upper"    => false,
+                        "verbatim" => true,
+                        _ => return Err(Error::new(case.span(), format!("unknown const_names `{}`, expected one of: upper, verbatim", case)))
+                    };
+                }
+                "const_prefix" => {
+                    options.const_prefix = parse_name_fragment(input)?;
+                }
+                "namespace" => {
+                    let name = parse_name_fragment(input)?;
+                    let name = name.trim_end_matches('_');
+                    if name.is_empty() {
+                        return Err(input.error("namespace cannot be empty"));
+                    }
+                    namespace = Some(name.to_string());
+                }
+                "sql_items" => {
+                    // `const` and `static` are keywords
+                    let item = input.call(Ident::parse_any)?;
+                    options.static_items = match item.to_string().as_str() {
+                        "const"  => false,
+                        "static" => true,
+                        _ => return Err(Error::new(item.span(), format!("unknown sql_items `{}`, expected one of: const, static", item)))
+                    };
+                }
+                "comments" => {
+                    let comments: Ident = input.parse()?;
+                    options.comments = match comments.to_string().as_str() {
+                        "inline" => sql::Comments::Inline,
+                        "keep"   => sql::Comments::Keep,
+                        "strip"  => sql::Comments::Strip,
+                        _ => return Err(Error::new(comments.span(), format!("unknown comments `{}`, expected one of: inline, keep, strip", comments)))
+                    };
+                }
+                "semicolons" => {
+                    let semicolons: Ident = input.parse()?;
+                    options.strip_semicolons = match semicolons.to_string().as_str() {
+                        "keep"  => Some(false),
+                        "strip" => Some(true),
+                        _ => return Err(Error::new(semicolons.span(), format!("unknown semicolons `{}`, expected one of: keep, strip", semicolons)))
+                    };
+                }
+                "minify" => {
+                    let minify: LitBool = input.parse()?;
+                    options.minify = minify.value;
+                }
+                "debug_args" => {
+                    let debug_args: LitBool = input.parse()?;
+                    options.debug_args = debug_args.value;
+                }
+                "builder" => {
+                    let builder: LitBool = input.parse()?;
+                    options.builder = builder.value;
+                }
+                "args_trait" => {
+                    let args_trait: LitBool = input.parse()?;
+                    options.args_trait = args_trait.value;
+                }
+                "serialize_args" => {
+                    let serialize_args: LitBool = input.parse()?;
+                    if serialize_args.value && !cfg!(feature = "serde") {
+                        return Err(Error::new(serialize_args.span(), "`serialize_args` needs the `serde` feature"));
+                    }
+                    options.serialize_args = serialize_args.value;
+                }
+                "hot_reload" => {
+                    let hot_reload: LitBool = input.parse()?;
+                    options.hot_reload = hot_reload.value;
+                }
+                "instrument" => {
+                    let instrument: LitBool = input.parse()?;
+                    if instrument.value && !cfg!(feature = "tracing") {
+                        return Err(Error::new(instrument.span(), "`instrument` needs the `tracing` feature"));
+                    }
+                    options.instrument = instrument.value;
+                }
+                "attrs" => {
+                    let content;
+                    syn::bracketed!(content in input);
+                    let attrs = Punctuated::<syn::Meta, Token![,]>::parse_terminated(&content)?;
+                    options.attrs = attrs.into_iter().collect();
+                }
+                "export" => {
+                    let path: syn::Path = input.parse()?;
+                    let from_root = path.leading_colon.is_none() && path.segments.first().is_some_and(|first| first.value().ident == "crate");
+                    if !from_root {
+                        return Err(Error::new(path.segments[0].ident.span(), "`export` needs the path of the module from the crate root - `crate::queries`"));
+                    }
+                    options.export = Some(path);
+                }
+                "registry" => {
+                    let registry: LitBool = input.parse()?;
+                    options.registry = registry.value;
+                }
+                "numbering" => {
+                    if input.peek(LitInt) {
+                        let first: LitInt = input.parse()?;
+                        options.numbering = Some(Numbering::From(first.value() as usize));
+                    } else {
+                        let numbering: Ident = input.parse()?;
+                        if numbering != "none" {
+                            return Err(Error::new(numbering.span(), format!("unknown numbering `{}`, expected the first placeholder number or none", numbering)));
+                        }
+                        options.numbering = Some(Numbering::None);
+                    }
+                }
+                "functions" => {
+                    let client: Ident = input.parse()?;
+                    let functions = match client.to_string().as_str() {
+                        "rusqlite"       => Functions::Rusqlite,
+                        "postgres"       => Functions::Postgres,
+                        "tokio_postgres" => Functions::TokioPostgres,
+                        "executor"       => Functions::Executor,
+                        _ => return Err(Error::new(client.span(), format!("unknown functions `{}`, expected one of: rusqlite, postgres, tokio_postgres, executor", client)))
+                    };
+                    if !functions.is_enabled() {
+                        let feature = functions.name().replace('_', "-");
+                        return Err(Error::new(client.span(), format!("`functions = {}` needs the `{}` feature", client, feature)));
+                    }
+                    options.functions = Some(functions);
+                }
+                "bind" => {
+                    let mode: Ident = input.parse()?;
+                    options.named = match mode.to_string().as_str() {
+                        "positional" => false,
+                        "named"      => true,
+                        _ => return Err(Error::new(mode.span(), format!("unknown bind mode `{}`, expected one of: positional, named", mode)))
+                    };
+                }
+                "vars" => {
+                    let content;
+                    syn::braced!(content in input);
+                    while !content.is_empty() {
+                        let name_span = content.cursor().span();
+                        let name = parse_name_fragment(&content)?;
+                        if options.vars.iter().any(|(var_name, _)| *var_name == name) {
+                            return Err(Error::new(name_span, format!("template variable `{}` is declared twice", name)));
+                        }
+                        content.parse::<Token![=]>()?;
+                        let value: LitStr = content.parse()?;
+                        options.vars.push((name, value.value()));
+                        if !content.is_empty() {
+                            content.parse::<Token![,]>()?;
+                        }
+                    }
+                }
+                "row_trait" => {
+                    options.row_trait = Some(input.parse()?);
+                }
+                "arg_bounds" => {
+                    let bounds = Punctuated::<TypeParamBound, Token![+]>::parse_separated_nonempty(input)?;
+                    options.arg_bounds = bounds.into_iter().collect();
+                }
+                _ => {
+                    return Err(Error::new(name.span(), format!("unknown option `{}`", name)));
+                }
+            }
+        }
+        if let Some( namespace ) = namespace {
+            // the namespace goes before explicit prefixes, so they stay usable within it
+            let const_namespace = if options.verbatim_names { namespace.to_string() } else { namespace.to_uppercase() };
+            options.const_prefix = format!("{}_{}", const_namespace, options.const_prefix);
+            options.macro_prefix = format!("{}_{}", namespace.to_lowercase(), options.macro_prefix);
+            options.struct_prefix = sql::to_camel_case(&namespace);
+        }
+        if options.minify && options.comments == sql::Comments::Keep {
+            return Err(input.error("`minify` removes comments, thus it cannot be used with `comments = keep`"));
+        }
+        if !options.arg_bounds.is_empty() && options.args != ArgsMode::Generic {
+            return Err(input.error("`arg_bounds` can only be used with `args = generic`"));
+        }
+        if let Some( functions ) = options.functions {
+            if options.verbatim_names && options.const_prefix.is_empty() && options.macro_prefix.is_empty() {
+                return Err(input.error(format!("`functions = {}` with `const_names = verbatim` needs a `const_prefix` or `macro_prefix`, as functions are named after the statements too", functions.name())));
+            }
+            if options.named {
+                return Err(input.error(format!("`functions = {}` cannot be used with `bind = named`", functions.name())));
+            }
+        }
+        if options.functions == Some(Functions::Rusqlite) && options.args != ArgsMode::Refs {
+            return Err(input.error("`functions = rusqlite` can only be used with `args = refs`"));
+        }
+        if options.functions == Some(Functions::Postgres) || options.functions == Some(Functions::TokioPostgres) {
+            // postgres and tokio-postgres bind `&(dyn ToSql + Sync)` arguments
+            options.args = ArgsMode::Generic;
+            if !options.arg_bounds.iter().any(|bound| quote!(#bound).to_string() == "Sync") {
+                options.arg_bounds.push(syn::parse_quote!(Sync));
+            }
+        }
+        if options.named && options.args != ArgsMode::Refs {
+            return Err(input.error("`bind = named` can only be used with `args = refs`"));
+        }
+        if options.builder && options.args != ArgsMode::Refs {
+            return Err(input.error("`builder` can only be used with `args = refs`"));
+        }
+        if options.args_trait && (options.args != ArgsMode::Refs || options.named) {
+            return Err(input.error("`args_trait` can only be used with `args = refs` and positional binding"));
+        }
+        if options.serialize_args && options.args != ArgsMode::Generic {
+            return Err(input.error("`serialize_args` can only be used with `args = generic`, as `&dyn ToSql` arguments cannot be serialized"));
+        }
+        if options.instrument && options.functions.is_none() {
+            return Err(input.error("`instrument` needs the generated functions of the `functions` option"));
+        }
+        options.schema = options.schema.take().map(|path| options.resolve(path));
+        options.unused_in = options.unused_in.take().map(|path| options.resolve(path));
+        options.manifest = options.manifest.take().map(|path| options.resolve(path));
+        Ok(options)
+    }
+
+    /// Returns the path relative to the `root` directory when it is relative and the `root` is set.
+    fn resolve(&self, path: String) -> String {
+        match &self.root {
+            Some( root ) if Path::new(&path).is_relative() => root.join(path).to_string_lossy().into_owned(),
+            _ => path
+        }
+    }
+}
+
+/// Returns the directory that the `root` option names - the `crate`, `workspace`, or the one of
+/// the `file` with the macro call.
+fn root_dir(root: &str) -> std::result::Result<PathBuf, String> {
+    let manifest_dir = || std::env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from).ok_or("CARGO_MANIFEST_DIR is not set - the crate is not built by cargo");
+    match root {
+        "crate" => Ok(manifest_dir()?),
+        "workspace" => {
+            let manifest_dir = manifest_dir()?;
+            // a package that is not a member of a workspace is a workspace of its own
+            let workspace_dir = manifest_dir.ancestors().find(|dir| {
+                std::fs::read_to_string(dir.join("Cargo.toml")).map(|manifest| manifest.lines().any(|line| line.trim() == "[workspace]")).unwrap_or(false)
+            });
+            Ok(workspace_dir.unwrap_or(&manifest_dir).to_path_buf())
+        }
+        "file" => {
+            if !proc_macro::is_available() {
+                return Err("the file with the macro call is only known to the macro".to_string());
+            }
+            let file = proc_macro::Span::call_site().local_file().ok_or("the file with the macro call is not a local file")?;
+            Ok(file.parent().map(Path::to_path_buf).unwrap_or_default())
+        }
+        _ => Err(format!("unknown root `{}`, expected one of: crate, file, workspace", root))
+    }
+}
+
+/// Parses a literal string that will become a part of generated names.
+fn parse_name_fragment(input: ParseStream) -> Result<String> {
+    let lit: LitStr = input.parse()?;
+    let value = lit.value();
+    if value.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Ok(value)
+    } else {
+        Err(Error::new(lit.span(), "expected letters, digits or underscores"))
+    }
+}
+
+impl Parse for IncludeSql {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path: Expr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        // several files - `["src/crew.sql", "src/ships.sql"]` - share the settings
+        let path_lits = match path {
+            Expr::Array( paths ) => {
+                let span = paths.span();
+                let path_lits = paths.elems.into_iter().map(|path| to_litstr(path, "SQL file path")).collect::<Result<Vec<_>>>()?;
+                if path_lits.is_empty() {
+                    return Err(Error::new(span, "the list of SQL files is empty"));
+                }
+                path_lits
+            }
+            path => vec![to_litstr(path, "SQL file path")?]
+        };
+        let path_lit = &path_lits[0];
+        if input.peek(Ident) && input.peek2(Token![=]) && input.fork().parse::<Ident>()? == "dialects" {
+            if path_lits.len() > 1 {
+                return Err(Error::new(path_lits[1].span(), "`dialects` can only be used with one SQL file"));
+            }
+            return IncludeSql::parse_variants(input, path_lit);
+        }
+        let (placeholder, dialect, options) = parse_settings(input)?;
+        if options.module && path_lits.len() > 1 {
+            return Err(Error::new(path_lits[1].span(), "`module` names the module after the SQL file, thus it needs one file"));
+        }
+
+        let mut files = path_lits.iter().map(|path_lit| {
+            let path = options.resolve(path_lit.value());
+            let statements = sql::parse_sql_file(&path, &placeholder, dialect.as_ref(), &options.vars, options.comments, options.minify);
+            (IncludeSql::checked(path, path_lit, statements, placeholder.clone(), dialect, options.clone()), path_lit)
+        });
+        let (mut include, _) = files.next().expect("at least one SQL file");
+        for (file, path_lit) in files {
+            include.append(file, path_lit);
+        }
+        Ok(include.with_manifest(path_lit))
+    }
+}
+
+/// Arguments of the `inline_sql!` - the SQL text takes the place of the file path.
+pub struct InlineSql(pub IncludeSql);
+
+/// Stands in for the file path in messages and in the statement inventory.
+const INLINE_PATH : &str = "<inline>";
+
+impl Parse for InlineSql {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let text: Expr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let (placeholder, dialect, options) = parse_settings(input)?;
+
+        let text_lit = to_litstr(text, "SQL text")?;
+        if options.module {
+            return Err(Error::new(text_lit.span(), "`module` needs a file to name the module after"));
+        }
+        if options.hot_reload {
+            return Err(Error::new(text_lit.span(), "`hot_reload` needs a file to reload the statements from"));
+        }
+        let statements = sql::parse_sql_str(&text_lit.value(), &placeholder, dialect.as_ref(), &options.vars, options.comments, options.minify);
+        Ok(InlineSql(IncludeSql::checked(INLINE_PATH.to_string(), &text_lit, statements, placeholder, dialect, options).with_manifest(&text_lit)))
+    }
+}
+
+impl IncludeSql {
+    /// Checks the parsed statements and collects errors of the SQL. They point to the SQL file
+    /// path or to the inline SQL.
+    fn checked(path: String, source_lit: &LitStr, statements: std::result::Result<Vec<sql::Stmt>, Vec<std::io::Error>>, placeholder: Placeholder, dialect: Option<Dialect>, options: Options) -> Self {
+        let statements = match statements {
+            Ok( statements ) => statements,
+            Err( errors ) => {
+                let errors = errors.iter().map(|err| Error::new(source_lit.span(), file_location(&path, &err.to_string()))).collect();
+                return IncludeSql { path, statements: Vec::new(), placeholder, dialect, options, errors, variants: Vec::new() };
+            }
+        };
+        let mut statements = statements;
+        let strip_semicolons = options.strip_semicolons.unwrap_or_else(|| dialect.as_ref().is_some_and(Dialect::strips_semicolons));
+        // the unsupported variant is reported once for the option rather than for each statement
+        let generate = options.generate.filter(|&item| explain_prefix(item, dialect.as_ref()).is_some());
+        for stmt in &mut statements {
+            if strip_semicolons {
+                stmt.strip_semicolon();
+            }
+            stmt.add_tags(&options.tags);
+            if let Some( item ) = generate {
+                stmt.add_generate(item);
+            }
+            stmt.const_name = options.const_name(&stmt.name);
+            if let Some( params ) = &mut stmt.params {
+                params.struct_name = Ident::new(&options.struct_name(&stmt.name), Span::call_site());
+                for group in &mut params.groups {
+                    group.struct_name = Ident::new(&options.struct_name(&group.name.to_string()), Span::call_site());
+                }
+            }
+        }
+        let mut errors = Vec::new();
+        if let Some( item ) = options.generate.filter(|_| generate.is_none()) {
+            let what = if item == sql::Generate::ExplainAnalyze { "EXPLAIN ANALYZE" } else { "EXPLAIN" };
+            errors.push(Error::new(source_lit.span(), format!("{}: `generate`: the dialect does not support {}", path, what)));
+        }
+        if (options.named || options.args != ArgsMode::Refs) && statements.iter().any(|stmt| stmt.params.as_ref().is_some_and(|params| !params.groups.is_empty())) {
+            errors.push(Error::new(source_lit.span(), format!("{}: parameter groups need `args = refs` and cannot be used with `bind = named`", path)));
+        }
+        if let Err( msg ) = validate::check_syntax(&path, &statements, dialect.as_ref()) {
+            errors.push(Error::new(source_lit.span(), msg));
+        }
+        if let Err( msg ) = verify::check_statements(&path, &statements) {
+            errors.push(Error::new(source_lit.span(), msg));
+        }
+        if let Some( schema_path ) = &options.schema {
+            if let Err( msg ) = schema::check_statements(schema_path, &path, &statements, dialect.as_ref()) {
+                errors.push(Error::new(source_lit.span(), msg));
+            }
+        }
+        if let Some( dir ) = &options.unused_in {
+            match usage::identifiers(dir) {
+                Ok( idents ) => {
+                    for stmt in &statements {
+                        let fn_name = format!("{}{}", options.macro_prefix, stmt.name);
+                        if !usage::is_used(&idents, &stmt.const_name.to_string(), &options.struct_name(&stmt.name), &fn_name) {
+                            errors.push(Error::new(source_lit.span(), format!("{}: `{}` is not used by the code in {}", stmt_location(&path, stmt), stmt.name, dir)));
+                        }
+                    }
+                }
+                Err( msg ) => errors.push(Error::new(source_lit.span(), msg))
+            }
+        }
+        for msg in const_name_collisions(&path, &statements, &[]) {
+            errors.push(Error::new(source_lit.span(), msg));
+        }
+        for stmt in &statements {
+            if stmt.params.as_ref().is_some_and(|params| !params.pieces.is_empty()) && (options.args != ArgsMode::Refs || options.functions.is_some_and(|functions| functions != Functions::Executor)) {
+                errors.push(Error::new(source_lit.span(), format!("{}: `{}`: statements with conditional blocks or identifier parameters need `args = refs` and can only have `functions = executor`", stmt_location(&path, stmt), stmt.name)));
+            }
+            if stmt.params.as_ref().is_some_and(|params| params.lst_params.iter().any(|param| param.tuple_len.is_some())) && (options.args != ArgsMode::Refs || matches!(options.functions, Some(Functions::Postgres) | Some(Functions::TokioPostgres))) {
+                errors.push(Error::new(source_lit.span(), format!("{}: `{}`: statements with tuple list parameters need `args = refs` and cannot have postgres or tokio_postgres functions", stmt_location(&path, stmt), stmt.name)));
+            }
+            if options.functions.is_some() && stmt.cache_ttl.is_some() && stmt.script.is_none() && stmt.migration.is_none() && !is_cacheable(stmt.kind, stmt.params.as_ref(), &options) {
+                errors.push(Error::new(source_lit.span(), format!("{}: `{}`: results of `-- cache:` statements are cached by their arguments, thus their functions need `args = generic` without list parameters and the statements cannot be `!` statements", stmt_location(&path, stmt), stmt.name)));
+            }
+            if options.functions.is_some() && stmt.params.as_ref().is_some_and(|params| params.lst_params.iter().any(sql::LstParam::rejects_empty)) {
+                errors.push(Error::new(source_lit.span(), format!("{}: `{}`: statements with `-- empty-in: error` cannot have generated functions, as their `into_sql_with_args` returns a `Result`", stmt_location(&path, stmt), stmt.name)));
+            }
+            for &item in &stmt.generate {
+                if explain_prefix(item, dialect.as_ref()).is_none() {
+                    let what = if item == sql::Generate::ExplainAnalyze { "EXPLAIN ANALYZE" } else { "EXPLAIN" };
+                    errors.push(Error::new(source_lit.span(), format!("{}: `{}`: the dialect does not support {}", stmt_location(&path, stmt), stmt.name, what)));
+                }
+            }
+        }
+        IncludeSql { path, statements, placeholder, dialect, options, errors, variants: Vec::new() }
+    }
+
+    /// Regenerates the schema snapshot of the `schema` option from the database that
+    /// `DATABASE_URL` points to. The statements are prepared by the database, which gives the
+    /// numbers of their parameters, and the tables of the database replace the ones in the
+    /// snapshot. Only Postgres variants are refreshed when the include has `dialects`.
+    ///
+    /// Errors that the outdated snapshot reported for the statements are ignored, as the refresh
+    /// replaces it, but statements that cannot be parsed cannot be refreshed.
+    pub fn refresh_schema(&self) -> std::result::Result<(), String> {
+        let schema_path = self.options.schema.as_deref().ok_or_else(|| format!("{}: `schema` option with the path to the snapshot is missing", self.path))?;
+        if !self.variants.is_empty() {
+            let mut variants = self.variants.iter().filter(|(_, variant)| matches!(variant.dialect, Some(Dialect::Postgres))).peekable();
+            if variants.peek().is_none() {
+                return Err(format!("{}: the schema snapshot is refreshed from Postgres, but `dialects` has no postgres", self.path));
+            }
+            return variants.try_for_each(|(_, variant)| variant.refresh_schema());
+        }
+        if self.statements.is_empty() && !self.errors.is_empty() {
+            return Err(self.errors.iter().map(Error::to_string).collect::<Vec<_>>().join("\n"));
+        }
+        // each file of the include has its own statements in the snapshot
+        let mut files : Vec<(&str, Vec<&sql::Stmt>)> = Vec::new();
+        for stmt in &self.statements {
+            let path = stmt.file.as_deref().unwrap_or(&self.path);
+            match files.iter_mut().find(|(file, _)| *file == path) {
+                Some( (_, statements) ) => statements.push(stmt),
+                None => files.push((path, vec![stmt]))
+            }
+        }
+        files.iter().try_for_each(|(path, statements)| verify::refresh_schema(path, statements, schema_path))
+    }
+
+    /// Adds the statements of another file of the include. Statements of the files remember
+    /// them, and their names must be unique across the files.
+    fn append(&mut self, include: IncludeSql, source_lit: &LitStr) {
+        let IncludeSql { path, statements, errors, .. } = include;
+        let num_statements = self.statements.len();
+        let self_path = &self.path;
+        for stmt in &mut self.statements {
+            stmt.file.get_or_insert_with(|| self_path.clone());
+        }
+        for mut stmt in statements {
+            if let Some( prev ) = self.statements.iter().find(|prev| prev.name.eq_ignore_ascii_case(&stmt.name)) {
+                let msg = format!("{}: statement `{}` is already defined at {}", stmt_location(&path, &stmt), stmt.name, stmt_location(&self.path, prev));
+                self.errors.push(Error::new(source_lit.span(), msg));
+                continue;
+            }
+            stmt.file.get_or_insert_with(|| path.clone());
+            self.statements.push(stmt);
+        }
+        // collisions within each file are reported when the file is checked
+        let (self_statements, appended) = self.statements.split_at(num_statements);
+        for msg in const_name_collisions(&self.path, appended, self_statements) {
+            self.errors.push(Error::new(source_lit.span(), msg));
+        }
+        self.errors.extend(errors);
+    }
+
+    /// Parses the dialects - `dialects = ["postgres", "sqlite"]` - and the optional arguments
+    /// that follow them. Each dialect gets the statements of the file, where the statements of
+    /// its variant of the file - `src/crew.postgres.sql` - replace the ones with the same names.
+    fn parse_variants(input: ParseStream, path_lit: &LitStr) -> Result<Self> {
+        input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        let content;
+        syn::bracketed!(content in input);
+        let names = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+        let options = Options::parse(input)?;
+        if names.is_empty() {
+            return Err(Error::new(path_lit.span(), "`dialects` needs at least one dialect"));
+        }
+        let path = options.resolve(path_lit.value());
+        let mut variants : Vec<(String, IncludeSql)> = Vec::with_capacity(names.len());
+        for name in names {
+            let dialect_name = name.value();
+            let dialect = Dialect::from_name(&dialect_name).ok_or_else(|| {
+                Error::new(name.span(), format!("unknown dialect `{}`, expected one of: {}", dialect_name, Dialect::NAMES))
+            })?;
+            if variants.iter().any(|(variant_name, _)| *variant_name == dialect_name) {
+                return Err(Error::new(name.span(), format!("dialect `{}` is listed twice", dialect_name)));
+            }
+            let placeholder = dialect_placeholder(dialect.placeholder(), Some(&dialect), &options, name.span())?;
+            let variant_path = variant_path(&path, &dialect_name);
+            let mut statements = sql::parse_sql_file(&path, &placeholder, Some(&dialect), &options.vars, options.comments, options.minify);
+            let include = match &mut statements {
+                Ok( base ) if std::path::Path::new(&variant_path).exists() => {
+                    let overrides = sql::parse_sql_overrides(&variant_path, &placeholder, Some(&dialect), &options.vars, options.comments, options.minify);
+                    match overrides.and_then(|overrides| sql::override_statements(base, overrides)) {
+                        Ok( () ) => IncludeSql::checked(path.clone(), path_lit, statements, placeholder, Some(dialect), options.clone()),
+                        Err( errors ) => IncludeSql::checked(variant_path, path_lit, Err(errors), placeholder, Some(dialect), options.clone())
+                    }
+                }
+                _ => IncludeSql::checked(path.clone(), path_lit, statements, placeholder, Some(dialect), options.clone())
+            };
+            variants.push((dialect_name, include));
+        }
+        let placeholder = variants[0].1.placeholder.clone();
+        Ok(IncludeSql { path, statements: Vec::new(), placeholder, dialect: None, options, errors: Vec::new(), variants }.with_manifest(path_lit))
+    }
+
+    /// Writes the manifest of the statements when the `manifest` option asks for it. Nothing is
+    /// written when the SQL has errors.
+    fn with_manifest(mut self, source_lit: &LitStr) -> Self {
+        let manifest_path = match &self.options.manifest {
+            Some( manifest_path ) => manifest_path,
+            None => return self
+        };
+        let result = if self.variants.is_empty() {
+            if !self.errors.is_empty() {
+                return self;
+            }
+            let statements = manifest::Statements { dialect: None, statements: &self.statements, placeholder: &self.placeholder };
+            manifest::write(manifest_path, &self.path, &[statements])
+        } else {
+            if self.variants.iter().any(|(_, include)| !include.errors.is_empty()) {
+                return self;
+            }
+            let variants : Vec<_> = self.variants.iter().map(|(name, include)| {
+                manifest::Statements { dialect: Some(name.as_str()), statements: &include.statements, placeholder: &include.placeholder }
+            }).collect();
+            manifest::write(manifest_path, &self.path, &variants)
+        };
+        if let Err( msg ) = result {
+            self.errors.push(Error::new(source_lit.span(), format!("`manifest`: {}", msg)));
+        }
+        self
+    }
+}
+
+/// Replaces the line number the error of the SQL starts with by the location in the file -
+/// `path:line: ...` - like the errors of the SQL validation.
+fn file_location(path: &str, msg: &str) -> String {
+    let line_msg = msg.strip_prefix("line ").and_then(|rest| rest.split_once(": "));
+    match line_msg {
+        Some( (line_num, msg) ) if line_num.bytes().all(|b| b.is_ascii_digit()) => format!("{}:{}: {}", path, line_num, msg),
+        _ => format!("{}: {}", path, msg)
+    }
+}
+
+/// Location of the first line of the statement.
+fn stmt_location(path: &str, stmt: &sql::Stmt) -> String {
+    format!("{}:{}", stmt.file.as_deref().unwrap_or(path), stmt.lines.first().copied().unwrap_or_default())
+}
+
+/// Returns the names of the constants that are generated for the statement - its text constant
+/// and the constants that accompany it.
+fn stmt_const_names(stmt: &sql::Stmt) -> Vec<String> {
+    let const_name = &stmt.const_name;
+    let mut suffixes = vec!["_KIND", "_INFO", "_FINGERPRINT"];
+    if stmt.idempotent {
+        suffixes.push("_IDEMPOTENT");
+    }
+    if stmt.cache_ttl.is_some() {
+        suffixes.push("_CACHE_TTL");
+    }
+    if stmt.script.is_some() {
+        suffixes.push("_STATEMENTS");
+    }
+    for &item in &stmt.generate {
+        suffixes.push(if item == sql::Generate::ExplainAnalyze { "_EXPLAIN_ANALYZE" } else { "_EXPLAIN" });
+    }
+    // statements with lists or conditional blocks bind as many arguments as their text
+    // assembled at run time has placeholders
+    if stmt.params.as_ref().is_none_or(|params| params.lst_params.is_empty() && params.pieces.is_empty()) {
+        suffixes.extend(["_PARAM_COUNT", "_PARAMS"]);
+    }
+    if stmt.columns.is_some() {
+        suffixes.push("_COLUMNS");
+    }
+    let column_suffixes = stmt.column_names.iter().flatten().map(|name| format!("_COL_{}", name));
+    let mut names = vec![const_name.to_string()];
+    names.extend(suffixes.into_iter().map(String::from).chain(column_suffixes).map(|suffix| companion_const(const_name, &suffix).to_string()));
+    names
+}
+
+/// Returns errors about constants that would be generated twice - `get_user` gets the
+/// `GET_USER_INFO` constant, which is also the text constant of `get_user_info`. Statements are
+/// checked against each other and against the `checked` ones, which do not collide themselves.
+fn const_name_collisions(path: &str, statements: &[sql::Stmt], checked: &[sql::Stmt]) -> Vec<String> {
+    let mut generated : Vec<(String, &sql::Stmt)> = checked.iter().flat_map(|stmt| stmt_const_names(stmt).into_iter().map(move |name| (name, stmt))).collect();
+    let mut errors = Vec::new();
+    for stmt in statements {
+        for name in stmt_const_names(stmt) {
+            match generated.iter().find(|(generated_name, _)| *generated_name == name) {
+                Some( (_, other) ) => errors.push(format!("{}: `{}`: constant `{}` is also generated for `{}` at {} - rename one of the statements", stmt_location(path, stmt), stmt.name, name, other.name, stmt_location(path, other))),
+                None => generated.push((name, stmt))
+            }
+        }
+    }
+    errors
+}
+
+/// Parses the parameter prefix or the dialect and the optional arguments that follow them.
+fn parse_settings(input: ParseStream) -> Result<(Placeholder, Option<Dialect>, Options)> {
+    let (dialect, placeholder, prefix_span) = if input.peek(Ident) && input.peek2(Token![=]) {
+        let span = input.cursor().span();
+        let dialect = parse_dialect(input)?;
+        let placeholder = dialect.placeholder();
+        (Some(dialect), placeholder, span)
+    } else {
+        let param_prefix: Expr = input.parse()?;
+        let param_prefix = to_litstr(param_prefix, "parameter prefix")?;
+        (None, Placeholder::numbered(&param_prefix.value()), param_prefix.span())
+    };
+    let options = Options::parse(input)?;
+    let placeholder = dialect_placeholder(placeholder, dialect.as_ref(), &options, prefix_span)?;
+    Ok((placeholder, dialect, options))
+}
+
+/// Adjusts the placeholder of the parameter prefix or of the dialect to the optional arguments.
+fn dialect_placeholder(mut placeholder: Placeholder, dialect: Option<&Dialect>, options: &Options, prefix_span: Span) -> Result<Placeholder> {
+    if let Some( numbering ) = options.numbering {
+        placeholder.numbering = numbering;
+    }
+    if let Some( dedup ) = options.dedup_params {
+        placeholder.dedup = dedup;
+    }
+    if options.named {
+        // the SQL is left as is, thus the prefix is only used to build argument names
+        if let Some( dialect ) = dialect {
+            match dialect.name_prefix() {
+                Some( prefix ) => placeholder.prefix = prefix.to_string(),
+                None => return Err(Error::new(prefix_span, "this dialect cannot bind arguments by name"))
+            }
+        }
+        placeholder.named = true;
+    }
+    Ok(placeholder)
+}
+
+/// Returns the path of the dialect variant of the SQL file - `src/crew.postgres.sql` for
+/// `src/crew.sql`.
+fn variant_path(path: &str, dialect_name: &str) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+    let file_name = match path.extension().and_then(|ext| ext.to_str()) {
+        Some( ext ) => format!("{}.{}.{}", stem, dialect_name, ext),
+        None => format!("{}.{}", stem, dialect_name)
+    };
+    path.with_file_name(file_name).to_string_lossy().into_owned()
+}
+
+fn parse_dialect(input: ParseStream) -> Result<Dialect> {
+    let key: Ident = input.parse()?;
+    if key != "dialect" {
+        return Err(Error::new(key.span(), "expected `dialect = <name>` or parameter prefix"));
+    }
+    input.parse::<Token![=]>()?;
+    let name: Ident = input.parse()?;
+    match Dialect::from_name(&name.to_string()) {
+        Some(dialect) => Ok(dialect),
+        None => Err(Error::new(name.span(), format!("unknown dialect `{}`, expected one of: {}", name, Dialect::NAMES)))
+    }
+}
+
+/// Returns the literal string, or the one that the `concat!` and `env!` macros would expand
+/// into - `concat!(env!("CARGO_MANIFEST_DIR"), "/sql/crew.sql")`.
+fn to_litstr(expr: Expr, kind: &str) -> Result<LitStr> {
+    let span = expr.span();
+    match expr {
+        Expr::Lit( syn::ExprLit { lit: Lit::Str( lit ), .. } ) => Ok(lit),
+        Expr::Macro( expr ) if is_string_macro(&expr.mac) => Ok(LitStr::new(&string_macro_value(&expr.mac)?, span)),
+        _ => Err(Error::new(span, format!("{} must be a literal string, or a `concat!` or `env!` of them", kind)))
+    }
+}
+
+fn is_string_macro(mac: &syn::Macro) -> bool {
+    mac.path.is_ident("concat") || mac.path.is_ident("env")
+}
+
+/// Evaluates `concat!` and `env!` like the compiler does. Environment variables are the ones
+/// of the compiler, which cargo sets - `CARGO_MANIFEST_DIR`.
+fn string_macro_value(mac: &syn::Macro) -> Result<String> {
+    let span = mac.path.span();
+    let args = mac.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated)?;
+    if mac.path.is_ident("env") {
+        let mut args = args.into_iter();
+        let name = match args.next() {
+            Some( name ) => to_litstr(name, "environment variable name")?,
+            None => return Err(Error::new(span, "`env!` takes the name of the environment variable"))
+        };
+        let msg = args.next().map(|msg| to_litstr(msg, "`env!` message")).transpose()?;
+        return std::env::var(name.value()).map_err(|_| {
+            let msg = msg.map(|msg| msg.value()).unwrap_or_else(|| format!("environment variable `{}` not defined at compile time", name.value()));
+            Error::new(name.span(), msg)
+        });
+    }
+    let mut value = String::new();
+    for arg in args {
+        match arg {
+            Expr::Lit( syn::ExprLit { lit, .. } ) => match lit {
+                Lit::Str( lit )   => value.push_str(&lit.value()),
+                Lit::Char( lit )  => value.push(lit.value()),
+                Lit::Int( lit )   => value.push_str(&lit.value().to_string()),
+                Lit::Bool( lit )  => value.push_str(&lit.value.to_string()),
+                Lit::Float( lit ) => value.push_str(&lit.value().to_string()),
+                lit => return Err(Error::new(lit.span(), "cannot concatenate a byte string literal"))
+            },
+            Expr::Macro( expr ) if is_string_macro(&expr.mac) => value.push_str(&string_macro_value(&expr.mac)?),
+            arg => return Err(Error::new(arg.span(), "expected a literal, or a `concat!` or `env!` of them"))
+        }
+    }
+    Ok(value)
+}
+
+macro_rules! len {
+    ($s:expr) => {
+        $s.len()
+    };
+    ($s:expr, $($t:expr),+) => {
+        $s.len() + len!($($t),+)
+    };
+}
+
+macro_rules! ident {
+    ($s:expr) => {
+        Ident::new($s, Span::call_site())
+    };
+    ($($s:expr),+) => {{
+        let cap = len!($($s),+);
+        let mut name = String::with_capacity(cap);
+        $(
+            name.push_str($s);
+        )+
+        ident!(&name)
+    }};
+}
+
+/// Generates warnings about parameters that comments mention, but statements do not use. These
+/// are usually left behind when a parameter is removed from the statement text. Only the
+/// selected dialect variant of the file is compiled, thus the shared statements are reported
+/// once too.
+fn add_missing_param_warnings(path: &str, statements: &[sql::Stmt], code: &mut Vec<TokenStream>) {
+    for stmt in statements {
+        for (param, line_num) in &stmt.missing_params {
+            let msg = format!("include-sql: {}:{}: the comment of `{}` mentions parameter `{}`, which the statement does not use", stmt.file.as_deref().unwrap_or(path), line_num, stmt.name, param);
+            code.push(warning("UNUSED_COMMENT_PARAM", &msg));
+        }
+    }
+}
+
+/// Returns the item that makes the compiler warn with the message. The macros cannot emit
+/// warnings, thus it is a use of a deprecated constant, which the compiler reports once per
+/// build.
+fn warning(const_name: &str, msg: &str) -> TokenStream {
+    let const_name = Ident::new(const_name, Span::call_site());
+    quote! {
+        const _ : () = {
+            #[deprecated(note = #msg)]
+            const #const_name : () = ();
+            #const_name
+        };
+    }
+}
+
+/// Writes the notes about the expansion into the directory that the `INCLUDE_SQL_VERBOSE`
+/// environment variable names, to the file named like the dump - `crew.notes`. Macros cannot
+/// emit compiler notes on stable Rust, and text printed to stderr would be mixed with the
+/// compiler output, thus the notes are kept in the file. A file that cannot be written is
+/// reported as a compiler warning.
+fn write_notes(dir: &Path, name: &str, notes: &[String], code: &mut Vec<TokenStream>) {
+    let path = dir.join(format!("{}.notes", name));
+    let mut text = notes.join("\n");
+    text.push('\n');
+    if let Err( err ) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, text)) {
+        code.push(warning("UNWRITTEN_NOTES", &format!("include-sql: cannot write {}: {}", path.display(), err)));
+    }
+}
+
+/// Adds notes about the statements found in the SQL file and how their parameters are bound.
+fn statement_notes(path: &str, statements: &[sql::Stmt], placeholder: &Placeholder, notes: &mut Vec<String>) {
+    notes.push(format!("{}: {} statement(s)", path, statements.len()));
+    for stmt in statements {
+        let line_num = stmt.lines.first().copied().unwrap_or_default();
+        // statements of another file of the include are located in it
+        let file = stmt.file.as_deref().unwrap_or(path);
+        let kind = match stmt.kind {
+            sql::StmtKind::Query    => "query",
+            sql::StmtKind::FetchOne => "fetches one row",
+            sql::StmtKind::Execute  => "executed"
+        };
+        notes.push(format!("{}:{}: `{}` - {}", file, line_num, stmt.name, kind));
+        let params = match &stmt.params {
+            Some( params ) => params,
+            None => {
+                notes.push(format!("{}:{}:   no parameters", file, line_num));
+                continue;
+            }
+        };
+        let cond_params : Vec<usize> = params.pieces.iter().filter_map(|piece| match piece {
+            sql::Piece::Block(index, _) => Some(*index),
+            _ => None
+        }).collect();
+        for (index, (param_name, null_type)) in params.pos_params.iter().zip(&params.null_types).enumerate() {
+            let class = if cond_params.contains(&index) {
+                "condition of a conditional block"
+            } else if null_type.is_some() {
+                "optional scalar"
+            } else {
+                "scalar"
+            };
+            let binding = if !params.pieces.is_empty() {
+                "bound when the statement is executed".to_string()
+            } else if placeholder.named {
+                "bound by name".to_string()
+            } else {
+                let arg_nums : Vec<_> = params.pos_args.iter().enumerate()
+                    .filter(|(_, arg)| arg.index == index)
+                    .map(|(num, _)| (num + 1).to_string())
+                    .collect();
+                format!("bound as argument {}", arg_nums.join(", "))
+            };
+            notes.push(format!("{}:{}:   `{}` - {}, {}", file, line_num, param_name, class, binding));
+        }
+        for param_name in unique_lst_params(&params.lst_params) {
+            match tuple_len(&params.lst_params, param_name) {
+                Some( len ) => notes.push(format!("{}:{}:   `{}` - IN list of {}-tuples, expanded when the statement is executed", file, line_num, param_name, len)),
+                None => notes.push(format!("{}:{}:   `{}` - IN list, expanded when the statement is executed", file, line_num, param_name))
+            }
+        }
+        for ident in &params.idents {
+            notes.push(format!("{}:{}:   `{}` - identifier, one of: {}", file, line_num, ident.name, ident.values.join(", ")));
+        }
+    }
+}
+
+fn add_kind(kind: sql::StmtKind, sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let kind_const = companion_const(sql_text_const, "_KIND");
+    let kind = match kind {
+        sql::StmtKind::Query    => quote! { include_sql_helper::StatementKind::Query },
+        sql::StmtKind::FetchOne => quote! { include_sql_helper::StatementKind::FetchOne },
+        sql::StmtKind::Execute  => quote! { include_sql_helper::StatementKind::Execute },
+    };
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #kind_const : include_sql_helper::StatementKind = #kind;
+    });
+}
+
+fn add_columns(columns: usize, column_names: &[Option<String>], sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let columns_const = companion_const(sql_text_const, "_COLUMNS");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #columns_const : usize = #columns;
+    });
+    for (index, name) in column_names.iter().enumerate() {
+        if let Some( name ) = name {
+            let index_const = companion_const(sql_text_const, &format!("_COL_{}", name));
+            code.push(quote! {
+                #[allow(dead_code)]
+                const #index_const : usize = #index;
+            });
+        }
+    }
+}
+
+fn add_script(statements: &[String], sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let statements_const = companion_const(sql_text_const, "_STATEMENTS");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #statements_const : &[&str] = &[ #( #statements ),* ];
+    });
+}
+
+/// Returns the debug assertion that the width of the rows the database returns - `column_count`,
+/// which is read from the prepared statement or a returned row - is the `<STATEMENT>_COLUMNS`
+/// width of the projection, which the `_COL_` indexes assume.
+fn column_count_check(stmt_name: &str, columns: Option<usize>, sql_text_const: &Ident, column_count: TokenStream) -> TokenStream {
+    if columns.is_none() {
+        return quote! {};
+    }
+    let columns_const = companion_const(sql_text_const, "_COLUMNS");
+    let msg = format!("`{}` returns a different number of columns than its projection has", stmt_name);
+    quote! { debug_assert_eq!(#columns_const, #column_count, #msg); }
+}
+
+/// Returns the debug assertion that the statement binds as many arguments as its text - `sql`,
+/// which might be assembled at run time - has placeholders.
+fn bound_args_check(stmt_name: &str, placeholder: &Placeholder, sql: TokenStream, num_args: TokenStream) -> TokenStream {
+    let param_prefix = &placeholder.prefix;
+    let numbered = matches!(placeholder.numbering, Numbering::From(_));
+    let msg = format!("`{}` binds a different number of arguments than its text has placeholders", stmt_name);
+    quote! { debug_assert_eq!(include_sql_helper::placeholder_count(#sql, #param_prefix, #numbered), #num_args, #msg); }
+}
+
+fn add_info(path: &str, stmt_name: &str, line_num: usize, params: Option<&sql::StmtParams>, sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let info_const = companion_const(sql_text_const, "_INFO");
+    let line_num = line_num as u32;
+    let param_names = params.map(param_names).unwrap_or_default();
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #info_const : include_sql_helper::StatementInfo = include_sql_helper::StatementInfo {
+            name: #stmt_name, file: #path, line: #line_num, params: &[ #( #param_names ),* ]
+        };
+    });
+}
+
+/// Returns the names of the parameters in the order of their placeholders. List parameters are
+/// bound after the positional ones, and identifiers are not bound.
+pub(crate) fn param_names(params: &sql::StmtParams) -> Vec<String> {
+    params.pos_params.iter().chain(unique_lst_params(&params.lst_params)).map(|name| name.unraw().to_string()).collect()
+}
+
+/// Returns the name of the constant that accompanies the statement text constant. The suffix
+/// follows the case of the statement constant, which is lowercase when names are kept verbatim.
+fn companion_const(sql_text_const: &Ident, suffix: &str) -> Ident {
+    let name = sql_text_const.to_string();
+    if name.chars().any(|c| c.is_ascii_lowercase()) {
+        ident!(&name, &suffix.to_lowercase())
+    } else {
+        ident!(&name, suffix)
+    }
+}
+
+/// Generates the fingerprint of the statement - FNV-1a hash of its normalized text - so queries
+/// can be tagged without hashing the text at run time.
+fn add_fingerprint(text: &str, sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let fingerprint_const = companion_const(sql_text_const, "_FINGERPRINT");
+    let fingerprint = fnv1a(&sql::normalized_text(text));
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #fingerprint_const : u64 = #fingerprint;
+    });
+}
+
+/// Generates the number of arguments that the statement text binds and the names of their
+/// parameters in the order they are bound. Statements with lists or conditional blocks bind as
+/// many as their text assembled at run time has placeholders, thus they do not get them.
+fn add_bound_params(params: Option<&sql::StmtParams>, placeholder: &Placeholder, sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let names : Vec<String> = match params {
+        Some( params ) if !params.lst_params.is_empty() || !params.pieces.is_empty() => return,
+        // named placeholders are bound once for each parameter
+        Some( params ) if placeholder.named => params.pos_params.iter().map(|name| name.unraw().to_string()).collect(),
+        Some( params ) => params.pos_args.iter().map(|arg| params.pos_params[arg.index].unraw().to_string()).collect(),
+        None => Vec::new()
+    };
+    let count = names.len();
+    let count_const = companion_const(sql_text_const, "_PARAM_COUNT");
+    let names_const = companion_const(sql_text_const, "_PARAMS");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #count_const : usize = #count;
+        #[allow(dead_code)]
+        const #names_const : &[&str] = &[ #( #names ),* ];
+    });
+}
+
+/// Generates the `_IDEMPOTENT` constant of the statement annotated with `-- idempotent`. Other
+/// statements do not get it, so there are fewer constants to collide with the ones of other
+/// statements.
+fn add_idempotent(sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let idempotent_const = companion_const(sql_text_const, "_IDEMPOTENT");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #idempotent_const : bool = true;
+    });
+}
+
+/// Returns the prefix of the statement text that requests the execution plan.
+fn explain_prefix(item: sql::Generate, dialect: Option<&Dialect>) -> Option<&'static str> {
+    let analyze = item == sql::Generate::ExplainAnalyze;
+    match dialect {
+        Some( dialect ) => dialect.explain_prefix(analyze),
+        None if analyze => Some("EXPLAIN ANALYZE "),
+        None => Some("EXPLAIN ")
+    }
+}
+
+fn add_explain(item: sql::Generate, dialect: Option<&Dialect>, sql_text_const: &Ident, text: &str, code: &mut Vec<TokenStream>) {
+    let suffix = match item {
+        sql::Generate::Explain => "_EXPLAIN",
+        sql::Generate::ExplainAnalyze => "_EXPLAIN_ANALYZE"
+    };
+    let explain_const = companion_const(sql_text_const, suffix);
+    // unsupported variants are rejected when the SQL file is parsed
+    let explain_text = format!("{}{}", explain_prefix(item, dialect).unwrap_or_default(), text);
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #explain_const : &str = #explain_text;
+    });
+}
+
+fn healthcheck_item(stmt_name: &str, sql_text_const: &Ident, time_limit: u64) -> TokenStream {
+    let time_limit = if time_limit > 0 {
+        quote! { Some(std::time::Duration::from_millis(#time_limit)) }
+    } else {
+        quote! { None }
+    };
+    quote! {
+        include_sql_helper::HealthCheck { name: #stmt_name, sql: #sql_text_const, time_limit: #time_limit }
+    }
+}
+
+fn add_healthchecks(healthchecks: &[TokenStream], options: &Options, code: &mut Vec<TokenStream>) {
+    let macro_prefix = &options.macro_prefix;
+    let checks_const = ident!(&macro_prefix.to_uppercase(), "HEALTHCHECKS");
+    let run_fn = ident!(macro_prefix, "run_healthchecks");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #checks_const : &[include_sql_helper::HealthCheck] = &[ #( #healthchecks ),* ];
+
+        #[allow(dead_code)]
+        fn #run_fn<E>(execute: impl FnMut(&str) -> std::result::Result<(), E>) -> Vec<include_sql_helper::HealthCheckReport<E>> {
+            include_sql_helper::run_healthchecks(#checks_const, execute)
+        }
+    });
+}
+
+fn add_registry(statements: &[TokenStream], options: &Options, code: &mut Vec<TokenStream>) {
+    let registry_const = ident!(&options.macro_prefix.to_uppercase(), "SQL_STATEMENTS");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #registry_const : &[(&str, &str)] = &[ #( #statements ),* ];
+    });
+}
+
+fn migration_item(stmt_name: &str, sql_text_const: &Ident, migration: &sql::Migration) -> TokenStream {
+    let version = migration.version;
+    let down = match &migration.down {
+        Some( down ) => quote! { Some(#down) },
+        None => quote! { None }
+    };
+    quote! {
+        include_sql_helper::Migration { version: #version, name: #stmt_name, up: #sql_text_const, down: #down }
+    }
+}
+
+fn add_migrations(migrations: &[TokenStream], options: &Options, code: &mut Vec<TokenStream>) {
+    let macro_prefix = &options.macro_prefix;
+    let migrations_const = ident!(&macro_prefix.to_uppercase(), "MIGRATIONS");
+    let migrate_fn = ident!(macro_prefix, "migrate");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #migrations_const : &[include_sql_helper::Migration] = &[ #( #migrations ),* ];
+
+        #[allow(dead_code)]
+        fn #migrate_fn<R: include_sql_helper::MigrationRunner + ?Sized>(runner: &mut R) -> std::result::Result<Vec<u32>, include_sql_helper::MigrationError<R::Error>> {
+            include_sql_helper::migrate(runner, #migrations_const)
+        }
+    });
+}
+
+fn add_cache_ttl(ttl: u64, sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let ttl_const = companion_const(sql_text_const, "_CACHE_TTL");
+    code.push(quote! {
+        #[allow(dead_code)]
+        const #ttl_const : std::time::Duration = std::time::Duration::from_millis(#ttl);
+    });
+}
+
+fn add_inventory_item(path: &str, stmt_name: &str, idempotent: bool, sql_text_const: &Ident, text: &str, code: &mut Vec<TokenStream>) {
+    let hash = fnv1a(text);
+    let kind_const = companion_const(sql_text_const, "_KIND");
+    // the statement is registered even when its constants are deprecated
+    code.push(quote! {
+        #[allow(deprecated)]
+        const _ : () = {
+            include_sql_helper::inventory::submit! {
+                include_sql_helper::Statement {
+                    name: #stmt_name,
+                    kind: #kind_const,
+                    idempotent: #idempotent,
+                    file: #path,
+                    text: #sql_text_const,
+                    hash: #hash
+                }
+            }
+        };
+    });
+}
+
+/// Generates the function that creates the tracing span of the statement - `select_ship_crew_span()`.
+///
+/// The span is named after the statement and has a field for each parameter, which callers can
+/// `record` once the argument values are known.
+fn add_span_fn(path: &str, stmt_name: &str, line_num: usize, params: Option<&sql::StmtParams>, options: &Options, code: &mut Vec<TokenStream>) {
+    let fn_name = span_fn_name(stmt_name, options);
+    let line_num = line_num as u32;
+    let param_names = params.map(param_names).unwrap_or_default();
+    code.push(quote! {
+        #[allow(dead_code)]
+        fn #fn_name() -> include_sql_helper::tracing::Span {
+            include_sql_helper::tracing::info_span!(
+                #stmt_name, "sql.statement" = #stmt_name, "sql.file" = #path, "sql.line" = #line_num
+                #( , #param_names = include_sql_helper::tracing::field::Empty )*
+            )
+        }
+    });
+}
+
+fn span_fn_name(stmt_name: &str, options: &Options) -> Ident {
+    ident!(&options.macro_prefix, stmt_name, "_span")
+}
+
+/// Statement that its `<statement>_sql()` function reloads - the absolute path of its SQL file
+/// and the names of the parameters it binds in the order of their arguments.
+struct Reload {
+    file: String,
+    bound: Vec<String>
+}
+
+/// Returns how the statement is reloaded, or `None` when the statement that the helper would
+/// read from the file is not the compiled one - annotations that change the text, template
+/// variables, or parameters that are not bound positionally, like IN lists, prevent reloading.
+fn reload_spec(path: &str, stmt_name: &str, text: &str, params: Option<&sql::StmtParams>, placeholder: &Placeholder, sql_files: &mut HashMap<String, Option<(String, String)>>) -> Option<Reload> {
+    let bound = match params {
+        Some( params ) if placeholder.named || !params.lst_params.is_empty() || !params.pieces.is_empty() => return None,
+        Some( params ) => params.pos_args.iter().map(|arg| params.pos_params[arg.index].unraw().to_string()).collect(),
+        None => Vec::new()
+    };
+    let (file, sql) = sql_files.entry(path.to_string()).or_insert_with(|| {
+        let file = std::fs::canonicalize(path).ok()?;
+        let sql = std::fs::read_to_string(&file).ok()?;
+        Some((file.to_str()?.to_string(), sql))
+    }).as_ref()?;
+    let (reloaded_text, reloaded_bound) = include_sql_helper::reload_statement(sql, stmt_name, &reload_placeholders(placeholder))?;
+    let same_text = reloaded_text == text || text.strip_suffix(';') == Some(reloaded_text.as_str());
+    if same_text && reloaded_bound == bound {
+        Some(Reload { file: file.clone(), bound })
+    } else {
+        None
+    }
+}
+
+fn reload_placeholders(placeholder: &Placeholder) -> include_sql_helper::Placeholders<'_> {
+    let first = match placeholder.numbering {
+        Numbering::From(first) => Some(first),
+        Numbering::None => None
+    };
+    include_sql_helper::Placeholders { prefix: &placeholder.prefix, first, dedup: placeholder.dedup }
+}
+
+/// Generates the function that returns the statement text - `select_ship_crew_sql()`. In debug
+/// builds it is the current text of the statement in the SQL file when it can be reloaded.
+fn add_sql_fn(stmt_name: &str, sql_text_const: &Ident, reload: Option<Reload>, placeholder: &Placeholder, options: &Options, code: &mut Vec<TokenStream>) {
+    let fn_name = ident!(&options.macro_prefix, stmt_name, "_sql");
+    let body = match reload {
+        Some( Reload { file, bound } ) => {
+            let include_sql_helper::Placeholders { prefix, first, dedup } = reload_placeholders(placeholder);
+            let first = match first {
+                Some( first ) => quote! { Some(#first) },
+                None => quote! { None }
+            };
+            quote! {
+                if cfg!(debug_assertions) {
+                    let placeholders = include_sql_helper::Placeholders { prefix: #prefix, first: #first, dedup: #dedup };
+                    include_sql_helper::reloaded_text(#file, #stmt_name, &placeholders, &[ #( #bound ),* ], #sql_text_const)
+                } else {
+                    #sql_text_const
+                }
+            }
+        }
+        None => quote! { #sql_text_const }
+    };
+    code.push(quote! {
+        #[allow(dead_code)]
+        fn #fn_name() -> &'static str {
+            #body
+        }
+    });
+}
+
+/// Wraps the body of a generated function, so it is executed inside the statement span, when
+/// the functions are instrumented.
+fn traced(body: TokenStream, stmt_name: &str, options: &Options) -> TokenStream {
+    if !options.instrument {
+        return body;
+    }
+    let span_fn = span_fn_name(stmt_name, options);
+    quote! {
+        let _span = #span_fn().entered();
+        #body
+    }
+}
+
+/// Wraps the body of a generated async function, so its future is instrumented with the
+/// statement span, when the functions are instrumented. Entered spans must not be held across
+/// `.await`.
+fn traced_async(body: TokenStream, stmt_name: &str, options: &Options) -> TokenStream {
+    if !options.instrument {
+        return body;
+    }
+    let span_fn = span_fn_name(stmt_name, options);
+    quote! {
+        include_sql_helper::tracing::Instrument::instrument(async move { #body }, #span_fn()).await
+    }
+}
+
+/// Calculates 64-bit FNV-1a hash of the statement text.
+pub(crate) fn fnv1a(text: &str) -> u64 {
+    let mut hash : u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Generates the structs of the parameter groups that the argument structs embed. The files of
+/// the include must declare the groups with the same parameters.
+fn add_param_groups(path: &str, statements: &[sql::Stmt], code: &mut Vec<TokenStream>) {
+    let mut groups : Vec<&sql::ParamGroup> = Vec::new();
+    let mut conflicts : Vec<&Ident> = Vec::new();
+    for group in statements.iter().filter_map(|stmt| stmt.params.as_ref()).flat_map(|params| &params.groups) {
+        match groups.iter().find(|added| added.struct_name == group.struct_name) {
+            Some( added ) if added.params != group.params && !conflicts.contains(&&group.name) => {
+                let msg = format!("{}: parameter group `{}` is declared with other parameters in another file", path, group.name);
+                code.push(quote! { compile_error!(#msg); });
+                conflicts.push(&group.name);
+            }
+            Some( _ ) => {}
+            None => groups.push(group)
+        }
+    }
+    for sql::ParamGroup { name, struct_name, params, .. } in groups {
+        let doc = format!(" Arguments of the `{}` parameters that the argument structs share", name);
+        code.push(quote! {
+            #[doc = #doc]
+            #[derive(Clone, Copy)]
+            #[allow(dead_code)]
+            struct #struct_name<'a> {
+                #( #params : &'a dyn ToSql ),*
+            }
+        });
+    }
+}
+
+fn add_pos_params(params: &sql::StmtParams, stmt_name: &str, placeholder: &Placeholder, sql_text_const: &Ident, options: &Options, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, pos_args, .. } = params;
+    let field_types = null_types.iter().map(pos_field_type);
+    let field_docs = field_docs(params, pos_params);
+    let fields = pos_struct_fields(params, field_docs, field_types);
+    code.push(quote! {
+        struct #struct_name<'a> {
+            #( #fields ),*
+        }
+    });
+    let arg_specs = &pos_args.iter().map(|arg| {
+        let param_name = &pos_params[arg.index];
+        let param_name = match sql::ParamGroup::find(&params.groups, arg.index) {
+            Some( (group, _) ) => {
+                let group_name = &group.name;
+                quote! { #group_name . #param_name }
+            }
+            None => quote! { #param_name }
+        };
+        match &null_types[arg.index] {
+            Some( null_type ) => quote! { #param_name | #null_type },
+            None => param_name
+        }
+    }).collect::<Vec<_>>();
+    let macro_prefix = &options.macro_prefix;
+    let using_args_macro = ident!("using_", macro_prefix, stmt_name, "_args");
+    let args_macro = ident!(macro_prefix, stmt_name, "_args");
+    let using_args_macro = args_macro_spec(&using_args_macro, struct_name, options);
+    let args_macro = args_macro_spec(&args_macro, struct_name, options);
+    code.push(quote! {
+        include_sql_helper::def_args!($ => #using_args_macro = #( #arg_specs ),*);
+        include_sql_helper::def_args!($ => #args_macro = #( #arg_specs ),*);
+    });
+    let arg_values = &pos_args.iter().map(|arg| {
+        pos_arg_value(pos_field(params, arg.index, quote! { self.item }), &null_types[arg.index])
+    }).collect::<Vec<_>>();
+    let iter = ident!(&struct_name.to_string(), "ArgsIter");
+    let num_args = pos_args.len();
+    let check = bound_args_check(stmt_name, placeholder, quote! { #sql_text_const }, quote! { #num_args });
+    code.push(quote! {
+        pub(crate) struct #iter<'a> {
+            item: #struct_name<'a>,
+            index: usize
+        }
+    });
+    code.push(quote! {
+        impl<'a> std::iter::IntoIterator for #struct_name<'a> {
+            type Item = &'a dyn ToSql;
+            type IntoIter = #iter<'a>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                #check
+                #iter { item: self, index: 0 }
+            }
+        }
+    });
+    let param_nums = 0..pos_args.len();
+    let fn_next = quote! {
+        fn next(&mut self) -> std::option::Option<Self::Item> {
+            let next = match self.index {
+                #( #param_nums => Some( #arg_values ), )*
+                _ => None,
+            };
+            self.index += 1;
+            next
+        }
+    };
+    code.push(quote! {
+        impl<'a> std::iter::Iterator for #iter<'a> {
+            type Item = &'a dyn ToSql;
+            #fn_next
+
+            // lets `collect` allocate the argument vector once
+            fn size_hint(&self) -> (usize, std::option::Option<usize>) {
+                let len = #num_args.saturating_sub(self.index);
+                (len, Some(len))
+            }
+        }
+    });
+    add_try_from_map(params, code);
+    let array_values = pos_args.iter().map(|arg| {
+        pos_arg_value(pos_field(params, arg.index, quote! { self }), &null_types[arg.index])
+    });
+    code.push(quote! {
+        impl<'a> #struct_name<'a> {
+            #[allow(dead_code)]
+            fn as_array(&self) -> [&'a dyn ToSql; #num_args] {
+                [ #( #array_values ),* ]
+            }
+
+            #[allow(dead_code)]
+            fn with_extra_args<'e>(self, extra: &'e [&'a dyn ToSql]) -> std::iter::Chain<#iter<'a>, std::iter::Copied<std::slice::Iter<'e, &'a dyn ToSql>>> {
+                std::iter::IntoIterator::into_iter(self).chain(extra.iter().copied())
+            }
+        }
+    });
+}
+
+fn add_named_params(params: &sql::StmtParams, stmt_name: &str, placeholder: &Placeholder, options: &Options, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, .. } = params;
+    let field_types = null_types.iter().map(pos_field_type);
+    let field_docs = field_docs(params, pos_params);
+    code.push(quote! {
+        struct #struct_name<'a> {
+            #( #field_docs #pos_params : #field_types ),*
+        }
+    });
+    let num_args = pos_params.len();
+    let arg_names = pos_params.iter().map(|param_name| format!("{}{}", placeholder.prefix, param_name.unraw()));
+    let arg_values = pos_params.iter().zip(null_types).map(|(param_name, null_type)| {
+        pos_arg_value(quote! { self.#param_name }, null_type)
+    });
+    code.push(quote! {
+        impl<'a> #struct_name<'a> {
+            #[allow(dead_code)]
+            fn named_args(&self) -> [(&'static str, &'a dyn ToSql); #num_args] {
+                [ #( (#arg_names, #arg_values) ),* ]
+            }
+        }
+    });
+    let macro_prefix = &options.macro_prefix;
+    let using_args_macro = ident!("using_", macro_prefix, stmt_name, "_args");
+    let args_macro = ident!(macro_prefix, stmt_name, "_args");
+    let using_args_macro = args_macro_spec(&using_args_macro, struct_name, options);
+    let args_macro = args_macro_spec(&args_macro, struct_name, options);
+    code.push(quote! {
+        include_sql_helper::def_named_args!($ => #using_args_macro);
+        include_sql_helper::def_named_args!($ => #args_macro);
+    });
+}
+
+/// Returns the name of the argument macro and its struct for `def_args!` - the struct is
+/// referred to by its path from the crate root when the macro is exported.
+fn args_macro_spec(macro_name: &Ident, struct_name: &Ident, options: &Options) -> TokenStream {
+    match &options.export {
+        Some( module ) => quote! { #[macro_export] #macro_name : [#module] #struct_name },
+        None => quote! { #macro_name : #struct_name }
+    }
+}
+
+/// Generates the trait of the statement arguments - `SelectShipCrewArgs` - with a method for each
+/// parameter, which the argument struct implements. Types of the application implement it to be
+/// bound without the argument struct.
+fn add_args_trait(params: &sql::StmtParams, stmt_name: &str, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, pos_args, .. } = params;
+    let trait_name = ident!(&struct_name.to_string(), "Args");
+    let doc = format!(" Arguments of `{}`", stmt_name);
+    let method_docs = field_docs(params, pos_params);
+    let arg_types = &null_types.iter().map(pos_field_type).collect::<Vec<_>>();
+    let arg_values = pos_args.iter().map(|arg| {
+        let param_name = &pos_params[arg.index];
+        pos_arg_value(quote! { self.#param_name() }, &null_types[arg.index])
+    });
+    let num_args = pos_args.len();
+    code.push(quote! {
+        #[doc = #doc]
+        #[allow(dead_code)]
+        trait #trait_name<'a> {
+            #( #method_docs fn #pos_params(&self) -> #arg_types; )*
+
+            /// Returns the arguments in the order they are bound to the statement.
+            fn bind_args(&self) -> [&'a dyn ToSql; #num_args] {
+                [ #( #arg_values ),* ]
+            }
+        }
+    });
+    let fields = (0..pos_params.len()).map(|index| pos_field(params, index, quote! { self }));
+    code.push(quote! {
+        impl<'a> #trait_name<'a> for #struct_name<'a> {
+            #( fn #pos_params(&self) -> #arg_types { #fields } )*
+        }
+    });
+}
+
+/// Generates the macro that fills the argument struct from the fields of a domain struct that
+/// are named after the parameters - `select_ship_crew_args_from!(sailor)`. Identifiers, tuple
+/// lists and parameter groups have no counterparts in domain structs, thus their statements do
+/// not get one.
+fn add_args_from_macro(params: &sql::StmtParams, stmt_name: &str, options: &Options, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, lst_params, pieces, idents, groups, .. } = params;
+    if !idents.is_empty() || !groups.is_empty() || lst_params.iter().any(|param| param.tuple_len.is_some()) {
+        return;
+    }
+    let cond_params : Vec<usize> = pieces.iter().filter_map(|piece| match piece {
+        sql::Piece::Block(index, _) => Some(*index),
+        _ => None
+    }).collect();
+    let is_optional = |index: usize| null_types[index].is_some() || cond_params.contains(&index);
+    let fields = pos_params.iter().enumerate().filter(|&(index, _)| !is_optional(index)).map(|(_, name)| name);
+    let opt_fields = pos_params.iter().enumerate().filter(|&(index, _)| is_optional(index)).map(|(_, name)| name);
+    let lst_fields = unique_lst_params(lst_params);
+    let macro_name = ident!(&options.macro_prefix, stmt_name, "_args_from");
+    let macro_spec = args_macro_spec(&macro_name, struct_name, options);
+    code.push(quote! {
+        include_sql_helper::def_args_from!($ => #macro_spec = [ #( #fields ),* ] [ #( #opt_fields ),* ] [ #( #lst_fields ),* ]);
+    });
+}
+
+fn add_owned_args(params: &sql::StmtParams, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, lst_params, .. } = params;
+    let owned_struct_name = ident!(&struct_name.to_string(), "Owned");
+    let lst_fields = &unique_lst_params(lst_params);
+    let field_types = null_types.iter().map(|null_type| match null_type {
+        Some( _ ) => quote! { Option<Box<dyn ToSql>> },
+        None => quote! { Box<dyn ToSql> }
+    });
+    let lst_types = lst_fields.iter().map(|name| match tuple_len(lst_params, name) {
+        Some( len ) => quote! { Vec<[Box<dyn ToSql>; #len]> },
+        None => quote! { Vec<Box<dyn ToSql>> }
+    });
+    let pos_docs = field_docs(params, pos_params);
+    let lst_docs = field_docs(params, lst_fields.iter().copied());
+    code.push(quote! {
+        #[allow(dead_code)]
+        struct #owned_struct_name {
+            #( #pos_docs #pos_params : #field_types, )*
+            #( #lst_docs #lst_fields : #lst_types ),*
+        }
+    });
+    let lifetime = if pos_params.is_empty() { quote! {} } else { quote! { '_, } };
+    let lst_iters = lst_fields.iter().map(|name| match tuple_len(lst_params, name) {
+        Some( len ) => quote! { impl Iterator<Item = [&dyn ToSql; #len]> + '_ },
+        None => quote! { impl Iterator<Item = &dyn ToSql> + '_ }
+    });
+    let pos_values = pos_field_inits(params, pos_params.iter().zip(null_types).map(|(param_name, null_type)| match null_type {
+        Some( _ ) => quote! { self.#param_name.as_ref().map(|arg| &**arg as &dyn ToSql) },
+        None => quote! { &*self.#param_name }
+    }));
+    let lst_values = lst_fields.iter().map(|name| match tuple_len(lst_params, name) {
+        Some( _ ) => quote! { self.#name.iter().map(|tuple| tuple.each_ref().map(|arg| &**arg as &dyn ToSql)) },
+        None => quote! { self.#name.iter().map(|arg| &**arg as &dyn ToSql) }
+    });
+    code.push(quote! {
+        #[allow(dead_code)]
+        impl #owned_struct_name {
+            fn as_args(&self) -> #struct_name< #lifetime #( #lst_iters ),* > {
+                #struct_name {
+                    #( #pos_values, )*
+                    #( #lst_fields : #lst_values ),*
+                }
+            }
+        }
+    });
+}
+
+/// Returns type parameters of the generic argument struct - one for each field.
+fn type_params(fields: &[&Ident]) -> Vec<Ident> {
+    fields.iter().map(|name| ident!(&sql::to_camel_case(&name.unraw().to_string()))).collect()
+}
+
+/// Returns bounds of the type parameters and argument trait objects.
+fn arg_bounds(options: &Options) -> TokenStream {
+    let arg_bounds = &options.arg_bounds;
+    quote! { ToSql #( + #arg_bounds )* }
+}
+
+/// Returns type parameters with their bounds.
+fn bounded_type_params(types: &[Ident], bounds: &TokenStream) -> Vec<TokenStream> {
+    types.iter().map(|name| quote! { #name : #bounds }).collect()
+}
+
+fn add_generic_pos_params(params: &sql::StmtParams, options: &Options, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, pos_args, .. } = params;
+    let fields = &pos_params.iter().collect::<Vec<_>>();
+    let types = &type_params(fields);
+    let bounds = &arg_bounds(options);
+    let type_bounds = &bounded_type_params(types, bounds);
+    let field_docs = field_docs(params, pos_params);
+    code.push(quote! {
+        struct #struct_name< #( #type_bounds ),* > {
+            #( #field_docs #fields : #types ),*
+        }
+    });
+    let pos_args = &pos_args.iter().map(|arg| &pos_params[arg.index]).collect::<Vec<_>>();
+    let num_args = pos_args.len();
+    code.push(quote! {
+        impl< #( #type_bounds ),* > #struct_name< #( #types ),* > {
+            fn args(&self) -> [&(dyn #bounds); #num_args] {
+                [ #( &self.#pos_args ),* ]
+            }
+        }
+    });
+}
+
+fn add_rusqlite_fn(stmt_name: &str, kind: sql::StmtKind, sql_text_const: &Ident, params: Option<&sql::StmtParams>, columns: Option<usize>, options: &Options, code: &mut Vec<TokenStream>) {
+    let fn_name = ident!(&options.macro_prefix, stmt_name);
+    let check = column_count_check(stmt_name, columns, sql_text_const, quote! { stmt.column_count() });
+    let (map_row, execute, result) = match kind {
+        sql::StmtKind::Query => (
+            quote! { T, F: FnMut(&include_sql_helper::rusqlite::Row<'_>) -> include_sql_helper::rusqlite::Result<T> },
+            quote! {
+                let rows = stmt.query_map(args, map_row)?;
+                rows.collect()
+            },
+            quote! { Vec<T> }
+        ),
+        sql::StmtKind::FetchOne => (
+            quote! { T, F: FnOnce(&include_sql_helper::rusqlite::Row<'_>) -> include_sql_helper::rusqlite::Result<T> },
+            quote! { stmt.query_row(args, map_row) },
+            quote! { T }
+        ),
+        sql::StmtKind::Execute => (
+            quote! {},
+            quote! { stmt.execute(args) },
+            quote! { usize }
+        )
+    };
+    let map_row_arg = match kind {
+        sql::StmtKind::Execute => quote! {},
+        _ => quote! { , map_row: F }
+    };
+    let params = match params {
+        Some( params ) => params,
+        None => {
+            let body = traced(quote! {
+                let args = include_sql_helper::rusqlite::NO_PARAMS;
+                let mut stmt = conn.prepare_cached(#sql_text_const)?;
+                #check
+                #execute
+            }, stmt_name, options);
+            code.push(quote! {
+                #[allow(dead_code)]
+                fn #fn_name< #map_row >(conn: &include_sql_helper::rusqlite::Connection #map_row_arg) -> include_sql_helper::rusqlite::Result<#result> {
+                    #body
+                }
+            });
+            return;
+        }
+    };
+    let sql::StmtParams { struct_name, pos_params, null_types, lst_params, .. } = params;
+    let (type_bounds, _) = if lst_params.is_empty() {
+        (quote! { 'a }, quote! {})
+    } else {
+        lst_struct_generics(params)
+    };
+    let pos_types = null_types.iter().map(pos_field_type);
+    let lst_fields = &unique_lst_params(lst_params);
+    let lst_types = &type_params(lst_fields);
+    let map_row = if map_row.is_empty() { map_row } else { quote! { , #map_row } };
+    let pos_fields = pos_field_inits(params, pos_params.iter().map(|param_name| quote! { #param_name }));
+    let body = traced(quote! {
+        let (sql, args) = include_sql_helper::IntoSqlWithArgs::into_sql_with_args(#struct_name { #( #pos_fields, )* #( #lst_fields ),* });
+        let mut stmt = conn.prepare_cached(&sql)?;
+        #check
+        #execute
+    }, stmt_name, options);
+    code.push(quote! {
+        #[allow(dead_code)]
+        fn #fn_name< #type_bounds #map_row >(conn: &include_sql_helper::rusqlite::Connection #( , #pos_params : #pos_types )* #( , #lst_fields : #lst_types )* #map_row_arg) -> include_sql_helper::rusqlite::Result<#result> {
+            #body
+        }
+    });
+}
+
+fn add_postgres_fn(stmt_name: &str, kind: sql::StmtKind, sql_text_const: &Ident, params: Option<&sql::StmtParams>, columns: Option<usize>, options: &Options, code: &mut Vec<TokenStream>) {
+    let fn_name = ident!(&options.macro_prefix, stmt_name);
+    let (method, result) = match kind {
+        sql::StmtKind::Query    => (quote! { query }, quote! { Vec<include_sql_helper::postgres::Row> }),
+        sql::StmtKind::FetchOne => (quote! { query_one }, quote! { include_sql_helper::postgres::Row }),
+        sql::StmtKind::Execute  => (quote! { execute }, quote! { u64 })
+    };
+    // statements are not prepared separately, thus the columns are checked on the returned rows
+    let row_check = column_count_check(stmt_name, columns, sql_text_const, quote! { row.len() });
+    let check = &match kind {
+        _ if columns.is_none() => quote! {},
+        sql::StmtKind::Query => quote! {
+            if let Ok( rows ) = &res {
+                if let Some( row ) = rows.first() {
+                    #row_check
+                }
+            }
+        },
+        sql::StmtKind::FetchOne => quote! {
+            if let Ok( row ) = &res {
+                #row_check
+            }
+        },
+        sql::StmtKind::Execute => quote! {}
+    };
+    let params = match params {
+        Some( params ) => params,
+        None => {
+            let body = traced(quote! {
+                let res = client.#method(#sql_text_const, &[]);
+                #check
+                res
+            }, stmt_name, options);
+            code.push(quote! {
+                #[allow(dead_code)]
+                fn #fn_name<C: include_sql_helper::postgres::GenericClient>(client: &mut C) -> std::result::Result<#result, include_sql_helper::postgres::Error> {
+                    #body
+                }
+            });
+            return;
+        }
+    };
+    let sql::StmtParams { struct_name, pos_params, pos_args, lst_params, .. } = params;
+    let bounds = &arg_bounds(options);
+    let pos_fields = &pos_params.iter().collect::<Vec<_>>();
+    let lst_fields = &unique_lst_params(lst_params);
+    let pos_types = &type_params(pos_fields);
+    let lst_types = &type_params(lst_fields);
+    let pos_type_bounds = &bounded_type_params(pos_types, bounds);
+    let lst_type_bounds = &bounded_type_params(lst_types, bounds);
+    let lifetime = if lst_params.is_empty() { quote! {} } else { quote! { 'a, } };
+    // the arguments are passed as `&(dyn ToSql + Sync)` even when they have additional bounds
+    let execute = if lst_params.is_empty() {
+        let pos_args = pos_args.iter().map(|arg| &pos_params[arg.index]);
+        quote! {
+            let args = #struct_name { #( #pos_fields ),* };
+            let res = client.#method(#sql_text_const, &[ #( &args.#pos_args as &(dyn ToSql + Sync) ),* ]);
+            #check
+            res
+        }
+    } else {
+        quote! {
+            let args = #struct_name { #( #pos_fields, )* #( #lst_fields ),* };
+            let (sql, args) = args.into_sql_with_args();
+            let args : Vec<&(dyn ToSql + Sync)> = args.into_iter().map(|arg| arg as &(dyn ToSql + Sync)).collect();
+            let res = client.#method(sql.as_str(), &args);
+            #check
+            res
+        }
+    };
+    let execute = traced(execute, stmt_name, options);
+    code.push(quote! {
+        #[allow(dead_code)]
+        fn #fn_name< #lifetime C: include_sql_helper::postgres::GenericClient #( , #pos_type_bounds )* #( , #lst_type_bounds )* >(client: &mut C #( , #pos_fields : #pos_types )* #( , #lst_fields : &'a [#lst_types] )*) -> std::result::Result<#result, include_sql_helper::postgres::Error> {
+            #execute
+        }
+    });
+}
+
+fn add_async_fn(stmt_name: &str, kind: sql::StmtKind, sql_text_const: &Ident, params: Option<&sql::StmtParams>, columns: Option<usize>, options: &Options, code: &mut Vec<TokenStream>) {
+    let fn_name = ident!(&options.macro_prefix, stmt_name);
+    let check = &column_count_check(stmt_name, columns, sql_text_const, quote! { stmt.columns().len() });
+    let (method, result) = match kind {
+        sql::StmtKind::Query    => (quote! { query }, quote! { Vec<include_sql_helper::tokio_postgres::Row> }),
+        sql::StmtKind::FetchOne => (quote! { query_one }, quote! { include_sql_helper::tokio_postgres::Row }),
+        sql::StmtKind::Execute  => (quote! { execute }, quote! { u64 })
+    };
+    let params = match params {
+        Some( params ) => params,
+        None => {
+            let body = traced_async(quote! {
+                let stmt = client.prepare_cached(#sql_text_const).await?;
+                #check
+                client.#method(&stmt, &[]).await
+            }, stmt_name, options);
+            code.push(quote! {
+                #[allow(dead_code)]
+                async fn #fn_name<C: include_sql_helper::AsyncClient>(client: &C) -> std::result::Result<#result, include_sql_helper::tokio_postgres::Error> {
+                    #body
+                }
+            });
+            return;
+        }
+    };
+    let sql::StmtParams { struct_name, pos_params, pos_args, lst_params, .. } = params;
+    let bounds = &arg_bounds(options);
+    let pos_types = &type_params(&pos_params.iter().collect::<Vec<_>>());
+    let lst_types = &type_params(&unique_lst_params(lst_params));
+    let pos_type_bounds = &bounded_type_params(pos_types, bounds);
+    let lst_type_bounds = &bounded_type_params(lst_types, bounds);
+    // generic argument structs with lists borrow them for `'a`
+    let lifetime = if lst_params.is_empty() { quote! {} } else { quote! { 'a, } };
+    // the arguments are passed as `&(dyn ToSql + Sync)` even when they have additional bounds
+    let execute = if lst_params.is_empty() {
+        let pos_args = pos_args.iter().map(|arg| &pos_params[arg.index]);
+        quote! {
+            let stmt = client.prepare_cached(#sql_text_const).await?;
+            #check
+            client.#method(&stmt, &[ #( &args.#pos_args as &(dyn ToSql + Sync) ),* ]).await
+        }
+    } else {
+        quote! {
+            let (sql, args) = args.into_sql_with_args();
+            let args : Vec<&(dyn ToSql + Sync)> = args.into_iter().map(|arg| arg as &(dyn ToSql + Sync)).collect();
+            let stmt = client.prepare(&sql).await?;
+            #check
+            client.#method(&stmt, &args).await
+        }
+    };
+    let execute = traced_async(execute, stmt_name, options);
+    code.push(quote! {
+        #[allow(dead_code)]
+        async fn #fn_name< #lifetime C: include_sql_helper::AsyncClient #( , #pos_type_bounds )* #( , #lst_type_bounds )* >(client: &C, args: &#struct_name< #lifetime #( #pos_types, )* #( #lst_types ),* >) -> std::result::Result<#result, include_sql_helper::tokio_postgres::Error> {
+            #execute
+        }
+    });
+}
+
+/// Generates the function that executes the statement via `include_sql_helper::Executor`.
+///
+/// The function only depends on the trait, thus it works with any database interface that
+/// implements it for the argument trait objects - `dyn ToSql` with the optional `arg_bounds`.
+fn add_executor_fn(stmt_name: &str, kind: sql::StmtKind, sql_text_const: &Ident, params: Option<&sql::StmtParams>, options: &Options, code: &mut Vec<TokenStream>) {
+    let fn_name = ident!(&options.macro_prefix, stmt_name);
+    let (method, result) = match kind {
+        sql::StmtKind::Query    => (quote! { query }, quote! { Vec<E::Row> }),
+        sql::StmtKind::FetchOne => (quote! { query_one }, quote! { E::Row }),
+        sql::StmtKind::Execute  => (quote! { execute }, quote! { u64 })
+    };
+    let bounds = &arg_bounds(options);
+    let params = match params {
+        Some( params ) => params,
+        None => {
+            let body = traced(quote! { executor.#method(#sql_text_const, &[]) }, stmt_name, options);
+            code.push(quote! {
+                #[allow(dead_code)]
+                fn #fn_name<E: include_sql_helper::Executor<dyn #bounds>>(executor: &mut E) -> std::result::Result<#result, E::Error> {
+                    #body
+                }
+            });
+            return;
+        }
+    };
+    let sql::StmtParams { struct_name, pos_params, lst_params, .. } = params;
+    if options.args == ArgsMode::Refs {
+        let (type_bounds, type_args) = if lst_params.is_empty() {
+            (quote! { 'a }, struct_lifetime(params))
+        } else {
+            lst_struct_generics(params)
+        };
+        let body = traced(quote! {
+            let (sql, args) = include_sql_helper::IntoSqlWithArgs::into_sql_with_args(args);
+            executor.#method(&sql, &args)
+        }, stmt_name, options);
+        code.push(quote! {
+            #[allow(dead_code)]
+            fn #fn_name< #type_bounds, E: include_sql_helper::Executor<dyn ToSql + 'a> >(executor: &mut E, args: #struct_name< #type_args >) -> std::result::Result<#result, E::Error> {
+                #body
+            }
+        });
+        return;
+    }
+    let pos_types = &type_params(&pos_params.iter().collect::<Vec<_>>());
+    let lst_types = &type_params(&unique_lst_params(lst_params));
+    let pos_type_bounds = &bounded_type_params(pos_types, bounds);
+    let lst_type_bounds = &bounded_type_params(lst_types, bounds);
+    let (type_args, execute) = if lst_params.is_empty() {
+        (quote! { #( #pos_types ),* }, quote! { executor.#method(#sql_text_const, &args.args()) })
+    } else {
+        (
+            quote! { 'a, #( #pos_types, )* #( #lst_types ),* },
+            quote! {
+                let (sql, args) = args.into_sql_with_args();
+                executor.#method(&sql, &args)
+            }
+        )
+    };
+    let execute = traced(execute, stmt_name, options);
+    code.push(quote! {
+        #[allow(dead_code)]
+        fn #fn_name< 'a, #( #pos_type_bounds, )* #( #lst_type_bounds, )* E: include_sql_helper::Executor<dyn #bounds + 'a> >(executor: &mut E, args: &'a #struct_name< #type_args >) -> std::result::Result<#result, E::Error> {
+            #execute
+        }
+    });
+}
+
+/// Tells whether the results of the generated function can be kept in `StatementCache`. The
+/// cache is keyed on the arguments, thus they must be generic values without lists, and only
+/// query results are cached.
+fn is_cacheable(kind: sql::StmtKind, params: Option<&sql::StmtParams>, options: &Options) -> bool {
+    !matches!(kind, sql::StmtKind::Execute) && params.is_none_or(|params| {
+        options.args == ArgsMode::Generic && params.lst_params.is_empty() && params.pieces.is_empty()
+    })
+}
+
+/// Generates the `_cached` variant of the statement function for statements annotated with
+/// `-- cache:`. It returns the result that the passed `StatementCache` holds for the arguments,
+/// or calls the statement function and keeps its result in the cache for `<STATEMENT>_CACHE_TTL`.
+/// Rows are not `Clone`, thus results are shared via `Arc`.
+fn add_cached_fn(functions: Functions, stmt_name: &str, kind: sql::StmtKind, sql_text_const: &Ident, params: Option<&sql::StmtParams>, options: &Options, code: &mut Vec<TokenStream>) {
+    let fn_name = ident!(&options.macro_prefix, stmt_name);
+    let cached_fn_name = ident!(&options.macro_prefix, stmt_name, "_cached");
+    let ttl_const = companion_const(sql_text_const, "_CACHE_TTL");
+    let pos_fields = &params.map_or_else(Vec::new, |params| params.pos_params.iter().collect::<Vec<_>>());
+    let pos_types = &type_params(pos_fields);
+    let bounds = arg_bounds(options);
+    let key_bounds = quote! { #bounds + std::hash::Hash + Eq + Clone };
+    let pos_type_bounds = &bounded_type_params(pos_types, &key_bounds);
+    let (type_bounds, conn_arg, call_args, key_fields, result, error) = match functions {
+        Functions::Rusqlite => {
+            let row_fn = if matches!(kind, sql::StmtKind::Query) { quote! { FnMut } } else { quote! { FnOnce } };
+            let result = if matches!(kind, sql::StmtKind::Query) { quote! { Vec<T> } } else { quote! { T } };
+            (
+                quote! { T, F: #row_fn(&include_sql_helper::rusqlite::Row<'_>) -> include_sql_helper::rusqlite::Result<T> },
+                quote! { conn: &include_sql_helper::rusqlite::Connection },
+                quote! { conn, map_row },
+                quote! {},
+                result,
+                quote! { include_sql_helper::rusqlite::Error }
+            )
+        }
+        Functions::Postgres => {
+            let result = if matches!(kind, sql::StmtKind::Query) { quote! { Vec<include_sql_helper::postgres::Row> } } else { quote! { include_sql_helper::postgres::Row } };
+            (
+                quote! { C: include_sql_helper::postgres::GenericClient #( , #pos_type_bounds )* },
+                quote! { client: &mut C #( , #pos_fields : #pos_types )* },
+                quote! { client #( , #pos_fields )* },
+                quote! { #( #pos_fields.clone(), )* },
+                result,
+                quote! { include_sql_helper::postgres::Error }
+            )
+        }
+        Functions::TokioPostgres => {
+            let result = if matches!(kind, sql::StmtKind::Query) { quote! { Vec<include_sql_helper::tokio_postgres::Row> } } else { quote! { include_sql_helper::tokio_postgres::Row } };
+            let (args_arg, call_args) = match params {
+                Some( params ) => {
+                    let struct_name = &params.struct_name;
+                    (quote! { , args: &#struct_name< #( #pos_types ),* > }, quote! { client, args })
+                }
+                None => (quote! {}, quote! { client })
+            };
+            (
+                quote! { C: include_sql_helper::AsyncClient #( , #pos_type_bounds )* },
+                quote! { client: &C #args_arg },
+                call_args,
+                quote! { #( args.#pos_fields.clone(), )* },
+                result,
+                quote! { include_sql_helper::tokio_postgres::Error }
+            )
+        }
+        Functions::Executor => {
+            let result = if matches!(kind, sql::StmtKind::Query) { quote! { Vec<E::Row> } } else { quote! { E::Row } };
+            match params {
+                Some( params ) => {
+                    let struct_name = &params.struct_name;
+                    (
+                        quote! { 'a, #( #pos_type_bounds, )* E: include_sql_helper::Executor<dyn #bounds + 'a> },
+                        quote! { executor: &mut E, args: &'a #struct_name< #( #pos_types ),* > },
+                        quote! { executor, args },
+                        quote! { #( args.#pos_fields.clone(), )* },
+                        result,
+                        quote! { E::Error }
+                    )
+                }
+                None => (
+                    quote! { E: include_sql_helper::Executor<dyn #bounds> },
+                    quote! { executor: &mut E },
+                    quote! { executor },
+                    quote! {},
+                    result,
+                    quote! { E::Error }
+                )
+            }
+        }
+    };
+    let map_row_arg = if functions == Functions::Rusqlite { quote! { , map_row: F } } else { quote! {} };
+    let (asyncness, call) = if functions == Functions::TokioPostgres {
+        (quote! { async }, quote! { #fn_name(#call_args).await? })
+    } else {
+        (quote! {}, quote! { #fn_name(#call_args)? })
+    };
+    code.push(quote! {
+        #[allow(dead_code)]
+        #asyncness fn #cached_fn_name< #type_bounds >(cache: &include_sql_helper::StatementCache<( #( #pos_types, )* ), std::sync::Arc<#result>>, #conn_arg #map_row_arg) -> std::result::Result<std::sync::Arc<#result>, #error> {
+            let cache_key = ( #key_fields );
+            if let Some( res ) = cache.get(#stmt_name, &cache_key) {
+                return Ok(res);
+            }
+            let res = std::sync::Arc::new(#call);
+            cache.insert(#stmt_name, cache_key, #ttl_const, res.clone());
+            Ok(res)
+        }
+    });
+}
+
+fn add_try_from_map(params: &sql::StmtParams, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, .. } = params;
+    let names = &pos_params.iter().map(|name| name.unraw().to_string()).collect::<Vec<_>>();
+    let values = pos_field_inits(params, pos_params.iter().zip(null_types).map(|(param_name, null_type)| {
+        let name = param_name.unraw().to_string();
+        match null_type {
+            Some( _ ) => quote! { map.get(#name).copied() },
+            None => quote! { *map.get(#name).ok_or(include_sql_helper::ArgsError::Missing(#name))? }
+        }
+    }));
+    code.push(quote! {
+        impl<'a, 'm, S: std::hash::BuildHasher> std::convert::TryFrom<&'m std::collections::HashMap<&'m str, &'a dyn ToSql, S>> for #struct_name<'a> {
+            type Error = include_sql_helper::ArgsError;
+
+            fn try_from(map: &'m std::collections::HashMap<&'m str, &'a dyn ToSql, S>) -> std::result::Result<Self, Self::Error> {
+                if let Some( name ) = map.keys().find(|name| ![ #( #names ),* ].contains(name)) {
+                    return Err(include_sql_helper::ArgsError::Unknown(name.to_string()));
+                }
+                Ok( #struct_name {
+                    #( #values ),*
+                })
+            }
+        }
+    });
+}
+
+/// Returns the type of the positional parameter field of the arguments struct.
+/// Generates the `Debug` implementation of the argument struct. Values of `&dyn ToSql` arguments
+/// and of lists cannot be printed, thus only whether they are provided is - `ship: ..`. Generic
+/// arguments are printed when their types implement `Debug`.
+fn add_debug_args(params: &sql::StmtParams, options: &Options, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, lst_params, pieces, idents, .. } = params;
+    let lst_fields = &unique_lst_params(lst_params);
+    let generic = options.args == ArgsMode::Generic && pieces.is_empty() && !options.named;
+    let (type_bounds, type_args, debug_bounds) = if generic {
+        let (type_bounds, type_args, types) = generic_struct_generics(params, options);
+        (type_bounds, type_args, quote! { where #( #types : std::fmt::Debug ),* })
+    } else if !lst_fields.is_empty() {
+        let (type_bounds, type_args) = lst_struct_generics(params);
+        (type_bounds, type_args, quote! {})
+    } else if pieces.is_empty() {
+        (quote! { 'a }, quote! { 'a }, quote! {})
+    } else {
+        let lifetime = struct_lifetime(params);
+        (lifetime.clone(), lifetime, quote! {})
+    };
+    let cond_params : Vec<usize> = pieces.iter().filter_map(|piece| match piece {
+        sql::Piece::Block(index, _) => Some(*index),
+        _ => None
+    }).collect();
+    let pos_values = pos_params.iter().enumerate().map(|(index, name)| {
+        if generic {
+            quote! { &self.#name }
+        } else if null_types[index].is_some() || cond_params.contains(&index) {
+            quote! { &self.#name.map(|_| format_args!("..")) }
+        } else {
+            quote! { &format_args!("..") }
+        }
+    });
+    let lst_values = lst_fields.iter().map(|name| {
+        if generic { quote! { &self.#name } } else { quote! { &format_args!("..") } }
+    });
+    let pos_names = pos_params.iter().map(|name| name.unraw().to_string());
+    let lst_names = lst_fields.iter().map(|name| name.unraw().to_string());
+    let ident_fields = &idents.iter().map(|ident| &ident.name).collect::<Vec<_>>();
+    let ident_names = idents.iter().map(|ident| ident.name.unraw().to_string());
+    let type_name = struct_name.to_string();
+    code.push(quote! {
+        impl< #type_bounds > std::fmt::Debug for #struct_name< #type_args > #debug_bounds {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(#type_name)
+                    #( .field(#pos_names, #pos_values) )*
+                    #( .field(#lst_names, #lst_values) )*
+                    #( .field(#ident_names, &self.#ident_fields) )*
+                    .finish()
+            }
+        }
+    });
+}
+
+/// Generates the builder of the argument struct - `SelectShipCrew::builder().ship(&id).build()`.
+/// Arguments of optional parameters and conditional blocks can be left out, the others are
+/// required and `build` returns the `ArgsError::Missing` error of the first one that is missing.
+fn add_args_builder(params: &sql::StmtParams, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, lst_params, pieces, idents, .. } = params;
+    let builder_name = ident!(&struct_name.to_string(), "Builder");
+    let lst_fields = &unique_lst_params(lst_params);
+    let lst_types = &type_params(lst_fields);
+    let (type_bounds, type_args) = if !lst_fields.is_empty() {
+        lst_struct_generics(params)
+    } else if pieces.is_empty() {
+        (quote! { 'a }, quote! { 'a })
+    } else {
+        let lifetime = struct_lifetime(params);
+        (lifetime.clone(), lifetime)
+    };
+    let cond_params : Vec<usize> = pieces.iter().filter_map(|piece| match piece {
+        sql::Piece::Block(index, _) => Some(*index),
+        _ => None
+    }).collect();
+    let ident_fields = &idents.iter().map(|ident| &ident.name).collect::<Vec<_>>();
+    let ident_types = &idents.iter().map(|ident| ident!(&struct_name.to_string(), &sql::to_camel_case(&ident.name.unraw().to_string()))).collect::<Vec<_>>();
+    let pos_values = pos_field_inits(params, pos_params.iter().enumerate().map(|(index, name)| {
+        if null_types[index].is_some() || cond_params.contains(&index) {
+            quote! { self.#name }
+        } else {
+            let param_name = name.unraw().to_string();
+            quote! { self.#name.ok_or(include_sql_helper::ArgsError::Missing(#param_name))? }
+        }
+    }));
+    let lst_values = lst_fields.iter().map(|name| {
+        let param_name = name.unraw().to_string();
+        quote! { self.#name.ok_or(include_sql_helper::ArgsError::Missing(#param_name))? }
+    });
+    let ident_values = ident_fields.iter().map(|name| {
+        let param_name = name.unraw().to_string();
+        quote! { self.#name.ok_or(include_sql_helper::ArgsError::Missing(#param_name))? }
+    });
+    let pos_docs = &field_docs(params, pos_params);
+    let lst_docs = &field_docs(params, lst_fields.iter().copied());
+    let ident_docs = &field_docs(params, ident_fields.iter().copied());
+    let fields = &pos_params.iter().chain(lst_fields.iter().copied()).chain(ident_fields.iter().copied()).collect::<Vec<_>>();
+    code.push(quote! {
+        #[allow(dead_code)]
+        struct #builder_name< #type_args > {
+            #( #pos_params : Option<&'a dyn ToSql>, )*
+            #( #lst_fields : Option<#lst_types>, )*
+            #( #ident_fields : Option<#ident_types>, )*
+        }
+    });
+    code.push(quote! {
+        impl< #type_bounds > #struct_name< #type_args > {
+            /// Returns the builder of the statement arguments.
+            #[allow(dead_code)]
+            fn builder() -> #builder_name< #type_args > {
+                #builder_name { #( #fields : None ),* }
+            }
+        }
+    });
+    let pos_setters = pos_params.iter().zip(pos_docs).map(|(name, doc)| builder_setter(name, doc, quote! { &'a dyn ToSql }));
+    let lst_setters = lst_fields.iter().zip(lst_types).zip(lst_docs).map(|((name, lst_type), doc)| builder_setter(name, doc, quote! { #lst_type }));
+    let ident_setters = ident_fields.iter().zip(ident_types).zip(ident_docs).map(|((name, ident_type), doc)| builder_setter(name, doc, quote! { #ident_type }));
+    code.push(quote! {
+        impl< #type_bounds > #builder_name< #type_args > {
+            #( #pos_setters )*
+            #( #lst_setters )*
+            #( #ident_setters )*
+
+            /// Returns the arguments, or the error of the first required argument that is missing.
+            #[allow(dead_code)]
+            fn build(self) -> std::result::Result<#struct_name< #type_args >, include_sql_helper::ArgsError> {
+                Ok( #struct_name {
+                    #( #pos_values, )*
+                    #( #lst_fields : #lst_values, )*
+                    #( #ident_fields : #ident_values, )*
+                })
+            }
+        }
+    });
+}
+
+/// Returns the method of the argument struct builder that sets the argument of the parameter.
+fn builder_setter(name: &Ident, doc: &TokenStream, arg_type: TokenStream) -> TokenStream {
+    quote! {
+        #doc
+        #[allow(dead_code)]
+        fn #name(mut self, value: #arg_type) -> Self {
+            self.#name = Some(value);
+            self
+        }
+    }
+}
+
+/// Returns the generics of the `args = generic` argument struct - type parameters with their
+/// bounds, type arguments and the type parameters themselves.
+fn generic_struct_generics(params: &sql::StmtParams, options: &Options) -> (TokenStream, TokenStream, Vec<Ident>) {
+    let pos_fields = &params.pos_params.iter().collect::<Vec<_>>();
+    let lst_fields = &unique_lst_params(&params.lst_params);
+    let pos_types = &type_params(pos_fields);
+    let lst_types = &type_params(lst_fields);
+    let bounds = &arg_bounds(options);
+    let pos_type_bounds = bounded_type_params(pos_types, bounds);
+    let lst_type_bounds = bounded_type_params(lst_types, bounds);
+    let lifetime = if lst_fields.is_empty() { quote! {} } else { quote! { 'a, } };
+    let type_bounds = quote! { #lifetime #( #pos_type_bounds, )* #( #lst_type_bounds ),* };
+    let type_args = quote! { #lifetime #( #pos_types, )* #( #lst_types ),* };
+    let types = pos_types.iter().chain(lst_types).cloned().collect();
+    (type_bounds, type_args, types)
+}
+
+/// Generates the `Serialize` implementation of the `args = generic` argument struct. Fields are
+/// serialized under the parameter names, thus the arguments can be saved with the statement.
+fn add_serialize_args(params: &sql::StmtParams, options: &Options, code: &mut Vec<TokenStream>) {
+    let struct_name = &params.struct_name;
+    let (type_bounds, type_args, types) = generic_struct_generics(params, options);
+    let fields = &params.pos_params.iter().chain(unique_lst_params(&params.lst_params)).collect::<Vec<_>>();
+    let field_names = fields.iter().map(|name| name.unraw().to_string());
+    let num_fields = fields.len();
+    let type_name = struct_name.to_string();
+    code.push(quote! {
+        impl< #type_bounds > include_sql_helper::serde::Serialize for #struct_name< #type_args >
+        where #( #types : include_sql_helper::serde::Serialize ),*
+        {
+            fn serialize<S: include_sql_helper::serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                use include_sql_helper::serde::ser::SerializeStruct;
+                let mut state = serializer.serialize_struct(#type_name, #num_fields)?;
+                #( state.serialize_field(#field_names, &self.#fields)?; )*
+                state.end()
+            }
+        }
+    });
+}
+
+/// Returns the doc comments of the argument struct fields - the descriptions of their parameters,
+/// `-- :ship - id of the ship`.
+fn field_docs<'a>(params: &sql::StmtParams, fields: impl IntoIterator<Item = &'a Ident>) -> Vec<TokenStream> {
+    fields.into_iter().map(|field| {
+        let name = field.unraw().to_string();
+        let lines = params.docs.iter().filter(|(param, _)| *param == name).map(|(_, doc)| format!(" {}", doc));
+        quote! { #( #[doc = #lines] )* }
+    }).collect()
+}
+
+/// Returns the fields of the argument struct for the positional parameters. Grouped parameters
+/// are replaced by the field of their group.
+fn pos_struct_fields(params: &sql::StmtParams, docs: Vec<TokenStream>, types: impl IntoIterator<Item = TokenStream>) -> Vec<TokenStream> {
+    let mut fields = Vec::with_capacity(params.pos_params.len());
+    for (index, ((name, doc), field_type)) in params.pos_params.iter().zip(docs).zip(types).enumerate() {
+        match sql::ParamGroup::find(&params.groups, index) {
+            Some( (group, 0) ) => {
+                let sql::ParamGroup { name, struct_name, .. } = group;
+                fields.push(quote! { #name : #struct_name<'a> });
+            }
+            Some( _ ) => {}
+            None => fields.push(quote! { #doc #name : #field_type })
+        }
+    }
+    fields
+}
+
+/// Returns the initializers of the argument struct fields of the positional parameters with
+/// their values. Values of grouped parameters initialize the group struct.
+fn pos_field_inits(params: &sql::StmtParams, values: impl IntoIterator<Item = TokenStream>) -> Vec<TokenStream> {
+    let values : Vec<TokenStream> = values.into_iter().collect();
+    let mut inits = Vec::with_capacity(values.len());
+    for (index, name) in params.pos_params.iter().enumerate() {
+        match sql::ParamGroup::find(&params.groups, index) {
+            Some( (group, 0) ) => {
+                let sql::ParamGroup { name, struct_name, params: group_params, indexes } = group;
+                let group_values = indexes.iter().map(|&index| &values[index]);
+                inits.push(quote! { #name : #struct_name { #( #group_params : #group_values ),* } });
+            }
+            Some( _ ) => {}
+            None => {
+                let value = &values[index];
+                inits.push(quote! { #name : #value });
+            }
+        }
+    }
+    inits
+}
+
+/// Returns the field of the positional parameter in the argument struct - `self.rank`, or
+/// `self.context.tenant` when the parameter is grouped.
+fn pos_field(params: &sql::StmtParams, index: usize, base: TokenStream) -> TokenStream {
+    let param_name = &params.pos_params[index];
+    match sql::ParamGroup::find(&params.groups, index) {
+        Some( (group, _) ) => {
+            let group_name = &group.name;
+            quote! { #base.#group_name.#param_name }
+        }
+        None => quote! { #base.#param_name }
+    }
+}
+
+fn pos_field_type(null_type: &Option<syn::Type>) -> TokenStream {
+    match null_type {
+        Some( _ ) => quote! { Option<&'a dyn ToSql> },
+        None => quote! { &'a dyn ToSql }
+    }
+}
+
+/// Returns the argument of the positional parameter. Missing values of optional parameters are
+/// replaced with NULLs of the parameter type.
+fn pos_arg_value(field: TokenStream, null_type: &Option<syn::Type>) -> TokenStream {
+    match null_type {
+        Some( null_type ) => quote! { #field.unwrap_or(&None::<#null_type>) },
+        None => field
+    }
+}
+
+fn add_into_sql_with_args(params: &sql::StmtParams, stmt_name: &str, idempotent: bool, placeholder: &Placeholder, sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    if params.lst_params.iter().any(sql::LstParam::rejects_empty) {
+        // the trait conversion cannot fail
+        return;
+    }
+    let struct_name = &params.struct_name;
+    let kind_const = companion_const(sql_text_const, "_KIND");
+    let sql_with_args = if params.lst_params.is_empty() && params.pieces.is_empty() {
+        let check = bound_args_check(stmt_name, placeholder, quote! { #sql_text_const }, quote! { args.len() });
+        quote! {{
+            let args : Vec<&'a (dyn ToSql + 'a)> = std::iter::IntoIterator::into_iter(self).collect();
+            #check
+            (std::borrow::Cow::Borrowed(#sql_text_const), args)
+        }}
+    } else {
+        quote! {{
+            let (sql, args) = #struct_name::into_sql_with_args(self);
+            (std::borrow::Cow::Owned(sql), args)
+        }}
+    };
+    let (type_bounds, type_args) = if params.lst_params.is_empty() {
+        (quote! { 'a }, struct_lifetime(params))
+    } else {
+        lst_struct_generics(params)
+    };
+    code.push(quote! {
+        impl< #type_bounds > include_sql_helper::IntoSqlWithArgs<'a, dyn ToSql + 'a> for #struct_name< #type_args > {
+            const KIND : include_sql_helper::StatementKind = #kind_const;
+            const IDEMPOTENT : bool = #idempotent;
+
+            fn into_sql_with_args(self) -> (std::borrow::Cow<'static, str>, Vec<&'a (dyn ToSql + 'a)>) {
+                #sql_with_args
+            }
+        }
+    });
+}
+
+fn add_lst_params(params: &sql::StmtParams, stmt_name: &str, placeholder: &Placeholder, sql_text_const: &Ident, options: &Options, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, pos_args, lst_params, .. } = params;
+    let generic = options.args == ArgsMode::Generic;
+    let bounds = &arg_bounds(options);
+
+    struct ExtLstParam<'a> {
+        param: &'a sql::LstParam,
+        usage: ParamUsage
+    }
+
+    enum ParamUsage {
+        Unique,
+        HasDups,
+        IsADup,
+        Repeated
+    }
+
+    // Numbered placeholders allow binding of the list once and then
+    // reusing its placeholders. Unnumbered ones need the arguments
+    // to be bound for each occurrence of the list parameter.
+    let numbered = match placeholder.numbering {
+        Numbering::From(_) => true,
+        Numbering::None => false
+    };
+    let bind_once = numbered && placeholder.dedup;
+
+    let mut ext_lst_params = Vec::new();
+    let lst_fields = &unique_lst_params(lst_params);
+    for param in lst_params {
+        if let Some( idx ) = ext_lst_params.iter().position(|ext: &ExtLstParam| ext.param.name == param.name) {
+            let ext = &mut ext_lst_params[idx];
+            if let ParamUsage::Unique = ext.usage {
+                ext.usage = ParamUsage::HasDups;
+            }
+            let usage = if bind_once { ParamUsage::IsADup } else { ParamUsage::Repeated };
+            ext_lst_params.push(ExtLstParam { param, usage });
+        } else {
+            ext_lst_params.push(ExtLstParam { param, usage: ParamUsage::Unique });
+        }
+    }
+
+    let param_prefix = &placeholder.prefix;
+    let first_param_no = match placeholder.numbering {
+        Numbering::From(num) => quote! { Some(#num) },
+        Numbering::None => quote! { None }
+    };
+    let num_pos_args = pos_args.len();
+    let last_param_no = match placeholder.numbering {
+        Numbering::From(num) => quote! { Some(#num + #num_pos_args + num_lst_items) },
+        Numbering::None => quote! { None }
+    };
+    let list_len = |param: &sql::LstParam| {
+        let param_name = &param.name;
+        if generic {
+            quote! { self.#param_name.len() }
+        } else {
+            let param_items = ident!(&param_name.unraw().to_string(), "_items");
+            match param.tuple_len {
+                Some( len ) => quote! { #param_items.size_hint().0 * #len },
+                None => quote! { #param_items.size_hint().0 }
+            }
+        }
+    };
+    let push_items = |param: &sql::LstParam, param_items: &Ident| {
+        match param.tuple_len {
+            Some( len ) => quote! {
+                include_sql_helper::push_tuples::<_, _, #len>(#param_items, #param_prefix, #first_param_no, &mut sql, &mut args)
+            },
+            None => quote! {
+                include_sql_helper::push_iter(#param_items, #param_prefix, #first_param_no, &mut sql, &mut args)
+            }
+        }
+    };
+    let lst_len = lst_params.iter().map(list_len);
+    let chunk_separators_len = lst_params.iter().filter_map(|param| {
+        param.chunks.as_ref().map(|chunks| {
+            let len = list_len(param);
+            let limit = chunks.limit;
+            let separator_len = chunks.separator.len();
+            quote! { + #len / #limit * #separator_len }
+        })
+    });
+    // each tuple is also wrapped in parentheses
+    let tuple_parens_len = lst_params.iter().filter_map(|param| {
+        param.tuple_len.map(|len| {
+            let len_expr = list_len(param);
+            quote! { + #len_expr / #len * 2 }
+        })
+    });
+    let sql_capacity = quote! {
+        let num_lst_items = #( #lst_len )+*;
+        let sql_capacity = #sql_text_const.len() + include_sql_helper::list_text_len(num_lst_items, #param_prefix, #last_param_no) #( #chunk_separators_len )* #( #tuple_parens_len )*;
+    };
+
+    let push_pos_arg = |arg: &sql::PosArg| {
+        let param_name = &pos_params[arg.index];
+        if generic {
+            quote! { args.push(&self.#param_name); }
+        } else {
+            let arg_value = pos_arg_value(quote! { self.#param_name }, &null_types[arg.index]);
+            quote! { args.push(#arg_value); }
+        }
+    };
+
+    let mut push_lst_args_code = Vec::new();
+    if !generic {
+        // list arguments might be iterators - their lengths are known only from their size hints
+        for param_name in lst_fields {
+            let param_items = ident!(&param_name.unraw().to_string(), "_items");
+            let into_iter = match tuple_len(lst_params, param_name) {
+                Some( len ) => quote! { include_sql_helper::TupleListArg::<'a, dyn ToSql + 'a, #len>::into_tuple_iter(self.#param_name) },
+                None => quote! { include_sql_helper::ListArg::into_arg_iter(self.#param_name) }
+            };
+            push_lst_args_code.push(quote! {
+                let #param_items = #into_iter;
+            });
+        }
+    }
+    push_lst_args_code.push(sql_capacity);
+    push_lst_args_code.push(quote! {
+        let mut args : Vec<&(dyn #bounds)> = Vec::with_capacity(#num_pos_args + num_lst_items);
+        let mut sql = String::with_capacity(sql_capacity);
+    });
+    let mut pos_args = pos_args.iter().peekable();
+    // numbers of scalar placeholders are known up front, thus their arguments go before the lists
+    if numbered {
+        for arg in pos_args.by_ref() {
+            push_lst_args_code.push(push_pos_arg(arg));
+        }
+    }
+    let mut from = 0;
+    for ext in ext_lst_params {
+        let param_name = &ext.param.name;
+        let text_end = ext.param.position;
+        while let Some( arg ) = pos_args.peek() {
+            if arg.position > text_end {
+                break;
+            }
+            push_lst_args_code.push(push_pos_arg(arg));
+            pos_args.next();
+        }
+        push_lst_args_code.push(quote! {
+            sql.push_str(&#sql_text_const[#from..#text_end]);
+        });
+        let param_items = ident!(&param_name.unraw().to_string(), "_items");
+        let param_args = ident!(&param_name.unraw().to_string(), "_args");
+        // placeholders are pushed only for list items, thus nothing is pushed for an empty list
+        let empty_check = match &ext.param.if_empty {
+            Some( sql::IfEmpty::Text(text) ) => quote! {
+                if sql.len() == list_start {
+                    sql.push_str(#text);
+                }
+            },
+            Some( sql::IfEmpty::Error ) => {
+                let name = param_name.unraw().to_string();
+                quote! {
+                    if sql.len() == list_start {
+                        return Err(include_sql_helper::ArgsError::EmptyList(#name));
+                    }
+                }
+            }
+            None => quote! {}
+        };
+        if ext.param.if_empty.is_some() {
+            push_lst_args_code.push(quote! {
+                let list_start = sql.len();
+            });
+        }
+        if let Some( chunks ) = &ext.param.chunks {
+            let limit = chunks.limit;
+            let separator = &chunks.separator;
+            // the placeholders of the repeated list are not copied, as each occurrence has its own separators
+            let has_dups = matches!(ext.usage, ParamUsage::HasDups);
+            push_lst_args_code.push(match ext.usage {
+                ParamUsage::IsADup => quote! {
+                    include_sql_helper::push_placeholder_chunks(#param_args.len(), #limit, #separator, #param_prefix, #first_param_no, #param_args.start, &mut sql);
+                },
+                ParamUsage::Repeated if !generic => quote! {
+                    include_sql_helper::push_placeholder_chunks(#param_args.len(), #limit, #separator, #param_prefix, #first_param_no, args.len(), &mut sql);
+                    args.extend_from_within(#param_args.clone());
+                },
+                _ if generic => {
+                    let save_range = if has_dups {
+                        quote! { let #param_args = args.len() .. args.len() + self.#param_name.len(); }
+                    } else {
+                        quote! {}
+                    };
+                    quote! {
+                        #save_range
+                        include_sql_helper::push_placeholder_chunks(self.#param_name.len(), #limit, #separator, #param_prefix, #first_param_no, args.len(), &mut sql);
+                        args.extend(self.#param_name.iter().map(|arg| arg as &(dyn #bounds)));
+                    }
+                }
+                _ if has_dups => quote! {
+                    let #param_args = include_sql_helper::push_iter_in_chunks(#param_items, #limit, #separator, #param_prefix, #first_param_no, &mut sql, &mut args);
+                },
+                _ => quote! {
+                    include_sql_helper::push_iter_in_chunks(#param_items, #limit, #separator, #param_prefix, #first_param_no, &mut sql, &mut args);
+                }
+            });
+            push_lst_args_code.push(empty_check);
+            from = text_end;
+            continue;
+        }
+        let save_list_text = bind_once && matches!(ext.usage, ParamUsage::HasDups);
+        if save_list_text {
+            push_lst_args_code.push(quote! {
+                let start = sql.len();
+            });
+        }
+        match ext.usage {
+            ParamUsage::Unique | ParamUsage::HasDups | ParamUsage::Repeated if generic => {
+                push_lst_args_code.push(quote! {
+                    include_sql_helper::push_placeholders(self.#param_name.len(), #param_prefix, #first_param_no, args.len(), &mut sql);
+                    args.extend(self.#param_name.iter().map(|arg| arg as &(dyn #bounds)));
+                });
+            }
+            ParamUsage::HasDups if !bind_once => {
+                let push_items = push_items(ext.param, &param_items);
+                push_lst_args_code.push(quote! {
+                    let #param_args = #push_items;
+                });
+            }
+            ParamUsage::Unique | ParamUsage::HasDups => {
+                let push_items = push_items(ext.param, &param_items);
+                push_lst_args_code.push(quote! {
+                    #push_items;
+                });
+            }
+            ParamUsage::Repeated => {
+                let push_placeholders = match ext.param.tuple_len {
+                    Some( len ) => quote! {
+                        include_sql_helper::push_tuple_placeholders(#param_args.len() / #len, #len, #param_prefix, #first_param_no, args.len(), &mut sql);
+                    },
+                    None => quote! {
+                        include_sql_helper::push_placeholders(#param_args.len(), #param_prefix, #first_param_no, args.len(), &mut sql);
+                    }
+                };
+                push_lst_args_code.push(quote! {
+                    #push_placeholders
+                    args.extend_from_within(#param_args.clone());
+                });
+            }
+            ParamUsage::IsADup => {
+                let param_list = ident!(&param_name.unraw().to_string(), "_list");
+                push_lst_args_code.push(quote! {
+                    sql.push_str(&#param_list);
+                });
+            }
+        }
+        if save_list_text {
+            let param_list = ident!(&param_name.unraw().to_string(), "_list");
+            push_lst_args_code.push(quote! {
+                let #param_list = sql[start .. sql.len()].to_string();
+            });
+        }
+        push_lst_args_code.push(empty_check);
+        from = text_end;
+    }
+    for arg in pos_args {
+        push_lst_args_code.push(push_pos_arg(arg));
+    }
+    push_lst_args_code.push(quote! {
+        sql.push_str(&#sql_text_const[#from..]);
+    });
+
+    let pos_fields = &pos_params.iter().collect::<Vec<_>>();
+    let lst_types = &type_params(lst_fields);
+    let pos_docs = &field_docs(params, pos_params);
+    let lst_docs = &field_docs(params, lst_fields.iter().copied());
+    // `-- empty-in: error` makes the conversion fallible
+    let fallible = lst_params.iter().any(sql::LstParam::rejects_empty);
+    let wrap_result = |result: TokenStream| {
+        if fallible {
+            quote! { std::result::Result<#result, include_sql_helper::ArgsError> }
+        } else {
+            result
+        }
+    };
+    let (ok, try_op) = if fallible { (quote! { Ok }, quote! { ? }) } else { (quote! {}, quote! {}) };
+    let check = &bound_args_check(stmt_name, placeholder, quote! { &sql }, quote! { args.len() });
+    if generic {
+        let pos_types = &type_params(pos_fields);
+        let pos_type_bounds = &bounded_type_params(pos_types, bounds);
+        let lst_type_bounds = &bounded_type_params(lst_types, bounds);
+        let type_bounds = &quote! {
+            'a, #( #pos_type_bounds, )* #( #lst_type_bounds ),*
+        };
+        let type_args = &quote! {
+            'a, #( #pos_types, )* #( #lst_types ),*
+        };
+        code.push(quote! {
+            struct #struct_name< #type_bounds > {
+                #( #pos_docs #pos_fields : #pos_types, )*
+                #( #lst_docs #lst_fields : &'a [#lst_types] ),*
+            }
+        });
+        let result = wrap_result(quote! { (String, Vec<&(dyn #bounds)>) });
+        code.push(quote! {
+            impl< #type_bounds > #struct_name< #type_args > {
+                fn into_sql_with_args(&self) -> #result {
+                    #( #push_lst_args_code )*
+                    #check
+                    #ok((sql, args))
+                }
+            }
+        });
+        return;
+    }
+
+    let (type_bounds, type_args) = lst_struct_generics(params);
+    let field_types = null_types.iter().map(pos_field_type);
+    code.push(quote! {
+        struct #struct_name< #type_args > {
+            #( #pos_docs #pos_fields : #field_types, )*
+            #( #lst_docs #lst_fields : #lst_types ),*
+        }
+    });
+    let result = &wrap_result(quote! { (String, Vec<&'a dyn ToSql>) });
+    code.push(quote! {
+        impl< #type_bounds > #struct_name< #type_args > {
+            fn into_sql_with_args(self) -> #result {
+                #( #push_lst_args_code )*
+                #check
+                #ok((sql, args))
+            }
+
+            #[allow(dead_code)]
+            fn with_extra_args(self, extra: &[&'a dyn ToSql]) -> #result {
+                let (sql, mut args) = self.into_sql_with_args()#try_op;
+                args.extend_from_slice(extra);
+                #ok((sql, args))
+            }
+        }
+    });
+}
+
+/// Generates the argument struct of the statement with conditional blocks. Its text is assembled
+/// when the statement is executed - from the pieces of the blocks which arguments are provided.
+fn add_cond_params(params: &sql::StmtParams, stmt_name: &str, placeholder: &Placeholder, sql_text_const: &Ident, code: &mut Vec<TokenStream>) {
+    let sql::StmtParams { struct_name, pos_params, null_types, pieces, idents, .. } = params;
+    let cond_params : Vec<usize> = pieces.iter().filter_map(|piece| match piece {
+        sql::Piece::Block(index, _) => Some(*index),
+        _ => None
+    }).collect();
+    let field_types = pos_params.iter().enumerate().map(|(index, _)| {
+        if cond_params.contains(&index) {
+            quote! { Option<&'a dyn ToSql> }
+        } else {
+            pos_field_type(&null_types[index])
+        }
+    });
+    let ident_fields = &idents.iter().map(|ident| &ident.name).collect::<Vec<_>>();
+    let ident_enums = &idents.iter().map(|ident| ident!(&struct_name.to_string(), &sql::to_camel_case(&ident.name.unraw().to_string()))).collect::<Vec<_>>();
+    for (ident, enum_name) in idents.iter().zip(ident_enums) {
+        let variants = &ident.variants();
+        let arms = variants.iter().zip(&ident.values).map(|(variant, value)| quote! { #enum_name::#variant => #value });
+        code.push(quote! {
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #[allow(dead_code)]
+            enum #enum_name {
+                #( #variants ),*
+            }
+
+            impl #enum_name {
+                /// Returns the identifier that replaces the parameter.
+                #[allow(dead_code)]
+                fn as_str(self) -> &'static str {
+                    match self {
+                        #( #arms ),*
+                    }
+                }
+            }
+        });
+    }
+    let lifetime = struct_lifetime(params);
+    let pos_docs = field_docs(params, pos_params);
+    let ident_docs = field_docs(params, ident_fields.iter().copied());
+    code.push(quote! {
+        struct #struct_name< #lifetime > {
+            #( #pos_docs #pos_params : #field_types, )*
+            #( #ident_docs #ident_fields : #ident_enums ),*
+        }
+    });
+
+    let bind_once = matches!(placeholder.numbering, Numbering::From(_)) && placeholder.dedup;
+    let param_nos = &pos_params.iter().map(|name| ident!(&name.unraw().to_string(), "_no")).collect::<Vec<_>>();
+    let first_param_no = match placeholder.numbering {
+        Numbering::From(num) => quote! { Some(#num) },
+        Numbering::None => quote! { None }
+    };
+    let num_placeholders = count_placeholders(pieces);
+    let last_param_no = match placeholder.numbering {
+        Numbering::From(num) => quote! { Some(#num + #num_placeholders) },
+        Numbering::None => quote! { None }
+    };
+    let param_prefix = &placeholder.prefix;
+    let push_arg = |index: usize, value: TokenStream| {
+        if bind_once {
+            let param_no = &param_nos[index];
+            quote! {
+                #param_no = include_sql_helper::push_arg(#value, #param_no, #param_prefix, #first_param_no, &mut sql, &mut args);
+            }
+        } else {
+            quote! {
+                include_sql_helper::push_arg(#value, None, #param_prefix, #first_param_no, &mut sql, &mut args);
+            }
+        }
+    };
+    let push_ident = |index: usize| {
+        let ident_name = &idents[index].name;
+        quote! { sql.push_str(self.#ident_name.as_str()); }
+    };
+    let mut push_pieces_code = Vec::new();
+    for piece in pieces {
+        match piece {
+            sql::Piece::Text(text) => push_pieces_code.push(quote! { sql.push_str(#text); }),
+            sql::Piece::Ident(index) => push_pieces_code.push(push_ident(*index)),
+            sql::Piece::Param(index) => {
+                let param_name = &pos_params[*index];
+                push_pieces_code.push(push_arg(*index, pos_arg_value(quote! { self.#param_name }, &null_types[*index])));
+            }
+            sql::Piece::Block(cond_index, block_pieces) => {
+                let cond_name = &pos_params[*cond_index];
+                let cond_value = ident!(&cond_name.unraw().to_string(), "_value");
+                let block_code = block_pieces.iter().map(|piece| match piece {
+                    sql::Piece::Text(text) => quote! { sql.push_str(#text); },
+                    sql::Piece::Param(index) if index == cond_index => push_arg(*index, quote! { #cond_value }),
+                    sql::Piece::Param(index) => {
+                        let param_name = &pos_params[*index];
+                        push_arg(*index, pos_arg_value(quote! { self.#param_name }, &null_types[*index]))
+                    }
+                    sql::Piece::Ident(index) => push_ident(*index),
+                    // blocks cannot be nested
+                    sql::Piece::Block(..) => quote! {}
+                });
+                push_pieces_code.push(quote! {
+                    if let Some( #cond_value ) = self.#cond_name {
+                        #( #block_code )*
+                    }
+                });
+            }
+        }
+    }
+    let check = bound_args_check(stmt_name, placeholder, quote! { &sql }, quote! { args.len() });
+    let declare_param_nos = if bind_once {
+        quote! { #( let mut #param_nos : Option<usize> = None; )* }
+    } else {
+        quote! {}
+    };
+    code.push(quote! {
+        impl<'a> #struct_name< #lifetime > {
+            // placeholder numbers of the arguments bound last are not read
+            #[allow(unused_assignments)]
+            fn into_sql_with_args(self) -> (String, Vec<&'a dyn ToSql>) {
+                let mut sql = String::with_capacity(#sql_text_const.len() + include_sql_helper::list_text_len(#num_placeholders, #param_prefix, #last_param_no));
+                let mut args : Vec<&'a dyn ToSql> = Vec::with_capacity(#num_placeholders);
+                #declare_param_nos
+                #( #push_pieces_code )*
+                #check
+                (sql, args)
+            }
+
+            #[allow(dead_code)]
+            fn with_extra_args(self, extra: &[&'a dyn ToSql]) -> (String, Vec<&'a dyn ToSql>) {
+                let (sql, mut args) = self.into_sql_with_args();
+                args.extend_from_slice(extra);
+                (sql, args)
+            }
+        }
+    });
+}
+
+/// Generates the macro that builds diesel's `sql_query` of the statement and the struct of the
+/// rows it returns.
+fn add_diesel_query(stmt_name: &str, sql_text_const: &Ident, diesel: &sql::DieselTypes, params: Option<&sql::StmtParams>, options: &Options, code: &mut Vec<TokenStream>) {
+    let struct_name = options.struct_name(stmt_name);
+    let macro_name = ident!(&options.macro_prefix, stmt_name, "_sql_query");
+    match params {
+        Some( params ) => {
+            let args_struct = ident!(&struct_name, "SqlQueryArgs");
+            let fields = &params.pos_params.iter().collect::<Vec<_>>();
+            let field_types = &type_params(fields);
+            // arguments are bound in the order of their placeholders
+            let binds = params.pos_args.iter().map(|arg| {
+                let param_name = &params.pos_params[arg.index];
+                let sql_type = diesel.binds.iter()
+                    .find(|(name, _)| param_name.unraw() == name)
+                    .map(|(_, sql_type)| sql_type);
+                quote! { #param_name #sql_type }
+            });
+            code.push(quote! {
+                #[allow(dead_code)]
+                struct #args_struct< #( #field_types ),* > {
+                    #( #fields : #field_types ),*
+                }
+                include_sql_helper::def_sql_query!($ => #macro_name : #sql_text_const, #args_struct = #( #binds ),*);
+            });
+        }
+        None => {
+            code.push(quote! {
+                include_sql_helper::def_sql_query!($ => #macro_name : #sql_text_const);
+            });
+        }
+    }
+    if !diesel.row.is_empty() {
+        let row_struct = ident!(&struct_name, "Row");
+        let columns = diesel.row.iter().map(|(name, sql_type, field_type)| quote! {
+            #[diesel(sql_type = #sql_type)]
+            #name : #field_type
+        });
+        code.push(quote! {
+            #[derive(diesel::QueryableByName)]
+            #[allow(dead_code)]
+            struct #row_struct {
+                #( #columns ),*
+            }
+        });
+    }
+}
+
+fn add_row_struct(stmt_name: &str, row: &[(Ident, syn::Type)], options: &Options, code: &mut Vec<TokenStream>) {
+    let row_struct = ident!(&options.struct_name(stmt_name), "Row");
+    let fields = &row.iter().map(|(name, _)| name).collect::<Vec<_>>();
+    let field_types = row.iter().map(|(_, field_type)| field_type);
+    let indexes = &(0..row.len()).collect::<Vec<_>>();
+    code.push(quote! {
+        #[allow(dead_code)]
+        struct #row_struct {
+            #( #fields : #field_types ),*
+        }
+    });
+    if cfg!(feature = "rusqlite") {
+        code.push(quote! {
+            impl #row_struct {
+                #[allow(dead_code)]
+                fn from_rusqlite_row(row: &include_sql_helper::rusqlite::Row<'_>) -> include_sql_helper::rusqlite::Result<Self> {
+                    Ok(Self { #( #fields : row.get(#indexes)? ),* })
+                }
+            }
+        });
+    }
+    // postgres rows are the tokio-postgres ones
+    let postgres = if cfg!(feature = "postgres") {
+        Some(quote! { include_sql_helper::postgres })
+    } else if cfg!(feature = "tokio-postgres") {
+        Some(quote! { include_sql_helper::tokio_postgres })
+    } else {
+        None
+    };
+    if let Some( postgres ) = postgres {
+        code.push(quote! {
+            impl #row_struct {
+                #[allow(dead_code)]
+                fn from_postgres_row(row: &#postgres::Row) -> std::result::Result<Self, #postgres::Error> {
+                    Ok(Self { #( #fields : row.try_get(#indexes)? ),* })
+                }
+            }
+        });
+    }
+    if let Some( row_trait ) = &options.row_trait {
+        // the trait decides which column types it can read, thus it is only named here
+        let values = indexes.iter().map(|index| quote! { #row_trait::get_column(row, #index)? });
+        code.push(quote! {
+            impl #row_struct {
+                #[allow(dead_code)]
+                fn from_row<R: #row_trait + ?Sized>(row: &R) -> std::result::Result<Self, R::Error> {
+                    Ok(Self { #( #fields : #values ),* })
+                }
+            }
+        });
+    }
+}
+
+/// Returns the number of placeholders in the text with all conditional blocks included.
+fn count_placeholders(pieces: &[sql::Piece]) -> usize {
+    pieces.iter().map(|piece| match piece {
+        sql::Piece::Text(_) | sql::Piece::Ident(_) => 0,
+        sql::Piece::Param(_) => 1,
+        sql::Piece::Block(_, block_pieces) => count_placeholders(block_pieces)
+    }).sum()
+}
+
+/// Returns the lifetime of the argument struct without lists. Structs with only identifier
+/// parameters do not have one.
+fn struct_lifetime(params: &sql::StmtParams) -> TokenStream {
+    if params.pos_params.is_empty() { quote! {} } else { quote! { 'a } }
+}
+
+/// Returns list parameters without repetitions.
+fn unique_lst_params(lst_params: &[sql::LstParam]) -> Vec<&Ident> {
+    let mut names : Vec<&Ident> = Vec::with_capacity(lst_params.len());
+    for param in lst_params {
+        if !names.contains(&&param.name) {
+            names.push(&param.name);
+        }
+    }
+    names
+}
+
+/// Returns the length of tuples of the list parameter, or `None` if it is not a tuple list.
+fn tuple_len(lst_params: &[sql::LstParam], name: &Ident) -> Option<usize> {
+    lst_params.iter().find(|param| param.name == *name).and_then(|param| param.tuple_len)
+}
+
+/// Returns generic parameters - with and without bounds - of the argument struct with lists.
+///
+/// List fields have their own types, so lists can be passed as slices, vectors or iterators.
+/// The struct has a lifetime only if it has scalar fields.
+fn lst_struct_generics(params: &sql::StmtParams) -> (TokenStream, TokenStream) {
+    let lst_fields = &unique_lst_params(&params.lst_params);
+    let lst_types = &type_params(lst_fields);
+    let lst_bounds = lst_fields.iter().map(|name| match tuple_len(&params.lst_params, name) {
+        Some( len ) => quote! { include_sql_helper::TupleListArg<'a, dyn ToSql + 'a, #len> },
+        None => quote! { include_sql_helper::ListArg<'a, dyn ToSql + 'a> }
+    });
+    let lifetime = if params.pos_params.is_empty() { quote! {} } else { quote! { 'a, } };
+    let type_bounds = quote! {
+        'a, #( #lst_types : #lst_bounds ),*
+    };
+    let type_args = quote! {
+        #lifetime #( #lst_types ),*
+    };
+    (type_bounds, type_args)
+}
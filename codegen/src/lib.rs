@@ -0,0 +1,18 @@
+//! `include-sql-codegen` parses SQL files and generates the items of their statements. It is the
+//! code behind the [include-sql](https://crates.io/crates/include-sql) macros and the
+//! [include-sql-build](https://crates.io/crates/include-sql-build) build script functions, which
+//! thus generate the same items. Applications use either of those rather than this crate.
+
+// the `root = "file"` option asks the macro API for the file with the macro call
+extern crate proc_macro;
+
+mod expand;
+mod sql;
+mod dialect;
+mod validate;
+mod verify;
+mod schema;
+mod usage;
+mod manifest;
+
+pub use expand::{IncludeSql, InlineSql, include_sql, inline_sql, include_sql_mod, source_text, module_name};
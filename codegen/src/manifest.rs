@@ -0,0 +1,87 @@
+//! Machine-readable manifest of the included statements.
+//!
+//! With the `manifest = "target/sql/crew.json"` option the macro writes a JSON file that lists
+//! each statement - its name, location, kind, parameters, placeholder style, fingerprint and
+//! text - for the tools that review the SQL an application runs without reading Rust.
+
+use std::fs;
+use std::path::Path;
+use crate::dialect::{Placeholder, Numbering};
+use crate::sql::{Stmt, StmtKind};
+use crate::expand::{fnv1a, param_names};
+
+/// Statements of the included file and the placeholders they were processed with. The
+/// statements of the dialect variants also name the dialect.
+pub(crate) struct Statements<'a> {
+    pub(crate) dialect: Option<&'a str>,
+    pub(crate) statements: &'a [Stmt],
+    pub(crate) placeholder: &'a Placeholder
+}
+
+/// Writes the manifest of the statements of the SQL file.
+pub(crate) fn write(manifest_path: &str, path: &str, variants: &[Statements]) -> Result<(), String> {
+    let mut entries = Vec::new();
+    for variant in variants {
+        for stmt in variant.statements {
+            entries.push(statement_entry(path, variant.dialect, stmt, variant.placeholder));
+        }
+    }
+    let json = format!("{{\n  \"file\": {},\n  \"statements\": [\n    {}\n  ]\n}}\n", json_string(path), entries.join(",\n    "));
+    let manifest_path = Path::new(manifest_path);
+    if let Some( dir ) = manifest_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| format!("cannot create directory {}: {}", dir.display(), err))?;
+    }
+    fs::write(manifest_path, json).map_err(|err| format!("cannot write {}: {}", manifest_path.display(), err))
+}
+
+fn statement_entry(path: &str, dialect: Option<&str>, stmt: &Stmt, placeholder: &Placeholder) -> String {
+    let mut fields = vec![("name", json_string(&stmt.name))];
+    if let Some( dialect ) = dialect {
+        fields.push(("dialect", json_string(dialect)));
+    }
+    let kind = match stmt.kind {
+        StmtKind::Query    => "query",
+        StmtKind::FetchOne => "fetch_one",
+        StmtKind::Execute  => "execute"
+    };
+    let params : Vec<String> = stmt.params.as_ref().map(param_names).unwrap_or_default().iter().map(|name| json_string(name)).collect();
+    fields.push(("file", json_string(stmt.file.as_deref().unwrap_or(path))));
+    fields.push(("line", stmt.lines.first().copied().unwrap_or_default().to_string()));
+    fields.push(("kind", json_string(kind)));
+    fields.push(("params", format!("[{}]", params.join(", "))));
+    fields.push(("placeholder", json_string(&placeholder_style(placeholder))));
+    // JSON numbers lose the precision of 64-bit ones in most parsers
+    fields.push(("fingerprint", json_string(&format!("{:016x}", fnv1a(&crate::sql::normalized_text(&stmt.text))))));
+    fields.push(("sql", json_string(&stmt.text)));
+    let fields : Vec<String> = fields.into_iter().map(|(name, value)| format!("\"{}\": {}", name, value)).collect();
+    format!("{{ {} }}", fields.join(", "))
+}
+
+/// Returns the first placeholder of a statement - `$1`, `?` or `:name`.
+fn placeholder_style(placeholder: &Placeholder) -> String {
+    if placeholder.named {
+        return format!("{}name", placeholder.prefix);
+    }
+    match placeholder.numbering {
+        Numbering::From(first) => format!("{}{}", placeholder.prefix, first),
+        Numbering::None => placeholder.prefix.clone()
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"'  => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c)
+        }
+    }
+    json.push('"');
+    json
+}
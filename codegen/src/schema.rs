@@ -0,0 +1,262 @@
+//! Validation of the included statements against a schema snapshot.
+//!
+//! The snapshot is a JSON file that lists tables with their columns and, for each included
+//! SQL file, the number of parameters of each statement:
+//!
+//! ```json
+//! {
+//!   "tables": {
+//!     "sailors": ["id", "ship_id", "name", "rank"]
+//!   },
+//!   "statements": {
+//!     "src/crew.sql": {
+//!       "select_ship_crew": 1
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Statements are checked against the snapshot when `include-sql` is built with the
+//! `schema-snapshot` feature and the `include_sql!` has the `schema = "path"` option.
+
+use crate::sql::Stmt;
+use crate::dialect::Dialect;
+
+#[cfg(feature = "schema-snapshot")]
+pub(crate) use snapshot::check_statements;
+
+#[cfg(feature = "schema-snapshot")]
+pub(crate) mod snapshot {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    use std::ops::ControlFlow;
+    use serde_json::{Map, Value};
+    use sqlparser::ast::{Expr, Ident, ObjectName, Query, SetExpr, SelectItem, Statement, TableFactor, Visit, Visitor};
+    use sqlparser::dialect::{self as sp, GenericDialect};
+    use sqlparser::parser::Parser;
+    use super::{Stmt, Dialect};
+
+    /// Tables and statement parameter counts captured from a database.
+    #[derive(Default)]
+    pub(crate) struct Schema {
+        pub(crate) tables: BTreeMap<String, Vec<String>>,
+        pub(crate) statements: BTreeMap<String, BTreeMap<String, usize>>
+    }
+
+    impl Schema {
+        pub(crate) fn load(path: &str) -> Result<Self, String> {
+            let text = std::fs::read_to_string(path).map_err(|err| format!("cannot read schema snapshot {}: {}", path, err))?;
+            let json : Value = serde_json::from_str(&text).map_err(|err| format!("cannot parse schema snapshot {}: {}", path, err))?;
+            let mut schema = Schema::default();
+            if let Some( tables ) = json.get("tables").and_then(Value::as_object) {
+                for (table, columns) in tables {
+                    let columns = columns.as_array().map(|columns| {
+                        columns.iter().filter_map(Value::as_str).map(str::to_lowercase).collect()
+                    }).unwrap_or_default();
+                    schema.tables.insert(table.to_lowercase(), columns);
+                }
+            }
+            if let Some( files ) = json.get("statements").and_then(Value::as_object) {
+                for (file, statements) in files {
+                    let counts = statements.as_object().map(|statements| {
+                        statements.iter().filter_map(|(name, count)| {
+                            count.as_u64().map(|count| (name.to_string(), count as usize))
+                        }).collect()
+                    }).unwrap_or_default();
+                    schema.statements.insert(file.to_string(), counts);
+                }
+            }
+            Ok(schema)
+        }
+
+        #[cfg_attr(not(feature = "verify-postgres"), allow(dead_code))]
+        pub(crate) fn save(&self, path: &str) -> Result<(), String> {
+            let mut tables = Map::new();
+            for (table, columns) in &self.tables {
+                tables.insert(table.to_string(), Value::from(columns.clone()));
+            }
+            let mut files = Map::new();
+            for (file, counts) in &self.statements {
+                let counts = counts.iter().map(|(name, count)| (name.to_string(), Value::from(*count))).collect();
+                files.insert(file.to_string(), Value::Object(counts));
+            }
+            let mut json = Map::new();
+            json.insert("tables".to_string(), Value::Object(tables));
+            json.insert("statements".to_string(), Value::Object(files));
+            let text = serde_json::to_string_pretty(&Value::Object(json)).map_err(|err| err.to_string())?;
+            std::fs::write(path, text + "\n").map_err(|err| format!("cannot write schema snapshot {}: {}", path, err))
+        }
+    }
+
+    /// Checks tables, columns and parameter counts of the statements against the snapshot.
+    pub(crate) fn check_statements(schema_path: &str, path: &str, statements: &[Stmt], dialect: Option<&Dialect>) -> Result<(), String> {
+        let schema = Schema::load(schema_path)?;
+        let (sql_dialect, param_prefix, numbered) : (Box<dyn sp::Dialect>, &str, bool) = match dialect {
+            Some(Dialect::Postgres) => (Box::new(sp::PostgreSqlDialect {}), "$", true),
+            Some(Dialect::Sqlite)   => (Box::new(sp::SQLiteDialect {}), "?", false),
+            Some(Dialect::MySql)    => (Box::new(sp::MySqlDialect {}), "?", false),
+            Some(Dialect::MsSql)    => (Box::new(sp::MsSqlDialect {}), "@p", true),
+            _ => (Box::new(GenericDialect {}), "?", false),
+        };
+        let counts = schema.statements.get(path);
+        // scripts and migrations usually create the schema, thus their tables are not in the snapshot yet
+        for stmt in statements.iter().filter(|stmt| stmt.script.is_none() && stmt.migration.is_none()) {
+            let line = stmt.lines.first().cloned().unwrap_or_default();
+            if let Some( expected ) = counts.and_then(|counts| counts.get(&stmt.name)) {
+                let num_params = num_params(stmt);
+                if num_params != *expected {
+                    return Err(format!("{}:{}: `{}` has {} parameter(s), but the schema snapshot expects {}", path, line, stmt.name, num_params, expected));
+                }
+            }
+            let text = crate::sql::replace_params(&stmt.source, param_prefix, numbered);
+            let ast = Parser::parse_sql(&*sql_dialect, &text).map_err(|err| format!("{}:{}: `{}` cannot be parsed: {}", path, line, stmt.name, err))?;
+            for sql_stmt in &ast {
+                check_statement(&schema, sql_stmt).map_err(|msg| format!("{}:{}: `{}` {}", path, line, stmt.name, msg))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn num_params(stmt: &Stmt) -> usize {
+        match &stmt.params {
+            Some( params ) => {
+                let mut names : Vec<_> = params.lst_params.iter().map(|param| &param.name).collect();
+                names.sort();
+                names.dedup();
+                params.pos_params.len() + names.len()
+            }
+            None => 0
+        }
+    }
+
+    /// Names referenced by a statement.
+    #[derive(Default)]
+    struct References {
+        tables: Vec<String>,
+        aliases: HashMap<String, String>,
+        derived: HashSet<String>,
+        columns: Vec<Vec<String>>,
+        insert_columns: Vec<(String, String)>,
+        output_names: HashSet<String>
+    }
+
+    impl Visitor for References {
+        type Break = ();
+
+        fn pre_visit_statement(&mut self, stmt: &Statement) -> ControlFlow<()> {
+            if let Statement::Insert(insert) = stmt {
+                let table = insert.table.to_string();
+                let table = last_name(&table);
+                for column in &insert.columns {
+                    self.insert_columns.push((table.clone(), name(column)));
+                }
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+            if let Some( with ) = &query.with {
+                for cte in &with.cte_tables {
+                    self.derived.insert(name(&cte.alias.name));
+                }
+            }
+            if let SetExpr::Select(select) = query.body.as_ref() {
+                for item in &select.projection {
+                    if let SelectItem::ExprWithAlias { alias, .. } = item {
+                        self.output_names.insert(name(alias));
+                    }
+                }
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<()> {
+            self.tables.push(last_name(&relation.to_string()));
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_table_factor(&mut self, table_factor: &TableFactor) -> ControlFlow<()> {
+            match table_factor {
+                TableFactor::Table { name: table, alias: Some(alias), .. } => {
+                    self.aliases.insert(name(&alias.name), last_name(&table.to_string()));
+                }
+                TableFactor::Derived { alias: Some(alias), .. } => {
+                    self.derived.insert(name(&alias.name));
+                }
+                _ => {}
+            }
+            ControlFlow::Continue(())
+        }
+
+        fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+            match expr {
+                Expr::Identifier(ident) => self.columns.push(vec![name(ident)]),
+                Expr::CompoundIdentifier(idents) => self.columns.push(idents.iter().map(name).collect()),
+                _ => {}
+            }
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn check_statement(schema: &Schema, stmt: &Statement) -> Result<(), String> {
+        match stmt {
+            Statement::Query(_) | Statement::Insert(_) | Statement::Update { .. } | Statement::Delete(_) => {}
+            _ => return Ok(())
+        }
+        let mut refs = References::default();
+        let _ = stmt.visit(&mut refs);
+
+        let mut columns = HashSet::new();
+        for table in &refs.tables {
+            if refs.derived.contains(table) {
+                continue;
+            }
+            match schema.tables.get(table) {
+                Some( table_columns ) => columns.extend(table_columns.iter()),
+                None => return Err(format!("references unknown table `{}`", table))
+            }
+        }
+        for (table, column) in &refs.insert_columns {
+            if let Some( table_columns ) = schema.tables.get(table) {
+                if !table_columns.contains(column) {
+                    return Err(format!("references unknown column `{}.{}`", table, column));
+                }
+            }
+        }
+        // unqualified names cannot be resolved when some of the sources are not tables
+        let all_tables = refs.derived.is_empty();
+        for column in &refs.columns {
+            match column.as_slice() {
+                [name] if all_tables && !columns.contains(name) && !refs.output_names.contains(name) => {
+                    return Err(format!("references unknown column `{}`", name));
+                }
+                [.., qualifier, name] => {
+                    let table = refs.aliases.get(qualifier).unwrap_or(qualifier);
+                    if let Some( table_columns ) = schema.tables.get(table) {
+                        if !table_columns.contains(name) {
+                            return Err(format!("references unknown column `{}.{}`", qualifier, name));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the name as it is stored in the snapshot - unquoted names are case insensitive.
+    fn name(ident: &Ident) -> String {
+        if ident.quote_style.is_some() { ident.value.clone() } else { ident.value.to_lowercase() }
+    }
+
+    /// Returns the table name without the schema qualifier.
+    fn last_name(object_name: &str) -> String {
+        let table = object_name.rsplit('.').next().unwrap_or(object_name);
+        if table.starts_with('"') { table.trim_matches('"').to_string() } else { table.to_lowercase() }
+    }
+}
+
+/// Schema snapshot validation is disabled. Statements cannot be checked.
+#[cfg(not(feature = "schema-snapshot"))]
+pub(crate) fn check_statements(_schema_path: &str, _path: &str, _statements: &[Stmt], _dialect: Option<&Dialect>) -> Result<(), String> {
+    Err("`schema` option requires the `schema-snapshot` feature".to_string())
+}
@@ -0,0 +1,2107 @@
+use std::borrow::Cow;
+use std::ops::Range;
+use std::fs::File;
+use std::path::Path;
+use std::io::{self, BufRead, BufReader};
+use lazy_static::lazy_static;
+use regex::Regex;
+use syn::{Ident, Type};
+use syn::ext::IdentExt;
+use proc_macro2::Span;
+use include_sql_helper::verbatim_ranges;
+use crate::dialect::{Placeholder, Numbering, Dialect};
+
+pub(crate) struct Stmt {
+    pub(crate) name: String,
+    pub(crate) kind: StmtKind,
+    pub(crate) const_name: Ident,
+    pub(crate) text: String,
+    pub(crate) params: Option<StmtParams>,
+    /// Text of the statement as it is written in the SQL file
+    #[cfg_attr(not(any(feature = "validate-sql", feature = "verify-postgres")), allow(dead_code))]
+    pub(crate) source: String,
+    /// Numbers of the SQL file lines from which the statement text was collected
+    pub(crate) lines: Vec<usize>,
+    /// How long, in milliseconds, results of the statement can be cached - `-- cache: 30s`
+    pub(crate) cache_ttl: Option<u64>,
+    /// Whether the statement can be safely retried - `-- idempotent`
+    pub(crate) idempotent: bool,
+    /// Companion items requested by `-- generate: explain`
+    pub(crate) generate: Vec<Generate>,
+    /// Time limit, in milliseconds, of a probe statement - `-- healthcheck: 500ms`. Probes
+    /// without a limit have it set to 0.
+    pub(crate) healthcheck: Option<u64>,
+    /// Types for diesel's `sql_query` - `-- diesel-bind:` and `-- diesel-row:`
+    pub(crate) diesel: Option<DieselTypes>,
+    /// Names and Rust types of the columns of the row struct - `-- returns: id i32, name String`
+    pub(crate) row: Vec<(Ident, Type)>,
+    /// Number of columns in the rows the statement returns, when the projection tells it
+    pub(crate) columns: Option<usize>,
+    /// Names of the projection columns, as constant name suffixes, where the projection item is a
+    /// column or has an alias. Names that several columns share are `None`.
+    pub(crate) column_names: Vec<Option<String>>,
+    /// Statements of the script - `-- name: init_schema (script)` - in the order of execution
+    pub(crate) script: Option<Vec<String>>,
+    /// Version and down SQL of a migration - `-- migration: 003 add_sailors_rank`. The statement
+    /// text is its up SQL.
+    pub(crate) migration: Option<Migration>,
+    /// Parameters that the comments of the statement mention, but its text does not use, and the
+    /// lines of the first mentions
+    pub(crate) missing_params: Vec<(String, usize)>,
+    /// Query tags of the statement - `-- tag: service=crew`
+    pub(crate) tags: Vec<String>,
+    /// Dialect variant of the SQL file - `src/crew.postgres.sql` - when the statement comes from
+    /// it rather than from the included file
+    pub(crate) file: Option<String>,
+    /// Predicates of the `cfg` attribute of the generated items - `-- cfg: feature = "admin"`
+    pub(crate) cfg: Vec<syn::NestedMeta>,
+    /// Attributes of the generated items - `-- attr: allow(dead_code)`
+    pub(crate) attrs: Vec<syn::Meta>
+}
+
+/// Schema migration. Its name and up SQL are those of the statement.
+pub(crate) struct Migration {
+    pub(crate) version: u32,
+    /// SQL after the `-- down` line
+    pub(crate) down: Option<String>
+}
+
+/// SQL types of the parameters and columns of the statement executed via diesel's `sql_query`.
+#[derive(Default)]
+pub(crate) struct DieselTypes {
+    /// Parameter names and their SQL types - `-- diesel-bind: ship Integer`
+    pub(crate) binds: Vec<(String, Type)>,
+    /// Column names, their SQL and Rust types - `-- diesel-row: name Text String`
+    pub(crate) row: Vec<(Ident, Type, Type)>
+}
+
+/// Companion item of the statement that `-- generate:` requests.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Generate {
+    /// `<STATEMENT>_EXPLAIN` constant with the text prefixed by EXPLAIN
+    Explain,
+    /// `<STATEMENT>_EXPLAIN_ANALYZE` constant with the text prefixed by EXPLAIN ANALYZE
+    ExplainAnalyze
+}
+
+impl Generate {
+    pub(crate) const NAMES : &'static str = "explain, explain_analyze";
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "explain"         => Some( Generate::Explain ),
+            "explain_analyze" => Some( Generate::ExplainAnalyze ),
+            _ => None
+        }
+    }
+}
+
+/// Kind of the statement as it is tagged by its name suffix.
+#[derive(Clone, Copy)]
+pub(crate) enum StmtKind {
+    Query,
+    FetchOne,
+    Execute
+}
+
+impl StmtKind {
+    fn from_suffix(suffix: Option<&str>) -> Self {
+        match suffix {
+            Some("<!") => StmtKind::FetchOne,
+            Some("!")  => StmtKind::Execute,
+            _ => StmtKind::Query
+        }
+    }
+}
+
+pub(crate) struct StmtParams {
+    pub(crate) struct_name: Ident,
+    pub(crate) pos_params: Vec<Ident>,
+    /// Types of NULL values of optional positional parameters - `-- param: rank? &str`
+    pub(crate) null_types: Vec<Option<Type>>,
+    pub(crate) pos_args: Vec<PosArg>,
+    pub(crate) lst_params: Vec<LstParam>,
+    /// Text of the statement with conditional blocks - `-- if :rank` - or identifier parameters
+    /// split into pieces that are assembled when the statement is executed. Empty for statements
+    /// without either.
+    pub(crate) pieces: Vec<Piece>,
+    /// Identifier parameters - `:#sort_column`
+    pub(crate) idents: Vec<IdentParam>,
+    /// Parameter names and descriptions of their fields - `-- :ship - id of the ship`
+    pub(crate) docs: Vec<(String, String)>,
+    /// Parameter groups - `-- group: context = tenant, user` - whose fields the argument struct
+    /// embeds in one field
+    pub(crate) groups: Vec<ParamGroup>
+}
+
+/// Parameters that statements of the file share and that their argument structs get together
+/// in one field of the group struct - `-- group: context = tenant, user`.
+#[derive(Clone)]
+pub(crate) struct ParamGroup {
+    /// Name of the group, which is the name of the field
+    pub(crate) name: Ident,
+    pub(crate) struct_name: Ident,
+    /// Names of the grouped parameters
+    pub(crate) params: Vec<Ident>,
+    /// Indexes of the grouped parameters in `pos_params` of the statement
+    pub(crate) indexes: Vec<usize>
+}
+
+impl ParamGroup {
+    /// Returns the group and the position in it of the positional parameter.
+    pub(crate) fn find(groups: &[ParamGroup], index: usize) -> Option<(&ParamGroup, usize)> {
+        groups.iter().find_map(|group| group.indexes.iter().position(|&grouped| grouped == index).map(|pos| (group, pos)))
+    }
+}
+
+/// Identifier parameter - `:#sort_column` - that is replaced by one of the identifiers its
+/// allowlist - `-- allow sort_column: name, rank` - declares.
+pub(crate) struct IdentParam {
+    pub(crate) name: Ident,
+    pub(crate) values: Vec<String>
+}
+
+/// Piece of the statement text with conditional blocks.
+pub(crate) enum Piece {
+    Text(String),
+    /// Placeholder of the positional parameter - its index in `pos_params`
+    Param(usize),
+    /// Pieces that are included when the argument of the parameter, which index in `pos_params`
+    /// is the first element, is provided
+    Block(usize, Vec<Piece>),
+    /// Identifier that replaces the identifier parameter - its index in `idents`
+    Ident(usize)
+}
+
+/// Positional argument in the order it is bound to the statement.
+pub(crate) struct PosArg {
+    /// Index of the parameter in `pos_params`
+    pub(crate) index: usize,
+    /// Position in the SQL text right after the placeholder
+    pub(crate) position: usize
+}
+
+/// Meta-comments that describe the statement.
+#[derive(Default)]
+struct StmtAnnotations {
+    cache_ttl: Option<u64>,
+    idempotent: bool,
+    params: Vec<ParamAnnotation>,
+    /// Explicit order of positional parameters - `-- param-order: ship, rank`
+    param_order: Vec<String>,
+    generate: Vec<Generate>,
+    healthcheck: Option<u64>,
+    blocks: Vec<BlockLines>,
+    diesel: Option<DieselTypes>,
+    /// Columns of the row struct - `-- returns: id i32, name String`
+    row: Vec<(Ident, Type)>,
+    /// Allowlists of identifier parameters - `-- allow sort_column: name, rank`
+    idents: Vec<(String, Vec<String>)>,
+    /// Maximum number of items in an IN list - `-- in-chunks: 1000`
+    in_chunks: Option<usize>,
+    /// Whether the query is sorted by the column that the arguments choose - `-- order_by: name, rank`
+    order_by: bool,
+    /// How the rows of the query are paginated - `-- paginate`
+    paginate: Option<Paginate>,
+    /// What an empty IN list turns into - `-- empty-in: false`
+    empty_in: Option<EmptyIn>,
+    /// Whether the statement is a script of several statements - `-- name: init_schema (script)`
+    script: bool,
+    /// Version of the migration - `-- migration: 003 add_sailors_rank`
+    migration: Option<u32>,
+    /// Where the down SQL of the migration starts in the text and in the lines - `-- down`
+    down: Option<(usize, usize)>,
+    /// Whether the text is minified, which the `minify` option of the include sets
+    minify: bool,
+    /// Parameters that comments mention - `-- Selects sailors of :ship` - and the comment lines
+    mentions: Vec<(String, usize)>,
+    /// Descriptions of the parameters - `-- :ship - id of the ship`
+    docs: Vec<(String, String)>,
+    /// Query tags that are appended to the text as a comment - `-- tag: service=crew`
+    tags: Vec<String>,
+    /// Predicates of the `cfg` attribute of the generated items - `-- cfg: feature = "admin"`
+    cfg: Vec<syn::NestedMeta>,
+    /// Attributes of the generated items - `-- attr: allow(dead_code)`
+    attrs: Vec<syn::Meta>
+}
+
+/// Policy for IN lists that have no items - `IN ()` is invalid SQL for most databases.
+#[derive(Clone, Copy, PartialEq)]
+enum EmptyIn {
+    /// `IN (NULL)`, which matches nothing
+    Null,
+    /// The IN condition becomes false and the NOT IN one becomes true
+    False,
+    /// `into_sql_with_args` returns an error
+    Error
+}
+
+/// How `-- paginate` limits the rows of the query to a page.
+#[derive(Clone, Copy)]
+enum Paginate {
+    /// The clause of the dialect - `LIMIT :limit OFFSET :offset` or `OFFSET :offset ROWS FETCH
+    /// NEXT :limit ROWS ONLY` - is appended to the query
+    Clause,
+    /// The query is wrapped into the selection of the ROWNUM range, which Oracle versions
+    /// before 12c need - `-- paginate: rownum`
+    Rownum
+}
+
+/// Conditional block - `-- if :rank` ... `-- end` - as a range of statement text lines.
+struct BlockLines {
+    param: String,
+    start: usize,
+    end: usize
+}
+
+/// Parameter description - `-- param: rank? &str`
+struct ParamAnnotation {
+    name: String,
+    /// Type of the NULL value of an optional parameter
+    null_type: Option<Type>
+}
+
+pub(crate) struct LstParam {
+    pub(crate) name: Ident,
+    pub(crate) position: usize,
+    /// Number of elements in each item of the tuple list - `(ship_id, rank) IN (:pairs)`
+    pub(crate) tuple_len: Option<usize>,
+    /// How the list is split when it is longer than the database allows - `-- in-chunks: 1000`
+    pub(crate) chunks: Option<ListChunks>,
+    /// What replaces placeholders of the list without items - `-- empty-in: null`
+    pub(crate) if_empty: Option<IfEmpty>
+}
+
+impl LstParam {
+    /// Whether `into_sql_with_args` returns an error when the list is empty.
+    pub(crate) fn rejects_empty(&self) -> bool {
+        matches!(self.if_empty, Some(IfEmpty::Error))
+    }
+}
+
+/// Replacement of the empty IN list.
+pub(crate) enum IfEmpty {
+    /// Text that takes the place of the list placeholders
+    Text(String),
+    /// The list is rejected
+    Error
+}
+
+/// Chunks of the IN list. Chunks after the first one repeat the IN condition - `) OR col IN (`.
+pub(crate) struct ListChunks {
+    /// Maximum number of items in a chunk
+    pub(crate) limit: usize,
+    /// Text between chunks
+    pub(crate) separator: String
+}
+
+/// What happens to the comments in the statement text.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub(crate) enum Comments {
+    /// Comments that follow SQL on the same line are kept, whole-line comments are dropped
+    #[default]
+    Inline,
+    /// Whole-line comments inside the statement text are kept too
+    Keep,
+    /// All comments are removed
+    Strip
+}
+
+pub(crate) fn parse_sql_file(path: &str, placeholder: &Placeholder, dialect: Option<&Dialect>, vars: &[(String, String)], comments: Comments, minify: bool) -> Result<Vec<Stmt>, Vec<io::Error>> {
+    let file = File::open(path).map_err(|err| vec![err])?;
+    let reader = BufReader::new(file);
+    let file_name = Path::new(&path)
+        .file_stem().unwrap_or_default()
+        .to_str().unwrap_or_default();
+    parse_sql(file_name, reader, placeholder, dialect, vars, comments, minify)
+}
+
+/// Parses statements of the dialect variant of the SQL file - `src/crew.postgres.sql` - which
+/// override the statements of the included file that have the same names.
+pub(crate) fn parse_sql_overrides(path: &str, placeholder: &Placeholder, dialect: Option<&Dialect>, vars: &[(String, String)], comments: Comments, minify: bool) -> Result<Vec<Stmt>, Vec<io::Error>> {
+    let text = std::fs::read_to_string(path).map_err(|err| vec![err])?;
+    // variables that the variant does not use are used by the statements it does not override
+    let used_vars : Vec<(String, String)> = vars.iter().filter(|(name, _)| TEMPLATE_VAR.captures_iter(&text).chain(ENV_VAR.captures_iter(&text)).any(|caps| caps[1] == *name)).cloned().collect();
+    let file_name = Path::new(&path)
+        .file_stem().unwrap_or_default()
+        .to_str().unwrap_or_default();
+    let mut statements = parse_sql(file_name, text.as_bytes(), placeholder, dialect, &used_vars, comments, minify)?;
+    for stmt in &mut statements {
+        stmt.file = Some(path.to_string());
+    }
+    Ok(statements)
+}
+
+/// Replaces the statements with the statements of the dialect variant that have the same names.
+pub(crate) fn override_statements(statements: &mut [Stmt], overrides: Vec<Stmt>) -> Result<(), Vec<io::Error>> {
+    let mut errors = Vec::new();
+    for stmt in overrides {
+        match statements.iter_mut().find(|base| base.name.eq_ignore_ascii_case(&stmt.name)) {
+            Some( base ) => *base = stmt,
+            None => {
+                let line_num = stmt.lines.first().copied().unwrap_or_default();
+                let msg = format!("line {}: statement `{}` does not override any statement of the included file", line_num, stmt.name);
+                errors.push(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+        }
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+/// Parses statements of the SQL text embedded into the source code. As there is no file name
+/// to fall back to, each statement must be named.
+pub(crate) fn parse_sql_str(text: &str, placeholder: &Placeholder, dialect: Option<&Dialect>, vars: &[(String, String)], comments: Comments, minify: bool) -> Result<Vec<Stmt>, Vec<io::Error>> {
+    // the text is usually indented to match the code around it
+    let text = text.lines().map(str::trim_start).collect::<Vec<_>>().join("\n");
+    parse_sql("", text.as_bytes(), placeholder, dialect, vars, comments, minify)
+}
+
+/// Parses statements of the SQL text. Errors do not stop the parsing, thus all of them are
+/// returned, except the ones that reading the text fails with.
+fn parse_sql(file_name: &str, mut reader: impl BufRead, placeholder: &Placeholder, dialect: Option<&Dialect>, vars: &[(String, String)], comments: Comments, minify: bool) -> Result<Vec<Stmt>, Vec<io::Error>> {
+    let mut errors = Vec::new();
+    let mut all_stmt = Vec::new();
+    let mut sql_name = String::with_capacity(50);
+    // line of the `-- name:` of the statement
+    let mut name_line = 0;
+    let mut sql_kind = StmtKind::Query;
+    let mut sql_text = String::with_capacity(500);
+    let mut sql_lines = Vec::new();
+    let mut sql_annotations = StmtAnnotations::default();
+    let mut static_only = false;
+    // constants of the file - `-- define: table_prefix = app_` - follow the `vars` of the macro,
+    // which take precedence over them
+    let declared_vars = vars.len();
+    let mut vars = vars.to_vec();
+    let mut defines : Vec<String> = Vec::new();
+    // parameter groups - `-- group: context = tenant, user` - and the lines they are declared at
+    let mut groups : Vec<(String, Vec<String>, usize)> = Vec::new();
+    let mut vars_used = vec![false; vars.len()];
+    let mut fragments : Vec<Fragment> = Vec::new();
+    let mut fragment_name : Option<String> = None;
+    // parameter of the conditional block that is not closed yet and the line where it starts
+    let mut open_block : Option<(String, usize)> = None;
+    let mut block_start = 0;
+    // whole-line comments that are kept if the statement text continues after them
+    let mut kept_comments : Vec<(String, usize)> = Vec::new();
+    // quoted text or block comment that the previous line left open
+    let mut open_span = OpenSpan::None;
+
+    let mut buffer = String::with_capacity(100);
+    let mut line_num = 0;
+    loop {
+        let num_read = reader.read_line(&mut buffer).map_err(|err| vec![err])?;
+        if num_read == 0 {
+            break;
+        }
+        line_num += 1;
+        // files saved on Windows might start with a byte order mark and end lines with CR LF,
+        // which `trim_end` removes together with the trailing whitespace
+        let line = if line_num == 1 { buffer.trim_start_matches('\u{feff}') } else { &buffer };
+        let line = line.trim_end();
+        // a line that fails is skipped, so the errors of the following lines are reported too
+        let res = (|| -> io::Result<()> {
+            if !line.is_empty() {
+                if line.starts_with("--") {
+                    let stmt_caps = STMT_NAME.captures(line);
+                    let migration_caps = if stmt_caps.is_none() { MIGRATION.captures(line) } else { None };
+                    let fragment_caps = if stmt_caps.is_none() && migration_caps.is_none() { FRAGMENT.captures(line) } else { None };
+                    if stmt_caps.is_some() || migration_caps.is_some() || fragment_caps.is_some() {
+                        if let Err( err ) = check_block_closed(open_block.take()) {
+                            errors.push(err);
+                        }
+                        if let Some( name ) = fragment_name.take() {
+                            fragments.push(Fragment { name, text: std::mem::take(&mut sql_text), lines: std::mem::take(&mut sql_lines) });
+                        } else if !sql_text.is_empty() {
+                            let name = if sql_name.is_empty() { file_name } else { &sql_name };
+                            let annotations = StmtAnnotations { minify, ..std::mem::take(&mut sql_annotations) };
+                            match Stmt::new(name, sql_kind, &sql_text, &sql_lines, annotations, placeholder, dialect) {
+                                Ok( stmt ) => all_stmt.push(stmt),
+                                Err( err ) => errors.push(stmt_error(err, &sql_lines))
+                            }
+                            sql_text.clear();
+                            sql_lines.clear();
+                        } else if !sql_name.is_empty() {
+                            errors.push(empty_stmt_error(&sql_name, name_line));
+                            sql_annotations = StmtAnnotations::default();
+                        }
+                        sql_name.clear();
+                        kept_comments.clear();
+                        open_span = OpenSpan::None;
+                    }
+                    if let Some( caps ) = stmt_caps {
+                        sql_name.push_str(&caps[1]);
+                        name_line = line_num;
+                        sql_kind = StmtKind::from_suffix(caps.get(2).map(|suffix| suffix.as_str()));
+                        if caps.get(3).is_some() {
+                            // scripts are executed statement by statement
+                            sql_kind = StmtKind::Execute;
+                            sql_annotations.script = true;
+                        }
+                    } else if let Some( caps ) = migration_caps {
+                        sql_name.push_str(&caps[2]);
+                        name_line = line_num;
+                        sql_kind = StmtKind::Execute;
+                        let version = caps[1].parse::<u32>().map_err(|_| {
+                            let msg = format!("line {}: invalid migration version `{}`, expected a number", line_num, &caps[1]);
+                            io::Error::new(io::ErrorKind::InvalidData, msg)
+                        })?;
+                        sql_annotations.migration = Some(version);
+                    } else if DOWN.is_match(line) {
+                        if sql_annotations.migration.is_none() || fragment_name.is_some() {
+                            let msg = format!("line {}: `-- down` outside of a migration", line_num);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        if sql_annotations.down.is_some() {
+                            let msg = format!("line {}: the migration has `-- down` twice", line_num);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        sql_annotations.down = Some((sql_text.len(), sql_lines.len()));
+                    } else if let Some( caps ) = fragment_caps {
+                        let name = &caps[1];
+                        if fragments.iter().any(|fragment| fragment.name == name) {
+                            let msg = format!("line {}: fragment `{}` is defined twice", line_num, name);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        fragment_name = Some(name.to_string());
+                    } else if let Some( caps ) = USE_FRAGMENT.captures(line) {
+                        let fragment = find_fragment(&fragments, &caps[1]).map_err(|msg| {
+                            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                        })?;
+                        if !sql_text.is_empty() {
+                            sql_text.push('\n');
+                        }
+                        sql_text.push_str(&fragment.text);
+                        sql_lines.extend_from_slice(&fragment.lines);
+                    } else if let Some( caps ) = IF_BLOCK.captures(line) {
+                        if fragment_name.is_some() {
+                            let msg = format!("line {}: fragments cannot have conditional blocks", line_num);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        if open_block.is_some() {
+                            let msg = format!("line {}: conditional blocks cannot be nested", line_num);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        open_block = Some((caps[1].to_string(), line_num));
+                        block_start = sql_lines.len();
+                    } else if END_BLOCK.is_match(line) {
+                        match open_block.take() {
+                            Some( (param, _) ) => sql_annotations.blocks.push(BlockLines { param, start: block_start, end: sql_lines.len() }),
+                            None => {
+                                let msg = format!("line {}: `-- end` without `-- if`", line_num);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                        }
+                    } else if let Some( caps ) = DEFINE.captures(line) {
+                        if !all_stmt.is_empty() || !sql_text.is_empty() || !sql_name.is_empty() || !fragments.is_empty() || fragment_name.is_some() {
+                            let msg = format!("line {}: constants must be defined before the first statement", line_num);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        let name = &caps[1];
+                        if defines.iter().any(|defined| defined == name) {
+                            let msg = format!("line {}: constant `{}` is defined twice", line_num, name);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        defines.push(name.to_string());
+                        if !vars.iter().any(|(var_name, _)| var_name == name) {
+                            vars.push((name.to_string(), caps[2].trim().to_string()));
+                            vars_used.push(false);
+                        }
+                    } else if let Some( caps ) = GROUP.captures(line) {
+                        if !all_stmt.is_empty() || !sql_text.is_empty() || !sql_name.is_empty() || !fragments.is_empty() || fragment_name.is_some() {
+                            let msg = format!("line {}: parameter groups must be declared before the first statement", line_num);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        let name = &caps[1];
+                        if syn::parse_str::<Ident>(name).is_err() {
+                            let msg = format!("line {}: parameter group `{}` cannot name a field", line_num, name);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        if groups.iter().any(|(group, _, _)| group == name) {
+                            let msg = format!("line {}: parameter group `{}` is declared twice", line_num, name);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        let mut params : Vec<String> = Vec::new();
+                        for param in caps[2].split(',').map(str::trim) {
+                            if param.is_empty() || !param.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                                let msg = format!("line {}: invalid parameter `{}` of group `{}`, expected a list of parameter names", line_num, param, name);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                            if let Some( (group, _, _) ) = groups.iter().find(|(_, grouped, _)| grouped.iter().any(|grouped| grouped == param)) {
+                                let msg = format!("line {}: parameter `{}` is already in group `{}`", line_num, param, group);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                            if !params.iter().any(|grouped| grouped == param) {
+                                params.push(param.to_string());
+                            }
+                        }
+                        groups.push((name.to_string(), params, line_num));
+                    } else if let Some( caps ) = STRICT.captures(line) {
+                        if !all_stmt.is_empty() || !sql_text.is_empty() || !sql_name.is_empty() || !fragments.is_empty() || fragment_name.is_some() {
+                            let msg = format!("line {}: strict mode must be set before the first statement", line_num);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        match &caps[1] {
+                            "static-only" => static_only = true,
+                            mode => {
+                                let msg = format!("line {}: unknown strict mode `{}`, expected static-only", line_num, mode);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                        }
+                    } else if let Some( caps ) = CACHE_TTL.captures(line) {
+                        match parse_duration(&caps[1]) {
+                            Some( ttl ) => sql_annotations.cache_ttl = Some(ttl),
+                            None => {
+                                let msg = format!("line {}: invalid cache duration `{}`, expected a number followed by ms, s, m or h", line_num, &caps[1]);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                        }
+                    } else if let Some( caps ) = HEALTHCHECK.captures(line) {
+                        let time_limit = match caps.get(1) {
+                            Some( limit ) => parse_duration(limit.as_str()).ok_or_else(|| {
+                                let msg = format!("line {}: invalid health check time limit `{}`, expected a number followed by ms, s, m or h", line_num, limit.as_str());
+                                io::Error::new(io::ErrorKind::InvalidData, msg)
+                            })?,
+                            None => 0
+                        };
+                        sql_annotations.healthcheck = Some(time_limit);
+                    } else if let Some( caps ) = IN_CHUNKS.captures(line) {
+                        match caps[1].parse::<usize>() {
+                            Ok( limit ) if limit > 0 => sql_annotations.in_chunks = Some(limit),
+                            _ => {
+                                let msg = format!("line {}: invalid IN list chunk size `{}`, expected a positive number", line_num, &caps[1]);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                        }
+                    } else if let Some( caps ) = EMPTY_IN.captures(line) {
+                        sql_annotations.empty_in = match &caps[1] {
+                            "null"  => Some(EmptyIn::Null),
+                            "false" => Some(EmptyIn::False),
+                            "error" => Some(EmptyIn::Error),
+                            policy => {
+                                let msg = format!("line {}: unknown empty IN list policy `{}`, expected one of: null, false, error", line_num, policy);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                        };
+                    } else if let Some( caps ) = CFG.captures(line) {
+                        let predicate = syn::parse_str(caps[1].trim()).map_err(|err| {
+                            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: invalid cfg predicate `{}`: {}", line_num, caps[1].trim(), err))
+                        })?;
+                        sql_annotations.cfg.push(predicate);
+                    } else if let Some( caps ) = ATTR.captures(line) {
+                        let attr = syn::parse_str(caps[1].trim()).map_err(|err| {
+                            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: invalid attribute `{}`: {}", line_num, caps[1].trim(), err))
+                        })?;
+                        sql_annotations.attrs.push(attr);
+                    } else if IDEMPOTENT.is_match(line) {
+                        sql_annotations.idempotent = true;
+                    } else if let Some( caps ) = PAGINATE.captures(line) {
+                        sql_annotations.paginate = match caps.get(1).map(|style| style.as_str()) {
+                            None => Some(Paginate::Clause),
+                            Some("rownum") => Some(Paginate::Rownum),
+                            Some(style) => {
+                                let msg = format!("line {}: unknown pagination `{}`, expected rownum or none", line_num, style);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                        };
+                    } else if let Some( caps ) = TAG.captures(line) {
+                        let tags = parse_tags(&caps[1]).map_err(|msg| {
+                            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                        })?;
+                        sql_annotations.tags.extend(tags);
+                    } else if let Some( caps ) = PARAM_ORDER.captures(line) {
+                        let names = caps[1].split(',').map(str::trim);
+                        for name in names {
+                            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                                let msg = format!("line {}: invalid parameter name `{}` in param-order", line_num, name);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                            if sql_annotations.param_order.iter().any(|ordered| ordered == name) {
+                                let msg = format!("line {}: parameter `{}` is listed twice in param-order", line_num, name);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                            sql_annotations.param_order.push(name.to_string());
+                        }
+                    } else if let Some( caps ) = GENERATE.captures(line) {
+                        for name in caps[1].split(',').map(str::trim) {
+                            match Generate::from_name(name) {
+                                Some( item ) => if !sql_annotations.generate.contains(&item) {
+                                    sql_annotations.generate.push(item);
+                                }
+                                None => {
+                                    let msg = format!("line {}: unknown generated item `{}`, expected one of: {}", line_num, name, Generate::NAMES);
+                                    return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                                }
+                            }
+                        }
+                    } else if let Some( caps ) = DIESEL_BIND.captures(line) {
+                        let diesel = sql_annotations.diesel.get_or_insert_with(DieselTypes::default);
+                        for entry in caps[1].split(',').map(str::trim) {
+                            let bind = parse_diesel_bind(entry).map_err(|msg| {
+                                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                            })?;
+                            diesel.binds.push(bind);
+                        }
+                    } else if let Some( caps ) = DIESEL_ROW.captures(line) {
+                        let diesel = sql_annotations.diesel.get_or_insert_with(DieselTypes::default);
+                        for entry in caps[1].split(',').map(str::trim) {
+                            let column = parse_diesel_column(entry).map_err(|msg| {
+                                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                            })?;
+                            diesel.row.push(column);
+                        }
+                    } else if let Some( caps ) = RETURNS.captures(line) {
+                        for entry in split_type_list(&caps[1]) {
+                            let column = parse_row_column(entry).map_err(|msg| {
+                                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                            })?;
+                            if sql_annotations.row.iter().any(|(name, _)| *name == column.0) {
+                                let msg = format!("line {}: column `{}` is returned twice", line_num, column.0);
+                                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                            }
+                            sql_annotations.row.push(column);
+                        }
+                    } else if let Some( caps ) = ORDER_BY.captures(line) {
+                        // the sort column and direction are identifier parameters with implied allowlists
+                        if let Some( (name, _) ) = sql_annotations.idents.iter().find(|(name, _)| name == "order_by" || name == "order_dir") {
+                            let msg = format!("line {}: allowlist of `{}` is declared twice", line_num, name);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        let columns = parse_allowlist("order_by", &caps[1]).map_err(|msg| {
+                            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                        })?;
+                        sql_annotations.idents.push(("order_by".to_string(), columns));
+                        sql_annotations.idents.push(("order_dir".to_string(), vec!["asc".to_string(), "desc".to_string()]));
+                        sql_annotations.order_by = true;
+                    } else if let Some( caps ) = ALLOW_IDENT.captures(line) {
+                        let name = &caps[1];
+                        if sql_annotations.idents.iter().any(|(ident_name, _)| ident_name == name) {
+                            let msg = format!("line {}: allowlist of `{}` is declared twice", line_num, name);
+                            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                        }
+                        let values = parse_allowlist(name, &caps[2]).map_err(|msg| {
+                            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                        })?;
+                        sql_annotations.idents.push((name.to_string(), values));
+                    } else if let Some( caps ) = PARAM_DOC.captures(line) {
+                        let param = ParamAnnotation::new(&caps[1], caps.get(2).is_some(), caps[3].trim()).map_err(|msg| {
+                            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                        })?;
+                        sql_annotations.mentions.push((param.name.clone(), line_num));
+                        sql_annotations.params.push(param);
+                    } else if let Some( caps ) = FIELD_DOC.captures(line) {
+                        sql_annotations.mentions.push((caps[1].to_string(), line_num));
+                        sql_annotations.docs.push((caps[1].to_string(), caps[2].to_string()));
+                    } else {
+                        if fragment_name.is_none() {
+                            for caps in PARAM_MENTION.captures_iter(line) {
+                                sql_annotations.mentions.push((caps[1].to_string(), line_num));
+                            }
+                        }
+                        if comments == Comments::Keep && !sql_text.is_empty() {
+                            kept_comments.push((line.to_string(), line_num));
+                        }
+                    }
+                } else {
+                    let line = if minify || comments == Comments::Strip {
+                        Cow::Owned(strip_comments(line, &mut open_span, minify))
+                    } else {
+                        Cow::Borrowed(line)
+                    };
+                    if line.is_empty() {
+                        // the line has nothing but a comment
+                        return Ok(());
+                    }
+                    for (comment, comment_line_num) in kept_comments.drain(..) {
+                        sql_text.push('\n');
+                        sql_text.push_str(&comment);
+                        sql_lines.push(comment_line_num);
+                    }
+                    let line = interpolate_template_vars(&line, &vars, &mut vars_used).and_then(|line| {
+                        interpolate_env_vars(line, &vars, &mut vars_used)
+                    }).and_then(|line| {
+                        expand_fragment_refs(line, &fragments)
+                    }).map_err(|msg| {
+                        io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+                    })?;
+                    if !sql_text.is_empty() {
+                        sql_text.push('\n');
+                    }
+                    sql_text.push_str(&line);
+                    // fragments might span several lines
+                    for _ in 0 ..= line.matches('\n').count() {
+                        sql_lines.push(line_num);
+                    }
+                }
+            }
+            Ok(())
+        })();
+        if let Err( err ) = res {
+            errors.push(err);
+        }
+        buffer.clear();
+    }
+    if let Err( err ) = check_block_closed(open_block) {
+        errors.push(err);
+    }
+    if fragment_name.is_none() && !sql_text.is_empty() {
+        let name = if sql_name.is_empty() { file_name } else { &sql_name };
+        let annotations = StmtAnnotations { minify, ..sql_annotations };
+        match Stmt::new(name, sql_kind, &sql_text, &sql_lines, annotations, placeholder, dialect) {
+            Ok( stmt ) => all_stmt.push(stmt),
+            Err( err ) => errors.push(stmt_error(err, &sql_lines))
+        }
+    } else if fragment_name.is_none() && !sql_name.is_empty() {
+        errors.push(empty_stmt_error(&sql_name, name_line));
+    }
+    for (idx, ((name, _), _)) in vars.iter().zip(&vars_used).enumerate().filter(|(_, (_, &used))| !used) {
+        let what = if idx < declared_vars { "template variable" } else { "constant" };
+        let msg = format!("{} `{}` is not used by any statement", what, name);
+        errors.push(io::Error::new(io::ErrorKind::InvalidData, msg));
+    }
+    for (idx, stmt) in all_stmt.iter().enumerate() {
+        // constants are named after statements in upper case
+        if let Some( prev ) = all_stmt[..idx].iter().find(|prev| prev.name.eq_ignore_ascii_case(&stmt.name)) {
+            let line_num = stmt.lines.first().copied().unwrap_or_default();
+            let prev_line_num = prev.lines.first().copied().unwrap_or_default();
+            let msg = format!("line {}: statement `{}` is already defined at line {}", line_num, stmt.name, prev_line_num);
+            errors.push(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        if let Some( migration ) = &stmt.migration {
+            let dup = all_stmt[..idx].iter().find(|prev| prev.migration.as_ref().is_some_and(|prev| prev.version == migration.version));
+            if let Some( prev ) = dup {
+                let msg = format!("`{}`: migration version {} is already used by `{}`", stmt.name, migration.version, prev.name);
+                errors.push(stmt_error(io::Error::new(io::ErrorKind::InvalidData, msg), &stmt.lines));
+            }
+        }
+    }
+    for (name, params, line_num) in &groups {
+        if !add_group(&mut all_stmt, name, params) {
+            let msg = format!("line {}: parameter group `{}` is not used by any statement", line_num, name);
+            errors.push(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+    }
+    if static_only {
+        for stmt in &all_stmt {
+            if let Err( msg ) = check_static_only(stmt) {
+                errors.push(stmt_error(io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt.name, msg)), &stmt.lines));
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(all_stmt)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Adds the parameter group to the statements that have all of its parameters as required
+/// positional ones. Statements with lists, conditional blocks or identifier parameters keep
+/// separate fields. Returns whether any statement got the group.
+fn add_group(statements: &mut [Stmt], name: &str, params: &[String]) -> bool {
+    let mut used = false;
+    for stmt_params in statements.iter_mut().filter_map(|stmt| stmt.params.as_mut()) {
+        if !stmt_params.lst_params.is_empty() || !stmt_params.pieces.is_empty() || !stmt_params.idents.is_empty() {
+            continue;
+        }
+        let indexes : Option<Vec<usize>> = params.iter().map(|param| {
+            stmt_params.pos_params.iter().position(|pos_param| pos_param.unraw() == param).filter(|&index| stmt_params.null_types[index].is_none())
+        }).collect();
+        if let Some( indexes ) = indexes {
+            let params = indexes.iter().map(|&index| stmt_params.pos_params[index].clone()).collect();
+            let (name, struct_name) = (Ident::new(name, Span::call_site()), Ident::new(&to_camel_case(name), Span::call_site()));
+            stmt_params.groups.push(ParamGroup { name, struct_name, params, indexes });
+            used = true;
+        }
+    }
+    used
+}
+
+/// Points the error of the statement to the line where the statement starts, unless the error
+/// already points to a line of its own.
+fn stmt_error(err: io::Error, stmt_lines: &[usize]) -> io::Error {
+    let msg = err.to_string();
+    if msg.starts_with("line ") {
+        return err;
+    }
+    let line_num = stmt_lines.first().copied().unwrap_or_default();
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line_num, msg))
+}
+
+fn empty_stmt_error(name: &str, line_num: usize) -> io::Error {
+    let msg = format!("line {}: statement `{}` has no SQL", line_num, name);
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn check_block_closed(open_block: Option<(String, usize)>) -> io::Result<()> {
+    match open_block {
+        Some( (param, line_num) ) => {
+            let msg = format!("line {}: conditional block of `{}` is not closed - `-- end`", line_num, param);
+            Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+        }
+        None => Ok(())
+    }
+}
+
+/// Checks that the statement of a `-- strict: static-only` file is prepared from its constant
+/// text as is, i.e. nothing in the text is built at run time.
+fn check_static_only(stmt: &Stmt) -> Result<(), String> {
+    if let Some( lst_param ) = stmt.params.as_ref().and_then(|params| params.lst_params.first()) {
+        return Err(format!("IN list parameter `{}` makes the statement text dynamic, which static-only files do not allow", lst_param.name.unraw()));
+    }
+    if let Some( ident ) = stmt.params.as_ref().and_then(|params| params.idents.first()) {
+        return Err(format!("identifier parameter `{}` makes the statement text dynamic, which static-only files do not allow", ident.name));
+    }
+    if stmt.params.as_ref().is_some_and(|params| !params.pieces.is_empty()) {
+        return Err("conditional blocks make the statement text dynamic, which static-only files do not allow".to_string());
+    }
+    Ok(())
+}
+
+/// Parses duration like `500ms`, `30s`, `5m` or `1h` into milliseconds.
+fn parse_duration(text: &str) -> Option<u64> {
+    let unit_start = text.find(|c: char| !c.is_ascii_digit())?;
+    let (num, unit) = text.split_at(unit_start);
+    let num : u64 = num.parse().ok()?;
+    let scale = match unit {
+        "ms" => 1,
+        "s"  => 1000,
+        "m"  => 60 * 1000,
+        "h"  => 60 * 60 * 1000,
+        _ => return None
+    };
+    num.checked_mul(scale)
+}
+
+macro_rules! ident {
+    ($s:expr) => {
+        Ident::new($s, Span::call_site())
+    };
+}
+
+impl ParamAnnotation {
+    fn new(name: &str, optional: bool, param_type: &str) -> Result<Self, String> {
+        let null_type = if optional {
+            if param_type.is_empty() {
+                return Err(format!("optional parameter `{}` needs a type for its NULL value - `-- param: {}? &str`", name, name));
+            }
+            let null_type = syn::parse_str(param_type).map_err(|err| format!("invalid type `{}` of `{}`: {}", param_type, name, err))?;
+            Some(null_type)
+        } else {
+            None
+        };
+        Ok(ParamAnnotation { name: name.to_string(), null_type })
+    }
+}
+
+impl Stmt {
+    /// Appends the query tag comment - `/* service=crew,stmt=select_ship_crew */` - to the text,
+    /// so server-side logs tell where the statement comes from. `include_tags` are the tags of all
+    /// statements in the file. Scripts and migrations are not tagged.
+    pub(crate) fn add_tags(&mut self, include_tags: &[String]) {
+        if self.script.is_some() || self.migration.is_some() || include_tags.is_empty() && self.tags.is_empty() {
+            return;
+        }
+        let tags : Vec<&str> = include_tags.iter().chain(&self.tags).map(String::as_str).collect();
+        let comment = format!("/* {},stmt={} */", tags.join(","), self.name);
+        append_sql(&mut self.text, &comment);
+        if let Some( params ) = self.params.as_mut().filter(|params| !params.pieces.is_empty()) {
+            match params.pieces.last_mut() {
+                Some( Piece::Text(text) ) => append_sql(text, &comment),
+                _ => params.pieces.push(Piece::Text(format!(" {}", comment)))
+            }
+        }
+    }
+
+    /// Requests the EXPLAIN variant of the statement when it is a query or modifies data.
+    /// Statements with IN lists, conditional blocks or identifier parameters, whose text is only
+    /// known at run time, as well as scripts and migrations get none.
+    pub(crate) fn add_generate(&mut self, item: Generate) {
+        if self.script.is_some() || self.migration.is_some() || self.generate.contains(&item) {
+            return;
+        }
+        if self.params.as_ref().is_some_and(|params| !params.lst_params.is_empty() || !params.pieces.is_empty()) {
+            return;
+        }
+        const EXPLAINABLE : [&str; 7] = ["select", "with", "values", "insert", "update", "delete", "merge"];
+        let text = normalized_text(&self.text);
+        let first_word = text.trim_start_matches('(').split(|c: char| !c.is_ascii_alphabetic()).next().unwrap_or_default();
+        if EXPLAINABLE.iter().any(|keyword| first_word.eq_ignore_ascii_case(keyword)) {
+            self.generate.push(item);
+        }
+    }
+
+    /// Removes the semicolon that terminates the statement. Scripts and migrations, which might
+    /// be several statements, and PL/SQL blocks, which end with `END;`, keep it.
+    pub(crate) fn strip_semicolon(&mut self) {
+        if self.script.is_some() || self.migration.is_some() || !sql_captures(&BLOCK_BEGIN, &self.text).is_empty() {
+            return;
+        }
+        if let Some( pos ) = terminating_semicolon(&self.text) {
+            self.text.remove(pos);
+        }
+        if let Some( Piece::Text(text) ) = self.params.as_mut().and_then(|params| params.pieces.last_mut()) {
+            if let Some( pos ) = terminating_semicolon(text) {
+                text.remove(pos);
+            }
+        }
+    }
+
+    fn new(stmt_name: &str, kind: StmtKind, stmt_text: &str, stmt_lines: &[usize], annotations: StmtAnnotations, placeholder: &Placeholder, dialect: Option<&Dialect>) -> io::Result<Self> {
+        if stmt_name.is_empty() {
+            // only the inline SQL has no file name to use instead
+            let line_num = stmt_lines.first().copied().unwrap_or_default();
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("line {}: the statement needs a name - `-- name: ...`", line_num)));
+        }
+        let name = ident!(&stmt_name.to_uppercase());
+        // the down SQL of a migration is kept as it is - only the up SQL is the statement
+        let (stmt_text, stmt_lines, down) = match annotations.down {
+            Some( (text_pos, lines_pos) ) => {
+                let down = interpolate_build_vars(stmt_text[text_pos..].trim()).map_err(|msg| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt_name, msg))
+                })?;
+                if down.is_empty() {
+                    let msg = format!("`{}`: the migration has no SQL after `-- down`", stmt_name);
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+                }
+                let down = if annotations.minify { join_lines(&down) } else { down };
+                (stmt_text[..text_pos].trim_end(), &stmt_lines[..lines_pos], Some(down))
+            }
+            None => (stmt_text, stmt_lines, None)
+        };
+        if annotations.migration.is_some() && stmt_text.trim().is_empty() {
+            let msg = format!("`{}`: the migration has no SQL before `-- down`", stmt_name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        let stmt_text = interpolate_build_vars(stmt_text).map_err(|msg| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt_name, msg))
+        })?;
+        let stmt_text = stmt_text.as_str();
+        let StmtAnnotations { cache_ttl, idempotent, params: param_annotations, param_order, generate, healthcheck, blocks, diesel, row, idents, in_chunks, order_by, paginate, empty_in, script, migration, down: _, minify, mentions, docs, tags, cfg, attrs } = annotations;
+        if (order_by || paginate.is_some()) && (!matches!(kind, StmtKind::Query) || script || migration.is_some()) {
+            let what = if order_by { "sorted" } else { "paginated" };
+            let msg = format!("`{}`: only queries can be {}", stmt_name, what);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        let mut stmt_text = stmt_text.to_string();
+        if order_by {
+            append_sql(&mut stmt_text, "ORDER BY :#order_by :#order_dir");
+        }
+        if let Some( paginate ) = paginate {
+            // the page is cut from the sorted rows
+            stmt_text = paginate_text(&stmt_text, paginate, dialect);
+        }
+        let stmt_text = stmt_text.as_str();
+        let idents = ident_params(stmt_text, idents).map_err(|msg| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt_name, msg))
+        })?;
+        // the constant text has the first allowed identifier of each identifier parameter
+        let source = replace_ident_params(stmt_text, &idents);
+        // chunks and the false replacement of an empty list repeat or extend the IN condition
+        let wrap_conditions = in_chunks.is_some() || empty_in == Some(EmptyIn::False);
+        let conditions = if wrap_conditions { list_conditions(&source) } else { Vec::new() };
+        let source = if wrap_conditions {
+            // the condition is wrapped to keep its precedence after it is extended with OR or AND
+            Cow::Owned(CHUNKED_IN.replace_all(&source, "($0)").into_owned())
+        } else {
+            source
+        };
+        let (text, pos_params, pos_args, mut lst_params) = if dialect.is_some_and(|dialect| dialect.is_unprepared(&source)) {
+            // the database executes these statements as they are, thus there is nothing to bind
+            (source.to_string(), Vec::new(), Vec::new(), Vec::new())
+        } else {
+            parse_sql_text(&source, placeholder, &param_order)
+        };
+        // placeholders are where they are in the text, thus lines are joined after they are found
+        let text = if minify { join_lines(&text) } else { text };
+        set_tuple_lens(&source, &mut lst_params).map_err(|msg| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt_name, msg))
+        })?;
+        if let Some( name ) = pos_params.iter().chain(lst_params.iter().map(|param| &param.name)).find(|name| NON_RAW_KEYWORDS.contains(&name.to_string().as_str())) {
+            let msg = format!("`{}`: parameter `{}` is a keyword that cannot be a field name", stmt_name, name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        if let Some( limit ) = in_chunks {
+            if lst_params.is_empty() {
+                let msg = format!("`{}`: in-chunks needs an IN list parameter to split", stmt_name);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            if conditions.len() != lst_params.len() {
+                let msg = format!("`{}`: in-chunks needs a column before each IN list - `col IN (:list)` - to repeat the condition for each chunk", stmt_name);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            for (lst_param, (column, negated)) in lst_params.iter_mut().zip(&conditions) {
+                let separator = if *negated { format!(") AND {} NOT IN (", column) } else { format!(") OR {} IN (", column) };
+                lst_param.chunks = Some(ListChunks { limit, separator });
+            }
+        }
+        if let Some( policy ) = empty_in {
+            if lst_params.is_empty() {
+                let msg = format!("`{}`: empty-in needs an IN list parameter", stmt_name);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            if policy == EmptyIn::False && conditions.len() != lst_params.len() {
+                let msg = format!("`{}`: `empty-in: false` needs a column before each IN list - `col IN (:list)` - to replace the condition", stmt_name);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            for (idx, lst_param) in lst_params.iter_mut().enumerate() {
+                lst_param.if_empty = Some(match policy {
+                    EmptyIn::Null => match lst_param.tuple_len {
+                        Some( len ) => IfEmpty::Text(format!("({})", vec!["NULL"; len].join(","))),
+                        None => IfEmpty::Text("NULL".to_string())
+                    },
+                    // `col IN (NULL)` is NULL, which the constant condition overrides - NULL AND FALSE is false
+                    EmptyIn::False if conditions[idx].1 => IfEmpty::Text("NULL) OR (1=1".to_string()),
+                    EmptyIn::False => IfEmpty::Text("NULL) AND (1=0".to_string()),
+                    EmptyIn::Error => IfEmpty::Error
+                });
+            }
+        }
+        if let Some( ident ) = idents.iter().find(|ident| pos_params.contains(&ident.name)) {
+            let msg = format!("`{}`: `{}` is used both as an identifier and as a value parameter", stmt_name, ident.name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        let mut missing_params : Vec<(String, usize)> = Vec::new();
+        for (name, line_num) in mentions {
+            let is_used = pos_params.iter().chain(lst_params.iter().map(|param| &param.name)).any(|param| param.unraw() == name)
+                || idents.iter().any(|ident| ident.name == name);
+            if !is_used && !missing_params.iter().any(|(missing, _)| *missing == name) {
+                missing_params.push((name, line_num));
+            }
+        }
+        check_param_order(&param_order, &pos_params, &pos_args, &lst_params, placeholder).map_err(|msg| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt_name, msg))
+        })?;
+        let mut null_types = vec![None; pos_params.len()];
+        for param in param_annotations {
+            if param.null_type.is_none() {
+                continue;
+            }
+            if lst_params.iter().any(|lst_param| lst_param.name.unraw() == param.name) {
+                let msg = format!("`{}`: list parameter `{}` cannot be optional", stmt_name, param.name);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            if let Some( idx ) = pos_params.iter().position(|name| name.unraw() == param.name) {
+                null_types[idx] = param.null_type;
+            }
+        }
+        if healthcheck.is_some() && (!pos_params.is_empty() || !lst_params.is_empty() || !idents.is_empty()) {
+            let msg = format!("`{}`: health check statements cannot have parameters", stmt_name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        if !generate.is_empty() && !lst_params.is_empty() {
+            let msg = format!("`{}`: EXPLAIN cannot be generated for statements with IN list parameters", stmt_name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        if placeholder.named && !lst_params.is_empty() {
+            let msg = format!("`{}`: IN list parameters cannot be bound by name", stmt_name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        let pieces = if blocks.is_empty() && idents.is_empty() {
+            Vec::new()
+        } else {
+            let dynamic_text = if blocks.is_empty() { "identifier parameters" } else { "conditional blocks" };
+            if !lst_params.is_empty() {
+                let msg = format!("`{}`: {} cannot be used in statements with IN list parameters", stmt_name, dynamic_text);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            if placeholder.named {
+                let msg = format!("`{}`: statements with {} cannot be bound by name", stmt_name, dynamic_text);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            if let Some( block ) = blocks.iter().find(|block| null_types.iter().zip(&pos_params).any(|(null_type, name)| null_type.is_some() && name.unraw() == block.param)) {
+                let msg = format!("`{}`: `{}` controls a conditional block, thus it cannot be annotated as optional", stmt_name, block.param);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            let mut pieces = text_pieces(stmt_text, &blocks, &pos_params, &idents).map_err(|msg| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt_name, msg))
+            })?;
+            if minify {
+                join_piece_lines(&mut pieces);
+            }
+            pieces
+        };
+        if let Some( diesel ) = &diesel {
+            check_diesel_binds(diesel, &pos_params, &lst_params, !pieces.is_empty(), placeholder).map_err(|msg| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("`{}`: {}", stmt_name, msg))
+            })?;
+        }
+        if script && (!pos_params.is_empty() || !lst_params.is_empty() || !idents.is_empty()) {
+            let msg = format!("`{}`: scripts cannot have parameters", stmt_name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        if migration.is_some() && (!pos_params.is_empty() || !lst_params.is_empty() || !idents.is_empty()) {
+            let msg = format!("`{}`: migrations cannot have parameters", stmt_name);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+        let params = if !pos_params.is_empty() || !lst_params.is_empty() || !idents.is_empty() {
+            Some( StmtParams { docs, ..StmtParams::new(stmt_name, pos_params, null_types, pos_args, lst_params, pieces, idents) } )
+        } else {
+            None
+        };
+        let column_names = match kind {
+            StmtKind::Execute => None,
+            _ => result_columns(&text)
+        };
+        let columns = column_names.as_ref().map(Vec::len);
+        if !row.is_empty() {
+            if matches!(kind, StmtKind::Execute) {
+                let msg = format!("`{}`: only queries and `<!` statements return rows", stmt_name);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            if diesel.as_ref().is_some_and(|diesel| !diesel.row.is_empty()) {
+                let msg = format!("`{}`: the row struct is defined both by `-- returns:` and by `-- diesel-row:`", stmt_name);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+            if let Some( columns ) = columns.filter(|&columns| columns != row.len()) {
+                let msg = format!("`{}`: `-- returns:` lists {} column(s), but the projection has {}", stmt_name, row.len(), columns);
+                return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+            }
+        }
+        let column_names = column_names.map(unique_column_names).unwrap_or_default();
+        let script = if script { Some(split_script(&text)) } else { None };
+        let migration = migration.map(|version| Migration { version, down });
+        Ok(Stmt {
+            name: stmt_name.to_string(), kind, const_name: name, text, params,
+            source: source.into_owned(), lines: stmt_lines.to_vec(), cache_ttl, idempotent, generate, healthcheck, diesel, row, columns, column_names, script, migration, missing_params, tags, file: None, cfg, attrs
+        })
+    }
+}
+
+impl StmtParams {
+    fn new(stmt_name: &str, pos_params: Vec<Ident>, null_types: Vec<Option<Type>>, pos_args: Vec<PosArg>, lst_params: Vec<LstParam>, pieces: Vec<Piece>, idents: Vec<IdentParam>) -> Self {
+        StmtParams { struct_name: ident!(&to_camel_case(stmt_name)), pos_params, null_types, pos_args, lst_params, pieces, idents, docs: Vec::new(), groups: Vec::new() }
+    }
+}
+
+impl IdentParam {
+    /// Returns names of the enum variants of the allowed identifiers.
+    pub(crate) fn variants(&self) -> Vec<Ident> {
+        self.values.iter().map(|value| ident!(&ident_variant(value))).collect()
+    }
+}
+
+/// Returns the name of the enum variant of the identifier - `created_at` becomes `CreatedAt`.
+fn ident_variant(value: &str) -> String {
+    to_camel_case(&value.replace('.', "_"))
+}
+
+/// Parses the allowlist of the identifier parameter - `name, rank, s.id`.
+fn parse_allowlist(name: &str, list: &str) -> Result<Vec<String>, String> {
+    let mut values : Vec<String> = Vec::new();
+    for value in list.split(',').map(str::trim) {
+        if !SQL_IDENT.is_match(value) {
+            return Err(format!("`{}` in the allowlist of `{}` is not an identifier", value, name));
+        }
+        if let Some( same ) = values.iter().find(|allowed| ident_variant(allowed) == ident_variant(value)) {
+            return Err(format!("`{}` and `{}` in the allowlist of `{}` cannot be told apart - both become `{}`", same, value, name, ident_variant(value)));
+        }
+        values.push(value.to_string());
+    }
+    Ok(values)
+}
+
+/// Matches identifier parameters of the statement with their allowlists.
+fn ident_params(stmt_text: &str, allowlists: Vec<(String, Vec<String>)>) -> Result<Vec<IdentParam>, String> {
+    let ident_caps = sql_captures(&SQL_IDENT_PARAM, stmt_text);
+    for caps in &ident_caps {
+        if !allowlists.iter().any(|(name, _)| *name == caps[1]) {
+            return Err(format!("identifier parameter `{}` needs an allowlist - `-- allow {}: name, id`", &caps[1], &caps[1]));
+        }
+    }
+    let mut idents = Vec::with_capacity(allowlists.len());
+    for (name, values) in allowlists {
+        if !ident_caps.iter().any(|caps| caps[1] == name) {
+            return Err(format!("allowlist of `{}` is not used - `:#{}`", name, name));
+        }
+        idents.push(IdentParam { name: param_ident(&name), values });
+    }
+    Ok(idents)
+}
+
+/// Sets lengths of tuples of the lists that are compared with a tuple of columns -
+/// `(ship_id, rank) IN (:pairs)`.
+fn set_tuple_lens(stmt_text: &str, lst_params: &mut [LstParam]) -> Result<(), String> {
+    let mut tuple_lists : Vec<(String, usize)> = Vec::new();
+    for caps in sql_captures(&TUPLE_IN, stmt_text) {
+        // a function call, like `coalesce(a, b) IN (:list)`, compares a single value
+        let keyword = caps[1].to_uppercase();
+        if !keyword.is_empty() && !TUPLE_KEYWORDS.contains(&keyword.as_str()) {
+            continue;
+        }
+        let len = caps[2].split(',').count();
+        if len > 1 {
+            tuple_lists.push((caps[3].to_string(), len));
+        }
+    }
+    for (name, len) in &tuple_lists {
+        for param in lst_params.iter_mut().filter(|param| param.name.unraw() == name.as_str()) {
+            if param.tuple_len.is_some_and(|other_len| other_len != *len) {
+                return Err(format!("tuple list `{}` is compared with tuples of different lengths", name));
+            }
+            param.tuple_len = Some(*len);
+        }
+    }
+    for param in lst_params.iter() {
+        let num_tuples = tuple_lists.iter().filter(|(name, _)| param.name.unraw() == name.as_str()).count();
+        let num_uses = lst_params.iter().filter(|other| other.name == param.name).count();
+        if num_tuples > 0 && num_tuples != num_uses {
+            return Err(format!("list `{}` is compared both with a tuple of columns and with a single value", param.name.unraw()));
+        }
+    }
+    Ok(())
+}
+
+/// Keywords that can precede the tuple of columns in `(ship_id, rank) IN (:pairs)`.
+const TUPLE_KEYWORDS : [&str; 10] = ["WHERE", "AND", "OR", "NOT", "ON", "HAVING", "WHEN", "THEN", "ELSE", "SELECT"];
+
+/// Returns the column each IN list of the statement is compared with and whether the list is
+/// a NOT IN one.
+fn list_conditions(stmt_text: &str) -> Vec<(String, bool)> {
+    sql_captures(&CHUNKED_IN, stmt_text).iter().map(|caps| (caps[1].to_string(), caps.get(2).is_some())).collect()
+}
+
+/// Replaces identifier parameters with the first identifiers of their allowlists.
+fn replace_ident_params<'t>(stmt_text: &'t str, idents: &[IdentParam]) -> Cow<'t, str> {
+    if idents.is_empty() {
+        return Cow::Borrowed(stmt_text);
+    }
+    let verbatim = verbatim_ranges(stmt_text);
+    SQL_IDENT_PARAM.replace_all(stmt_text, |caps: &regex::Captures| {
+        if caps.get(0).is_some_and(|m| !is_sql_match(stmt_text, &verbatim, m)) {
+            return caps[0].to_string();
+        }
+        idents.iter().find(|ident| ident.name.unraw() == caps[1]).map(|ident| ident.values[0].clone()).unwrap_or_default()
+    })
+}
+
+/// Parses the parameter and its SQL type - `ship Integer`.
+fn parse_diesel_bind(entry: &str) -> Result<(String, Type), String> {
+    let mut parts = entry.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some( name ), Some( sql_type ), None) if name.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+            let sql_type = syn::parse_str(sql_type).map_err(|err| format!("invalid SQL type `{}` of `{}`: {}", sql_type, name, err))?;
+            Ok((name.to_string(), sql_type))
+        }
+        _ => Err(format!("invalid diesel-bind `{}`, expected a parameter name followed by its SQL type - `ship Integer`", entry))
+    }
+}
+
+/// Parses the column, its SQL and Rust types - `name Text String`.
+fn parse_diesel_column(entry: &str) -> Result<(Ident, Type, Type), String> {
+    let mut parts = entry.split_whitespace();
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some( name ), Some( sql_type ), Some( field_type ), None) if name.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+            let sql_type = syn::parse_str(sql_type).map_err(|err| format!("invalid SQL type `{}` of `{}`: {}", sql_type, name, err))?;
+            let field_type = syn::parse_str(field_type).map_err(|err| format!("invalid type `{}` of `{}`: {}", field_type, name, err))?;
+            Ok((ident!(name), sql_type, field_type))
+        }
+        _ => Err(format!("invalid diesel-row `{}`, expected a column name followed by its SQL and Rust types - `name Text String`", entry))
+    }
+}
+
+/// Parses the column of the row struct - `name String`.
+fn parse_row_column(entry: &str) -> Result<(Ident, Type), String> {
+    let entry = entry.trim();
+    let (name, field_type) = entry.split_once(char::is_whitespace).unwrap_or((entry, ""));
+    if name.is_empty() || field_type.trim().is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(format!("invalid returned column `{}`, expected a column name followed by its Rust type - `name String`", entry));
+    }
+    let field_type = syn::parse_str(field_type.trim()).map_err(|err| format!("invalid type `{}` of `{}`: {}", field_type.trim(), name, err))?;
+    Ok((ident!(name), field_type))
+}
+
+/// Splits the list of columns at commas that are not inside generic arguments or tuples -
+/// `id i32, pos (i32, i32)`.
+fn split_type_list(list: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (pos, c) in list.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                entries.push(&list[start..pos]);
+                start = pos + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&list[start..]);
+    entries
+}
+
+/// Checks that diesel-bind gives the SQL type of each parameter that `sql_query` can bind.
+fn check_diesel_binds(diesel: &DieselTypes, pos_params: &[Ident], lst_params: &[LstParam], has_blocks: bool, placeholder: &Placeholder) -> Result<(), String> {
+    if let Some( lst_param ) = lst_params.first() {
+        return Err(format!("diesel `sql_query` cannot bind IN list parameter `{}`", lst_param.name.unraw()));
+    }
+    if has_blocks {
+        return Err("diesel `sql_query` cannot execute statements with conditional blocks or identifier parameters".to_string());
+    }
+    if placeholder.named {
+        return Err("diesel `sql_query` binds arguments by position, not by name".to_string());
+    }
+    for (name, _) in &diesel.binds {
+        if !pos_params.iter().any(|param| param.unraw() == name) {
+            return Err(format!("`{}` in diesel-bind is not a parameter of the statement", name));
+        }
+    }
+    for param in pos_params {
+        if !diesel.binds.iter().any(|(name, _)| param.unraw() == name) {
+            return Err(format!("diesel-bind does not give the SQL type of `{}` - `-- diesel-bind: {} Text`", param.unraw(), param.unraw()));
+        }
+    }
+    Ok(())
+}
+
+/// Splits the text of the statement with conditional blocks into pieces. Lines of each block
+/// become the pieces of that block.
+fn text_pieces(stmt_text: &str, blocks: &[BlockLines], pos_params: &[Ident], idents: &[IdentParam]) -> Result<Vec<Piece>, String> {
+    let mut cond_params = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        match pos_params.iter().position(|name| name.unraw() == block.param) {
+            Some( index ) => cond_params.push(index),
+            None => return Err(format!("conditional block of `{}` does not use the parameter", block.param))
+        }
+    }
+    let mut pieces = Vec::new();
+    let mut group = String::new();
+    let mut group_block = None;
+    for (line_idx, line) in stmt_text.split('\n').enumerate() {
+        let line_block = blocks.iter().position(|block| block.start <= line_idx && line_idx < block.end);
+        if line_block != group_block {
+            push_text_pieces(&group, group_block.map(|idx| cond_params[idx]), pos_params, idents, &mut pieces);
+            group.clear();
+            group_block = line_block;
+        }
+        // the line break goes with the line, so it is omitted with the block
+        if line_idx > 0 {
+            group.push('\n');
+        }
+        group.push_str(line);
+    }
+    push_text_pieces(&group, group_block.map(|idx| cond_params[idx]), pos_params, idents, &mut pieces);
+
+    for piece in &pieces {
+        match piece {
+            Piece::Param(index) if cond_params.contains(index) => {
+                return Err(format!("`{}` controls a conditional block, thus it can only be used inside its blocks", pos_params[*index].unraw()));
+            }
+            Piece::Block(cond_index, block_pieces) => {
+                if !block_pieces.iter().any(|piece| matches!(piece, Piece::Param(index) if index == cond_index)) {
+                    return Err(format!("conditional block of `{}` does not use the parameter", pos_params[*cond_index].unraw()));
+                }
+                for piece in block_pieces {
+                    if let Piece::Param(index) = piece {
+                        if index != cond_index && cond_params.contains(index) {
+                            return Err(format!("`{}` controls a conditional block, thus it can only be used inside its blocks", pos_params[*index].unraw()));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(pieces)
+}
+
+/// Splits the text into text, parameter and identifier pieces. When the text is a conditional
+/// block, the latter are wrapped into a block piece.
+fn push_text_pieces(text: &str, cond_param: Option<usize>, pos_params: &[Ident], idents: &[IdentParam], pieces: &mut Vec<Piece>) {
+    if text.is_empty() {
+        return;
+    }
+    let mut text_pieces = Vec::new();
+    let mut from = 0;
+    for caps in sql_captures(&SQL_PIECE_PARAM, text) {
+        if let Some( param_match ) = caps.get(0) {
+            let piece = if caps.get(1).is_some() {
+                idents.iter().position(|ident| ident.name.unraw() == caps[2]).map(Piece::Ident)
+            } else {
+                pos_params.iter().position(|name| name.unraw() == caps[2]).map(Piece::Param)
+            };
+            if let Some( piece ) = piece {
+                text_pieces.push(Piece::Text(text[from..param_match.start()].to_string()));
+                text_pieces.push(piece);
+                from = param_match.end();
+            }
+        }
+    }
+    text_pieces.push(Piece::Text(text[from..].to_string()));
+    match cond_param {
+        Some( index ) => pieces.push(Piece::Block(index, text_pieces)),
+        None => pieces.extend(text_pieces)
+    }
+}
+
+/// Checks that the explicit order lists each positional parameter of the statement once.
+fn check_param_order(param_order: &[String], pos_params: &[Ident], pos_args: &[PosArg], lst_params: &[LstParam], placeholder: &Placeholder) -> Result<(), String> {
+    if param_order.is_empty() {
+        return Ok(());
+    }
+    let bound_once = placeholder.named || matches!(placeholder.numbering, Numbering::From(_)) && placeholder.dedup;
+    if !bound_once {
+        return Err("param-order needs numbered placeholders that bind repeated parameters once".to_string());
+    }
+    for (index, name) in param_order.iter().enumerate() {
+        if lst_params.iter().any(|param| param.name.unraw() == name) {
+            return Err(format!("list parameter `{}` cannot be ordered, lists are bound after all other parameters", name));
+        }
+        if !pos_args.iter().any(|arg| arg.index == index) {
+            return Err(format!("`{}` in param-order is not a parameter of the statement", name));
+        }
+    }
+    if let Some( missing ) = pos_params.get(param_order.len()) {
+        return Err(format!("param-order does not list parameter `{}`", missing));
+    }
+    Ok(())
+}
+
+/// Replaces named parameters with placeholders. Unless `param_order` says otherwise, parameters
+/// are numbered in the order of their first occurrence, thus parameters added after the existing
+/// ones never change the numbers of the latter.
+fn parse_sql_text(stmt_text: &str, placeholder: &Placeholder, param_order: &[String]) -> (String, Vec<Ident>, Vec<PosArg>, Vec<LstParam>) {
+    let mut text = String::with_capacity(stmt_text.len());
+    let mut sql_in_params = Vec::new();
+    for caps in sql_captures(&SQL_IN_PARAM, stmt_text) {
+        let param_name = &caps[1];
+        if !sql_in_params.iter().any(|name| name == param_name) {
+            sql_in_params.push(param_name.to_string());
+        }
+    }
+    let mut from = 0;
+    let mut pos_params = param_order.to_vec();
+    let mut pos_args = Vec::new();
+    let mut lst_params = Vec::new();
+    for caps in sql_captures(&SQL_PARAM, stmt_text) {
+        if let Some( param_match ) = caps.get(0) {
+            let text_end = param_match.start();
+            text.push_str(&stmt_text[from..text_end]);
+            let param_name = &caps[1];
+            if sql_in_params.iter().any(|name| name == param_name) {
+                let param = LstParam { name: param_ident(param_name), position: text.len(), tuple_len: None, chunks: None, if_empty: None };
+                lst_params.push(param);
+            } else {
+                let index = match pos_params.iter().position(|name| name == param_name) {
+                    Some( idx ) => idx,
+                    None => {
+                        pos_params.push(param_name.to_string());
+                        pos_params.len() - 1
+                    }
+                };
+                let is_new = !pos_args.iter().any(|arg: &PosArg| arg.index == index);
+                if placeholder.named {
+                    text.push_str(param_match.as_str());
+                    if is_new {
+                        pos_args.push(PosArg { index, position: text.len() });
+                    }
+                    from = param_match.end();
+                    continue;
+                }
+                text.push_str(&placeholder.prefix);
+                match placeholder.numbering {
+                    Numbering::From(first_param_no) if placeholder.dedup => {
+                        text.push_str(&(first_param_no + index).to_string());
+                        if is_new {
+                            pos_args.push(PosArg { index, position: text.len() });
+                        }
+                    }
+                    Numbering::From(first_param_no) => {
+                        text.push_str(&(first_param_no + pos_args.len()).to_string());
+                        pos_args.push(PosArg { index, position: text.len() });
+                    }
+                    Numbering::None => {
+                        pos_args.push(PosArg { index, position: text.len() });
+                    }
+                }
+            }
+            from = param_match.end();
+        }
+    }
+    text.push_str(&stmt_text[from..]);
+    if !param_order.is_empty() {
+        // ordered parameters are bound by their numbers rather than by their first occurrence
+        pos_args.sort_by_key(|arg| arg.index);
+    }
+
+    let pos_params : Vec<_> = pos_params.into_iter().map(|name| param_ident(&name)).collect();
+
+    (text, pos_params, pos_args, lst_params)
+}
+
+/// Replaces `{{build.version}}` with the package version and other `{{build.<name>}}`
+/// placeholders with values of the `BUILD_<NAME>` environment variables.
+fn interpolate_build_vars(stmt_text: &str) -> Result<String, String> {
+    let mut text = String::with_capacity(stmt_text.len());
+    let mut from = 0;
+    for caps in BUILD_VAR.captures_iter(stmt_text) {
+        if let Some( var_match ) = caps.get(0) {
+            let var_name = match &caps[1] {
+                "version" => "CARGO_PKG_VERSION".to_string(),
+                name => format!("BUILD_{}", name.to_uppercase())
+            };
+            let value = std::env::var(&var_name).map_err(|_| {
+                format!("`{}` needs the {} environment variable, which is not set", var_match.as_str(), var_name)
+            })?;
+            text.push_str(&stmt_text[from..var_match.start()]);
+            text.push_str(&value);
+            from = var_match.end();
+        }
+    }
+    text.push_str(&stmt_text[from..]);
+    Ok(text)
+}
+
+/// Named piece of SQL text - `-- fragment: crew_columns` - that statements include.
+struct Fragment {
+    name: String,
+    text: String,
+    /// Numbers of the file lines of the text
+    lines: Vec<usize>
+}
+
+fn find_fragment<'a>(fragments: &'a [Fragment], name: &str) -> Result<&'a Fragment, String> {
+    fragments.iter().find(|fragment| fragment.name == name).ok_or_else(|| {
+        format!("fragment `{}` is not defined - fragments must be defined above the statements that use them", name)
+    })
+}
+
+/// Replaces fragment references - `:{crew_columns}` - in the statement line with the fragment text.
+fn expand_fragment_refs<'a>(line: Cow<'a, str>, fragments: &[Fragment]) -> Result<Cow<'a, str>, String> {
+    if !line.contains(":{") {
+        return Ok(line);
+    }
+    let mut text = String::with_capacity(line.len());
+    let mut from = 0;
+    for caps in FRAGMENT_REF.captures_iter(&line) {
+        if let Some( ref_match ) = caps.get(0) {
+            let fragment = find_fragment(fragments, &caps[1])?;
+            text.push_str(&line[from..ref_match.start()]);
+            text.push_str(&fragment.text);
+            from = ref_match.end();
+        }
+    }
+    text.push_str(&line[from..]);
+    Ok(Cow::Owned(text))
+}
+
+/// Replaces template variables - `{{schema}}` - in the statement line with their values from the
+/// `vars` argument of the macro or the constants of the file. Text is left as is when neither
+/// declares variables.
+fn interpolate_template_vars<'a>(line: &'a str, vars: &[(String, String)], used: &mut [bool]) -> Result<Cow<'a, str>, String> {
+    if vars.is_empty() {
+        return Ok(Cow::Borrowed(line));
+    }
+    let mut text = String::with_capacity(line.len());
+    let mut from = 0;
+    for caps in TEMPLATE_VAR.captures_iter(line) {
+        if let Some( var_match ) = caps.get(0) {
+            let var_name = &caps[1];
+            let idx = vars.iter().position(|(name, _)| name == var_name).ok_or_else(|| {
+                format!("`{}` is not declared - `vars = {{ \"{}\" = \"...\" }}` or `-- define: {} = ...`", var_match.as_str(), var_name, var_name)
+            })?;
+            used[idx] = true;
+            text.push_str(&line[from..var_match.start()]);
+            text.push_str(&vars[idx].1);
+            from = var_match.end();
+        }
+    }
+    text.push_str(&line[from..]);
+    Ok(Cow::Owned(text))
+}
+
+/// Replaces environment variables - `${SCHEMA}` - in the statement line with their values. The
+/// `vars` argument of the macro can set them too, and its values take precedence over the ones
+/// of the environment the macro is expanded in.
+fn interpolate_env_vars<'a>(line: Cow<'a, str>, vars: &[(String, String)], used: &mut [bool]) -> Result<Cow<'a, str>, String> {
+    if !line.contains("${") {
+        return Ok(line);
+    }
+    let mut text = String::with_capacity(line.len());
+    let mut from = 0;
+    for caps in ENV_VAR.captures_iter(&line) {
+        if let Some( var_match ) = caps.get(0) {
+            let var_name = &caps[1];
+            let value = match vars.iter().position(|(name, _)| name == var_name) {
+                Some( idx ) => {
+                    used[idx] = true;
+                    vars[idx].1.clone()
+                }
+                None => std::env::var(var_name).map_err(|_| {
+                    format!("`{}` needs the {} environment variable, which is not set", var_match.as_str(), var_name)
+                })?
+            };
+            text.push_str(&line[from..var_match.start()]);
+            text.push_str(&value);
+            from = var_match.end();
+        }
+    }
+    text.push_str(&line[from..]);
+    Ok(Cow::Owned(text))
+}
+
+/// Replaces line breaks of the minified text with spaces. Line breaks in quoted text stay.
+/// Lines of the minified text have no comments, which would otherwise comment out what follows.
+fn join_lines(text: &str) -> String {
+    let verbatim = verbatim_ranges(text);
+    text.char_indices().map(|(pos, c)| if c == '\n' && !verbatim.iter().any(|range| range.contains(&pos)) { ' ' } else { c }).collect()
+}
+
+/// Returns the statement text without comments and with runs of whitespace outside quoted text
+/// collapsed into single spaces, as minification does. Texts that differ only in formatting and
+/// comments have the same normalized text.
+pub(crate) fn normalized_text(text: &str) -> String {
+    let mut open = OpenSpan::None;
+    let lines : Vec<String> = text.lines().map(|line| {
+        let line = strip_comments(line, &mut open, true);
+        // trailing whitespace of a quoted text that continues on the next line is a part of it
+        if matches!(open, OpenSpan::None) { line.trim_end().to_string() } else { line }
+    }).filter(|line| !line.is_empty()).collect();
+    join_lines(&lines.join("\n"))
+}
+
+/// Replaces line breaks of the minified text pieces with spaces.
+fn join_piece_lines(pieces: &mut [Piece]) {
+    for piece in pieces {
+        match piece {
+            Piece::Text(text) => *text = join_lines(text),
+            Piece::Block(_, block_pieces) => join_piece_lines(block_pieces),
+            _ => {}
+        }
+    }
+}
+
+/// Quoted text or block comment that continues on the next line.
+enum OpenSpan {
+    None,
+    Quote(char),
+    DollarQuote(String),
+    Comment
+}
+
+/// Removes comments from the line of the statement text and, when `collapse` is set, collapses
+/// runs of whitespace into single spaces. `open` is what the previous lines left open - quoted
+/// text is kept as is, so `'--'` stays.
+fn strip_comments(line: &str, open: &mut OpenSpan, collapse: bool) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut rest = if collapse && matches!(open, OpenSpan::None) { line.trim_start() } else { line };
+    loop {
+        match std::mem::replace(open, OpenSpan::None) {
+            OpenSpan::Quote(quote) => match rest.find(quote) {
+                Some( end ) => {
+                    // a doubled quote just opens the next quoted part
+                    text.push_str(&rest[..=end]);
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    text.push_str(rest);
+                    *open = OpenSpan::Quote(quote);
+                    break;
+                }
+            },
+            OpenSpan::DollarQuote(tag) => match rest.find(&tag) {
+                Some( end ) => {
+                    text.push_str(&rest[..end + tag.len()]);
+                    rest = &rest[end + tag.len()..];
+                }
+                None => {
+                    text.push_str(rest);
+                    *open = OpenSpan::DollarQuote(tag);
+                    break;
+                }
+            },
+            OpenSpan::Comment => match rest.find("*/") {
+                Some( end ) => rest = &rest[end + 2..],
+                None => {
+                    *open = OpenSpan::Comment;
+                    break;
+                }
+            },
+            OpenSpan::None => {
+                let Some( pos ) = rest.find(['\'', '"', '`', '$', '-', '/']) else {
+                    push_code(&mut text, rest, collapse);
+                    break;
+                };
+                push_code(&mut text, &rest[..pos], collapse);
+                rest = &rest[pos..];
+                if rest.starts_with("--") {
+                    break;
+                } else if let Some( comment ) = rest.strip_prefix("/*") {
+                    rest = comment;
+                    *open = OpenSpan::Comment;
+                } else if rest.starts_with('$') {
+                    let tag_len = rest[1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(0);
+                    if rest[1 + tag_len..].starts_with('$') {
+                        let tag = &rest[..tag_len + 2];
+                        text.push_str(tag);
+                        *open = OpenSpan::DollarQuote(tag.to_string());
+                        rest = &rest[tag.len()..];
+                    } else {
+                        text.push('$');
+                        rest = &rest[1..];
+                    }
+                } else {
+                    let c = rest.as_bytes()[0] as char;
+                    text.push(c);
+                    rest = &rest[1..];
+                    if c != '-' && c != '/' {
+                        *open = OpenSpan::Quote(c);
+                    }
+                }
+            }
+        }
+    }
+    let len = text.trim_end().len();
+    text.truncate(len);
+    text
+}
+
+/// Appends SQL to the text. When `collapse` is set, runs of whitespace become single spaces.
+fn push_code(text: &mut String, code: &str, collapse: bool) {
+    if !collapse {
+        text.push_str(code);
+        return;
+    }
+    let mut in_space = false;
+    for c in code.chars() {
+        if !c.is_whitespace() {
+            text.push(c);
+        } else if !in_space {
+            text.push(' ');
+        }
+        in_space = c.is_whitespace();
+    }
+}
+
+/// Returns the position of the semicolon that ends the text, if the text ends with one. Comments
+/// after it do not count.
+fn terminating_semicolon(text: &str) -> Option<usize> {
+    let verbatim = verbatim_ranges(text);
+    let (pos, c) = text.char_indices().rev().find(|(pos, c)| !c.is_whitespace() && !verbatim.iter().any(|range| range.contains(pos)))?;
+    (c == ';').then_some(pos)
+}
+
+/// Inserts the SQL - a tag comment or a clause - at the end of the statement text, but before
+/// its terminating semicolon. The SQL starts a new line when the text ends with a line comment.
+fn append_sql(text: &mut String, sql: &str) {
+    let end = terminating_semicolon(text).unwrap_or(text.trim_end().len());
+    let ends_with_line_comment = verbatim_ranges(&text[..end]).last().is_some_and(|range| range.end == end && text[range.start..].starts_with("--"));
+    let separator = if ends_with_line_comment { '\n' } else { ' ' };
+    text.insert_str(end, &format!("{}{}", separator, sql));
+}
+
+/// Limits the rows of the query to the page that the `limit` and `offset` parameters select.
+fn paginate_text(text: &str, paginate: Paginate, dialect: Option<&Dialect>) -> String {
+    match paginate {
+        Paginate::Clause => {
+            let mut text = text.to_string();
+            append_sql(&mut text, dialect.map_or("LIMIT :limit OFFSET :offset", Dialect::pagination));
+            text
+        }
+        Paginate::Rownum => {
+            let end = terminating_semicolon(text).unwrap_or(text.len());
+            let (query, rest) = text.split_at(end);
+            // the query keeps its lines, which conditional blocks refer to, and the wrapping
+            // starts a new line after it in case it ends with a line comment
+            format!("SELECT * FROM (SELECT q.*, ROWNUM AS row_num FROM ({}\n) q WHERE ROWNUM <= :offset + :limit) WHERE row_num > :offset{}", query.trim_end(), rest)
+        }
+    }
+}
+
+/// Splits the query tags - `service=crew, team=fleet`.
+pub(crate) fn parse_tags(text: &str) -> Result<Vec<String>, String> {
+    let tags : Vec<String> = text.split(',').map(str::trim).filter(|tag| !tag.is_empty()).map(str::to_string).collect();
+    if tags.is_empty() {
+        return Err("expected query tags - `service=crew`".to_string());
+    }
+    if let Some( tag ) = tags.iter().find(|tag| tag.contains("*/")) {
+        return Err(format!("query tag `{}` cannot contain `*/`, which ends the comment", tag));
+    }
+    Ok(tags)
+}
+
+/// Returns whether the match is a part of the SQL itself - it starts outside of comments and
+/// quoted text and it is not the type of a `::` cast - `id::text`.
+fn is_sql_match(text: &str, verbatim: &[Range<usize>], m: regex::Match) -> bool {
+    let is_verbatim = verbatim.iter().any(|range| range.contains(&m.start()));
+    let is_cast = m.as_str().starts_with(':') && text[..m.start()].ends_with(':');
+    !is_verbatim && !is_cast
+}
+
+/// Returns matches of the pattern that are a part of the SQL itself.
+fn sql_captures<'t>(pattern: &Regex, text: &'t str) -> Vec<regex::Captures<'t>> {
+    let verbatim = verbatim_ranges(text);
+    pattern.captures_iter(text).filter(|caps| caps.get(0).is_some_and(|m| is_sql_match(text, &verbatim, m))).collect()
+}
+
+/// Keywords that cannot be raw identifiers, thus parameters cannot be named like them.
+const NON_RAW_KEYWORDS : [&str; 4] = ["crate", "self", "Self", "super"];
+
+/// Returns the identifier of the parameter. Parameters named like Rust keywords - `:type` -
+/// become raw identifiers - `r#type` - so they can still name struct fields.
+fn param_ident(name: &str) -> Ident {
+    match syn::parse_str::<Ident>(name) {
+        Ok( ident ) => ident,
+        Err( _ ) if !NON_RAW_KEYWORDS.contains(&name) => syn::parse_str(&format!("r#{}", name)).unwrap_or_else(|_| ident!(name)),
+        Err( _ ) => ident!(name)
+    }
+}
+
+pub(crate) fn to_camel_case(stmt_name: &str) -> String {
+    let mut name = String::with_capacity(stmt_name.len());
+    for name_fragment in stmt_name.split('_') {
+        let mut chars = name_fragment.chars();
+        if let Some( first ) = chars.next() {
+            if let Some( c ) = first.to_uppercase().next() {
+                name.push(c);
+            }
+            for c in chars {
+                if let Some( c ) = c.to_lowercase().next() {
+                    name.push(c);
+                }
+            }
+        }
+    }
+    name
+}
+
+/// Splits the script into statements at semicolons that are outside of quotes, dollar quoted
+/// bodies and comments. Empty statements are dropped.
+fn split_script(text: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some( (pos, c) ) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                for (_, q) in chars.by_ref() {
+                    if q == c {
+                        break;
+                    }
+                }
+            }
+            '$' => {
+                // `$$` or `$body$` opens a dollar quoted text that ends with the same tag
+                let tag_len = text[pos + 1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(0);
+                if text[pos + 1 + tag_len..].starts_with('$') {
+                    let tag = &text[pos..pos + tag_len + 2];
+                    let body_start = pos + tag.len();
+                    let end = text[body_start..].find(tag).map_or(text.len(), |end| body_start + end + tag.len());
+                    while chars.peek().is_some_and(|&(next_pos, _)| next_pos < end) {
+                        chars.next();
+                    }
+                }
+            }
+            '-' if chars.peek().is_some_and(|&(_, next)| next == '-') => {
+                for (_, n) in chars.by_ref() {
+                    if n == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().is_some_and(|&(_, next)| next == '*') => {
+                chars.next();
+                let mut prev = ' ';
+                for (_, n) in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+            }
+            ';' => {
+                statements.push(text[start..pos].trim().to_string());
+                start = pos + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(text[start..].trim().to_string());
+    statements.retain(|stmt| !stmt.is_empty());
+    statements
+}
+
+/// Returns the columns of the rows the statement returns when the statement text tells them -
+/// from the projection of the SELECT or from the RETURNING list - with the names of the ones
+/// that are named. `None` when the projection has `*` or the statement is neither.
+fn result_columns(text: &str) -> Option<Vec<Option<String>>> {
+    // words and commas outside of parentheses, quotes and comments
+    let mut words : Vec<(usize, usize)> = Vec::new();
+    let mut commas = Vec::new();
+    let mut depth = 0i32;
+    let mut chars = text.char_indices().peekable();
+    while let Some( (pos, c) ) = chars.next() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '\'' | '"' | '`' => {
+                // doubled quotes inside are an escaped quote followed by the next quoted part
+                for (_, q) in chars.by_ref() {
+                    if q == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek().is_some_and(|&(_, next)| next == '-') => {
+                for (_, n) in chars.by_ref() {
+                    if n == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek().is_some_and(|&(_, next)| next == '*') => {
+                chars.next();
+                let mut prev = ' ';
+                for (_, n) in chars.by_ref() {
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+            }
+            ',' if depth == 0 => commas.push(pos),
+            _ if depth == 0 && (c.is_alphanumeric() || c == '_') => {
+                let mut end = pos + c.len_utf8();
+                while let Some( &(next_pos, next) ) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_' || next == '$') {
+                        break;
+                    }
+                    end = next_pos + next.len_utf8();
+                    chars.next();
+                }
+                words.push((pos, end));
+            }
+            _ => {}
+        }
+    }
+    let word_is = |&(start, end): &(usize, usize), keyword: &str| text[start..end].eq_ignore_ascii_case(keyword);
+    let first = words.first()?;
+    // data modifying statements, including the ones with a SELECT of the values, return the RETURNING list
+    let start = match words.iter().find(|word| word_is(word, "returning")) {
+        Some( returning ) => returning,
+        None if word_is(first, "select") || word_is(first, "with") => words.iter().find(|word| word_is(word, "select"))?,
+        None => return None
+    };
+    const CLAUSES : [&str; 14] = ["from", "into", "where", "group", "having", "order", "limit", "offset", "fetch", "union", "intersect", "except", "window", "for"];
+    let end = words.iter()
+        .find(|word| word.0 > start.1 && CLAUSES.iter().any(|clause| word_is(word, clause)))
+        .map_or(text.len(), |word| word.0);
+    if word_is(start, "returning") && end < text.len() {
+        // RETURNING ... INTO binds the values instead of returning rows
+        return None;
+    }
+    let mut columns = Vec::new();
+    let mut item_start = start.1;
+    for &pos in commas.iter().filter(|&&pos| start.1 < pos && pos < end).chain(std::iter::once(&end)) {
+        let item = text[item_start..pos].trim();
+        let item = item.strip_prefix("DISTINCT ").or_else(|| item.strip_prefix("distinct ")).unwrap_or(item).trim();
+        if item.is_empty() || item == "*" || item.ends_with(".*") {
+            return None;
+        }
+        columns.push(column_name(item));
+        item_start = pos + 1;
+    }
+    Some(columns)
+}
+
+/// Returns the name of the projection item - its alias or the column it selects - in the form
+/// of a constant name suffix, `None` for expressions without an alias.
+fn column_name(item: &str) -> Option<String> {
+    let name = match COLUMN_ALIAS.captures(item) {
+        // quoted with ", ` or [], or not quoted
+        Some( caps ) => (1..=4).find_map(|idx| caps.get(idx)).map_or("", |name| name.as_str()),
+        None => COLUMN_REF.captures(item)?.get(1).map_or("", |name| name.as_str())
+    };
+    let mut const_name = String::with_capacity(name.len());
+    for word in name.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()) {
+        if !const_name.is_empty() {
+            const_name.push('_');
+        }
+        const_name.push_str(&word.to_uppercase());
+    }
+    if const_name.is_empty() || const_name.starts_with(|c: char| c.is_ascii_digit()) {
+        None
+    } else {
+        Some(const_name)
+    }
+}
+
+/// Forgets names that several columns share, as the name does not tell which of them it is.
+fn unique_column_names(names: Vec<Option<String>>) -> Vec<Option<String>> {
+    names.iter().map(|name| {
+        name.as_ref().filter(|name| names.iter().filter(|other| other.as_ref() == Some(name)).count() == 1).cloned()
+    }).collect()
+}
+
+/// Replaces named parameters in the statement text with placeholders. When placeholders are
+/// numbered each parameter gets its own number, which is reused if the parameter is repeated.
+#[cfg(any(feature = "validate-sql", feature = "verify-postgres"))]
+pub(crate) fn replace_params(stmt_text: &str, param_prefix: &str, numbered: bool) -> String {
+    let mut names = Vec::new();
+    let verbatim = verbatim_ranges(stmt_text);
+    SQL_PARAM.replace_all(stmt_text, |caps: &regex::Captures| {
+        if caps.get(0).is_some_and(|m| !is_sql_match(stmt_text, &verbatim, m)) {
+            caps[0].to_string()
+        } else if numbered {
+            let param_name = caps[1].to_string();
+            let param_no = match names.iter().position(|name| *name == param_name) {
+                Some( idx ) => idx + 1,
+                None => {
+                    names.push(param_name);
+                    names.len()
+                }
+            };
+            format!("{}{}", param_prefix, param_no)
+        } else {
+            param_prefix.to_string()
+        }
+    }).into_owned()
+}
+
+lazy_static! {
+    static ref STMT_NAME : Regex = Regex::new(r"^--\s*name:\s*([[:word:]]+)(<!|!)?(?:\s*\((script)\))?").expect("bad statement name line pattern");
+    static ref MIGRATION : Regex = Regex::new(r"^--\s*migration:\s*(\S+)\s+([[:word:]]+)\s*$").expect("bad migration line pattern");
+    static ref DOWN : Regex = Regex::new(r"^--\s*down\s*$").expect("bad migration down line pattern");
+    static ref STRICT : Regex = Regex::new(r"^--\s*strict:\s*(\S*)\s*$").expect("bad strict mode line pattern");
+    static ref CACHE_TTL : Regex = Regex::new(r"^--\s*cache:\s*(\S*)").expect("bad cache line pattern");
+    static ref FRAGMENT : Regex = Regex::new(r"^--\s*fragment:\s*([[:word:]]+)\s*$").expect("bad fragment line pattern");
+    static ref USE_FRAGMENT : Regex = Regex::new(r"^--\s*use:\s*([[:word:]]+)\s*$").expect("bad fragment use line pattern");
+    static ref FRAGMENT_REF : Regex = Regex::new(r":\{\s*([[:word:]]+)\s*\}").expect("bad fragment reference pattern");
+    static ref IF_BLOCK : Regex = Regex::new(r"^--\s*if\s+:([[:word:]]+)\s*$").expect("bad conditional block start pattern");
+    static ref END_BLOCK : Regex = Regex::new(r"^--\s*end\s*$").expect("bad conditional block end pattern");
+    static ref RETURNS : Regex = Regex::new(r"^--\s*returns:\s*(.*)$").expect("bad returns line pattern");
+    static ref DIESEL_BIND : Regex = Regex::new(r"^--\s*diesel-bind:\s*(.*)$").expect("bad diesel-bind line pattern");
+    static ref DIESEL_ROW : Regex = Regex::new(r"^--\s*diesel-row:\s*(.*)$").expect("bad diesel-row line pattern");
+    static ref GROUP : Regex = Regex::new(r"^--\s*group:\s*([[:word:]]+)\s*=(.*)$").expect("bad parameter group pattern");
+    static ref DEFINE : Regex = Regex::new(r"^--\s*define:\s*([[:word:]]+)\s*=(.*)$").expect("bad constant definition pattern");
+    static ref TEMPLATE_VAR : Regex = Regex::new(r"\{\{\s*([[:word:]]+)\s*\}\}").expect("bad template variable pattern");
+    static ref ENV_VAR : Regex = Regex::new(r"\$\{\s*([[:word:]]+)\s*\}").expect("bad environment variable pattern");
+    static ref BUILD_VAR : Regex = Regex::new(r"\{\{\s*build\.([[:word:]]+)\s*\}\}").expect("bad build variable pattern");
+    static ref HEALTHCHECK : Regex = Regex::new(r"^--\s*healthcheck(?::\s*(\S+))?\s*$").expect("bad health check line pattern");
+    static ref PAGINATE : Regex = Regex::new(r"^--\s*paginate(?::\s*(\S+))?\s*$").expect("bad paginate line pattern");
+    static ref CFG : Regex = Regex::new(r"^--\s*cfg:(.*)$").expect("bad cfg line pattern");
+    static ref ATTR : Regex = Regex::new(r"^--\s*attr:(.*)$").expect("bad attribute line pattern");
+    static ref IDEMPOTENT : Regex = Regex::new(r"^--\s*idempotent\s*$").expect("bad idempotent line pattern");
+    static ref GENERATE : Regex = Regex::new(r"^--\s*generate:\s*(.*)$").expect("bad generate line pattern");
+    static ref PARAM_ORDER : Regex = Regex::new(r"^--\s*param-order:\s*(.*)$").expect("bad parameter order pattern");
+    static ref ORDER_BY : Regex = Regex::new(r"^--\s*order_by:\s*(.*)$").expect("bad order_by line pattern");
+    static ref ALLOW_IDENT : Regex = Regex::new(r"^--\s*allow\s+([[:word:]]+)\s*:\s*(.*)$").expect("bad identifier allowlist pattern");
+    static ref SQL_IDENT : Regex = Regex::new(r"^[[:alpha:]_][[:word:]]*(\.[[:alpha:]_][[:word:]]*)*$").expect("bad identifier pattern");
+    static ref SQL_IDENT_PARAM : Regex = Regex::new(r":#([[:alpha:]_][[:word:]]*)").expect("bad identifier parameter pattern");
+    static ref SQL_PIECE_PARAM : Regex = Regex::new(r":(#)?([[:alpha:]_][[:word:]]*)").expect("bad parameter or identifier pattern");
+    static ref TAG : Regex = Regex::new(r"^--\s*tag:(.*)$").expect("bad query tag line pattern");
+    static ref FIELD_DOC : Regex = Regex::new(r"^--\s*:([[:word:]]+)\s+-\s+(.*\S)\s*$").expect("bad parameter field description pattern");
+    static ref PARAM_MENTION : Regex = Regex::new(r"(?:^|[^:[:word:]]):([[:alpha:]_][[:word:]]*)").expect("bad parameter mention pattern");
+    static ref PARAM_DOC : Regex = Regex::new(r"^--\s*param:\s*([[:word:]]+)(\?)?(.*)$").expect("bad parameter description pattern");
+    static ref BLOCK_BEGIN : Regex = Regex::new(r"(?i)\bBEGIN\b").expect("bad block start pattern");
+    static ref SQL_PARAM : Regex = Regex::new(r":([[:alpha:]_][[:word:]]*)").expect("bad parameter name pattern");
+    static ref EMPTY_IN : Regex = Regex::new(r"^--\s*empty-in:\s*(\S*)\s*$").expect("bad empty IN list line pattern");
+    static ref TUPLE_IN : Regex = Regex::new(r"([[:word:]]*)\s*\(([^()]*)\)\s*(?:[Nn][Oo][Tt]\s+)?[Ii][Nn]\s*\(\s*:([[:alpha:]_][[:word:]]*)\s*\)").expect("bad tuple IN list pattern");
+    static ref IN_CHUNKS : Regex = Regex::new(r"^--\s*in-chunks:\s*(\S*)\s*$").expect("bad IN list chunks line pattern");
+    static ref CHUNKED_IN : Regex = Regex::new(r"\b((?:[[:word:]]+\.)*[[:word:]]+)(\s+[Nn][Oo][Tt])?\s+[Ii][Nn]\s*\(\s*:[[:alpha:]_][[:word:]]*\s*\)").expect("bad chunked IN list pattern");
+    static ref COLUMN_ALIAS : Regex = Regex::new(r#"(?i)\sAS\s+(?:"([^"]+)"|`([^`]+)`|\[([^\]]+)\]|([[:word:]]+))\s*$"#).expect("bad column alias pattern");
+    static ref COLUMN_REF : Regex = Regex::new(r"^(?:[[:word:]]+\.)*([[:word:]]+)$").expect("bad column reference pattern");
+    static ref SQL_IN_PARAM : Regex = Regex::new(r"\b[Ii][Nn]\s*\(\s*:([[:alpha:]_][[:word:]]*)\s*\)").expect("bad IN parameter pattern");
+}
@@ -0,0 +1,70 @@
+//! Detection of statements that no Rust code refers to anymore.
+//!
+//! With the `unused_in = "src"` option the macro reads the Rust files of the directory and of
+//! its subdirectories, and reports the statements whose generated items none of these files
+//! mentions.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use proc_macro2::{TokenStream, TokenTree};
+
+/// Returns the identifiers of the Rust files in the directory and in its subdirectories.
+/// Comments and string literals are skipped, while the arguments of macros are not.
+pub(crate) fn identifiers(dir: &str) -> Result<HashSet<String>, String> {
+    let mut idents = HashSet::new();
+    collect_dir(Path::new(dir), &mut idents)?;
+    Ok(idents)
+}
+
+fn collect_dir(dir: &Path, idents: &mut HashSet<String>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|err| format!("cannot read directory {}: {}", dir.display(), err))?;
+    for entry in entries {
+        let path = entry.map_err(|err| format!("cannot read directory {}: {}", dir.display(), err))?.path();
+        if path.is_dir() {
+            collect_dir(&path, idents)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let text = fs::read_to_string(&path).map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+            let tokens = TokenStream::from_str(&text).map_err(|_| format!("cannot tokenize {}", path.display()))?;
+            collect_tokens(tokens, idents);
+        }
+    }
+    Ok(())
+}
+
+fn collect_tokens(tokens: TokenStream, idents: &mut HashSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => collect_tokens(group.stream(), idents),
+            TokenTree::Ident(ident) => {
+                let ident = ident.to_string();
+                let ident = ident.strip_prefix("r#").map(str::to_string).unwrap_or(ident);
+                idents.insert(ident);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns whether any of the identifiers refers to the items that are generated for the
+/// statement - its constants (`SELECT_SHIP_CREW` and `SELECT_SHIP_CREW_KIND`), structs
+/// (`SelectShipCrew` and `SelectShipCrewRow`), macros (`select_ship_crew_args!` and
+/// `using_select_ship_crew_args!`) or function (`select_ship_crew`).
+pub(crate) fn is_used(idents: &HashSet<String>, const_name: &str, struct_name: &str, fn_name: &str) -> bool {
+    idents.iter().any(|ident| {
+        let macro_name = ident.strip_prefix("using_").unwrap_or(ident);
+        extends(ident, const_name, |c| c == '_')
+            || extends(ident, struct_name, char::is_uppercase)
+            || extends(macro_name, fn_name, |c| c == '_')
+    })
+}
+
+/// Returns whether the identifier is the name or the name followed by a suffix that starts
+/// with a matching character.
+fn extends(ident: &str, name: &str, suffix_start: impl Fn(char) -> bool) -> bool {
+    match ident.strip_prefix(name) {
+        Some( suffix ) => suffix.chars().next().is_none_or(suffix_start),
+        None => false
+    }
+}
@@ -0,0 +1,63 @@
+//! Compile-time validation of the SQL syntax.
+//!
+//! Statements are parsed by [sqlparser](https://github.com/sqlparser-rs/sqlparser-rs) when
+//! `include-sql` is built with the `validate-sql` feature.
+
+use crate::sql::Stmt;
+use crate::dialect::Dialect;
+
+/// Parses each statement and returns the description of the first syntax error.
+#[cfg(feature = "validate-sql")]
+pub(crate) fn check_syntax(path: &str, statements: &[Stmt], dialect: Option<&Dialect>) -> Result<(), String> {
+    use lazy_static::lazy_static;
+    use regex::Regex;
+    use sqlparser::dialect::{self as sp, GenericDialect};
+    use sqlparser::parser::Parser;
+
+    lazy_static! {
+        static ref ERROR_POSITION : Regex = Regex::new(r"\s*at Line: (\d+), Column: (\d+)$").expect("bad error position pattern");
+    }
+
+    let (sql_dialect, param_prefix, numbered) : (Box<dyn sp::Dialect>, &str, bool) = match dialect {
+        Some(Dialect::Postgres) => (Box::new(sp::PostgreSqlDialect {}), "$", true),
+        Some(Dialect::Sqlite)   => (Box::new(sp::SQLiteDialect {}), "?", false),
+        Some(Dialect::MySql)    => (Box::new(sp::MySqlDialect {}), "?", false),
+        Some(Dialect::MsSql)    => (Box::new(sp::MsSqlDialect {}), "@p", true),
+        _ => (Box::new(GenericDialect {}), "?", false),
+    };
+
+    for stmt in statements {
+        if dialect.is_some_and(|dialect| dialect.is_unprepared(&stmt.source)) {
+            continue;
+        }
+        let mut text = crate::sql::replace_params(&stmt.source, param_prefix, numbered);
+        if let Some(Dialect::MySql) = dialect {
+            // sqlparser does not know the MySQL assignment operator. The replacement keeps the
+            // columns of the following text.
+            text = text.replace(":=", " =");
+        }
+        if let Err(err) = Parser::parse_sql(&*sql_dialect, &text) {
+            let msg = err.to_string();
+            let first_line = stmt.lines.first().cloned().unwrap_or_default();
+            let (line, column, msg) = match ERROR_POSITION.captures(&msg) {
+                Some( caps ) => {
+                    let stmt_line : usize = caps[1].parse().unwrap_or(1);
+                    let line = stmt.lines.get(stmt_line.saturating_sub(1)).cloned().unwrap_or(first_line);
+                    let column : usize = caps[2].parse().unwrap_or(1);
+                    let end = caps.get(0).map(|m| m.start()).unwrap_or(msg.len());
+                    (line, column, &msg[..end])
+                }
+                None => (first_line, 1, msg.as_str())
+            };
+            let src_line = stmt.source.lines().nth(stmt.lines.iter().position(|&n| n == line).unwrap_or(0)).unwrap_or_default();
+            return Err(format!("{}:{}:{}: syntax error in `{}`: {}\n{}", path, line, column, stmt.name, msg, src_line));
+        }
+    }
+    Ok(())
+}
+
+/// Syntax validation is disabled. Statements are accepted as is.
+#[cfg(not(feature = "validate-sql"))]
+pub(crate) fn check_syntax(_path: &str, _statements: &[Stmt], _dialect: Option<&Dialect>) -> Result<(), String> {
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Verification of the included statements against a live database.
+//!
+//! When `include-sql` is built with the `verify-postgres` feature and the `DATABASE_URL`
+//! environment variable points to a Postgres database, every included statement is prepared
+//! by that database during the macro expansion. Statements that reference missing tables or
+//! columns fail the compilation.
+
+use crate::sql::Stmt;
+
+/// Prepares each statement in the database and returns the description of the first failure.
+///
+/// The database is only read. The schema snapshot is regenerated by `refresh_schema`, which
+/// build scripts call explicitly.
+#[cfg(feature = "verify-postgres")]
+pub(crate) fn check_statements(path: &str, statements: &[Stmt]) -> Result<(), String> {
+    let url = match std::env::var("DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => return Ok(())
+    };
+    if !url.starts_with("postgres://") && !url.starts_with("postgresql://") {
+        return Ok(());
+    }
+    let mut client = connect(&url)?;
+    prepare_statements(&mut client, path, statements).map(|_| ())
+}
+
+#[cfg(feature = "verify-postgres")]
+fn connect(url: &str) -> Result<postgres_client::Client, String> {
+    postgres_client::Client::connect(url, postgres_client::NoTls).map_err(|err| format!("cannot connect to DATABASE_URL: {}", err))
+}
+
+/// Prepares each statement in the database and returns the numbers of their parameters, or the
+/// description of the first failure.
+#[cfg(feature = "verify-postgres")]
+fn prepare_statements<'a>(client: &mut postgres_client::Client, path: &str, statements: impl IntoIterator<Item = &'a Stmt>) -> Result<Vec<(&'a str, usize)>, String> {
+    use postgres_client::error::ErrorPosition;
+
+    let mut param_counts = Vec::new();
+    // statements of scripts depend on the ones before them, like inserts into the created tables,
+    // and migrations change the schema rather than query it
+    for stmt in statements.into_iter().filter(|stmt| stmt.script.is_none() && stmt.migration.is_none()) {
+        let text = crate::sql::replace_params(&stmt.source, "$", true);
+        let prepared = client.prepare(&text);
+        if let Ok(prepared) = &prepared {
+            param_counts.push((stmt.name.as_str(), prepared.params().len()));
+        }
+        if let Err(err) = prepared {
+            let first_line = stmt.lines.first().cloned().unwrap_or_default();
+            let (line, msg) = match err.as_db_error() {
+                Some( db_err ) => {
+                    let line = match db_err.position() {
+                        Some(ErrorPosition::Original(pos)) => {
+                            let offset = text.char_indices().nth(*pos as usize - 1).map(|(offset, _)| offset).unwrap_or(0);
+                            let stmt_line = text[..offset].matches('\n').count();
+                            stmt.lines.get(stmt_line).cloned().unwrap_or(first_line)
+                        }
+                        _ => first_line
+                    };
+                    (line, db_err.message().to_string())
+                }
+                None => (first_line, err.to_string())
+            };
+            return Err(format!("{}:{}: `{}` cannot be prepared: {}", path, line, stmt.name, msg));
+        }
+    }
+    Ok(param_counts)
+}
+
+/// Prepares each statement in the database that `DATABASE_URL` points to and captures tables of
+/// the database and parameter counts of the statements into the snapshot.
+#[cfg(all(feature = "verify-postgres", feature = "schema-snapshot"))]
+pub(crate) fn refresh_schema(path: &str, statements: &[&Stmt], schema_path: &str) -> Result<(), String> {
+    use std::collections::BTreeMap;
+    use crate::schema::snapshot::Schema;
+
+    let url = std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL with the database to refresh the schema snapshot from is not set".to_string())?;
+    let mut client = connect(&url)?;
+    let param_counts = prepare_statements(&mut client, path, statements.iter().copied())?;
+    let mut schema = if std::path::Path::new(schema_path).exists() { Schema::load(schema_path)? } else { Schema::default() };
+    let rows = client.query("
+        SELECT table_name::text, column_name::text
+          FROM information_schema.columns
+         WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+         ORDER BY table_name, ordinal_position
+    ", &[]).map_err(|err| format!("cannot read database schema: {}", err))?;
+    let mut tables : BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for row in rows {
+        let table : String = row.get(0);
+        let column : String = row.get(1);
+        tables.entry(table).or_default().push(column);
+    }
+    schema.tables = tables;
+    let counts = param_counts.iter().map(|(name, count)| (name.to_string(), *count)).collect();
+    schema.statements.insert(path.to_string(), counts);
+    schema.save(schema_path)
+}
+
+/// Schema snapshot cannot be regenerated without a database and the snapshot format.
+#[cfg(not(all(feature = "verify-postgres", feature = "schema-snapshot")))]
+pub(crate) fn refresh_schema(_path: &str, _statements: &[&Stmt], _schema_path: &str) -> Result<(), String> {
+    Err("refreshing the schema snapshot needs the `verify-postgres` and `schema-snapshot` features".to_string())
+}
+
+/// Database verification is disabled. Statements are accepted as is.
+#[cfg(not(feature = "verify-postgres"))]
+pub(crate) fn check_statements(_path: &str, _statements: &[Stmt]) -> Result<(), String> {
+    Ok(())
+}
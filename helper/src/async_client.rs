@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
+use tokio_postgres::{Client, Error, Row, Statement};
+use tokio_postgres::types::ToSql;
+
+/// Client that executes the statements of the generated tokio-postgres functions.
+///
+/// `include-sql` generates async functions that prepare statements via `prepare_cached`. The
+/// trait is implemented by the tokio-postgres `Client`, which prepares the statement for every
+/// call, and by [`CachedClient`], which prepares each statement once.
+///
+/// This is only available with the `tokio-postgres` feature.
+///
+pub trait AsyncClient: Sync {
+    /// Returns the prepared statement.
+    fn prepare_cached(&self, sql: &'static str) -> impl Future<Output = Result<Statement, Error>> + Send;
+
+    /// Prepares a statement that has its text built at run time, like the ones with lists.
+    fn prepare(&self, sql: &str) -> impl Future<Output = Result<Statement, Error>> + Send;
+
+    /// Executes the statement and returns the resulting rows.
+    fn query(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<Vec<Row>, Error>> + Send;
+
+    /// Executes the statement that returns exactly one row.
+    fn query_one(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<Row, Error>> + Send;
+
+    /// Executes the statement and returns the number of modified rows.
+    fn execute(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<u64, Error>> + Send;
+}
+
+impl AsyncClient for Client {
+    fn prepare_cached(&self, sql: &'static str) -> impl Future<Output = Result<Statement, Error>> + Send {
+        Client::prepare(self, sql)
+    }
+
+    fn prepare(&self, sql: &str) -> impl Future<Output = Result<Statement, Error>> + Send {
+        Client::prepare(self, sql)
+    }
+
+    fn query(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<Vec<Row>, Error>> + Send {
+        Client::query(self, stmt, args)
+    }
+
+    fn query_one(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<Row, Error>> + Send {
+        Client::query_one(self, stmt, args)
+    }
+
+    fn execute(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<u64, Error>> + Send {
+        Client::execute(self, stmt, args)
+    }
+}
+
+/// tokio-postgres `Client` that keeps statements prepared by the generated functions.
+///
+/// Prepared statements belong to the connection, thus each client needs its own cache. The
+/// wrapper dereferences to the client, so it can be used wherever the client is.
+///
+/// This is only available with the `tokio-postgres` feature.
+///
+pub struct CachedClient {
+    client: Client,
+    statements: Mutex<HashMap<&'static str, Statement>>
+}
+
+impl CachedClient {
+    /// Wraps the client.
+    pub fn new(client: Client) -> Self {
+        CachedClient { client, statements: Mutex::new(HashMap::new()) }
+    }
+
+    /// Returns the wrapped client.
+    pub fn into_inner(self) -> Client {
+        self.client
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<&'static str, Statement>> {
+        // prepared statements remain valid even if another thread panicked while holding the lock
+        self.statements.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Deref for CachedClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.client
+    }
+}
+
+impl AsyncClient for CachedClient {
+    async fn prepare_cached(&self, sql: &'static str) -> Result<Statement, Error> {
+        let cached = self.lock().get(sql).cloned();
+        if let Some( stmt ) = cached {
+            return Ok(stmt);
+        }
+        let stmt = self.client.prepare(sql).await?;
+        self.lock().insert(sql, stmt.clone());
+        Ok(stmt)
+    }
+
+    fn prepare(&self, sql: &str) -> impl Future<Output = Result<Statement, Error>> + Send {
+        self.client.prepare(sql)
+    }
+
+    fn query(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<Vec<Row>, Error>> + Send {
+        self.client.query(stmt, args)
+    }
+
+    fn query_one(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<Row, Error>> + Send {
+        self.client.query_one(stmt, args)
+    }
+
+    fn execute(&self, stmt: &Statement, args: &[&(dyn ToSql + Sync)]) -> impl Future<Output = Result<u64, Error>> + Send {
+        self.client.execute(stmt, args)
+    }
+}
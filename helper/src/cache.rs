@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// In-memory cache of statement results.
+///
+/// Results are keyed by the statement name and the statement arguments. Statements annotated
+/// with `-- cache: 30s` get the `<STATEMENT>_CACHE_TTL` constant, which tells how long their
+/// results can be kept in the cache, and their generated functions get `_cached` variants that
+/// look up and keep their results in the cache passed to them.
+///
+/// ```
+/// use include_sql_helper::StatementCache;
+/// use std::time::Duration;
+///
+/// const SELECT_SHIP_NAME_CACHE_TTL : Duration = Duration::from_secs(30);
+///
+/// let cache = StatementCache::new();
+/// let name = cache.get_or_try_insert_with("select_ship_name", 42, SELECT_SHIP_NAME_CACHE_TTL, || {
+///     // execute the statement here
+///     Ok::<_, ()>(String::from("Enterprise"))
+/// });
+/// assert_eq!(Ok(String::from("Enterprise")), name);
+///
+/// let name = cache.get_or_try_insert_with("select_ship_name", 42, SELECT_SHIP_NAME_CACHE_TTL, || {
+///     Err(()) // not executed as the result is cached
+/// });
+/// assert_eq!(Ok(String::from("Enterprise")), name);
+/// ```
+///
+pub struct StatementCache<K, V> {
+    entries: Mutex<Entries<K, V>>
+}
+
+/// Cached results and their expiration times keyed by the statement name and the arguments
+type Entries<K, V> = HashMap<&'static str, HashMap<K, (Instant, V)>>;
+
+impl<K: Hash + Eq, V: Clone> StatementCache<K, V> {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        StatementCache { entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Returns the cached result of the statement executed with `args` unless it has expired.
+    pub fn get(&self, stmt_name: &'static str, args: &K) -> Option<V> {
+        let entries = self.lock();
+        let (expires, value) = entries.get(stmt_name)?.get(args)?;
+        if Instant::now() < *expires { Some(value.clone()) } else { None }
+    }
+
+    /// Caches the result of the statement executed with `args` for the `ttl` duration.
+    ///
+    /// Expired results of all statements are removed first, so results of arguments that are
+    /// not used again do not pile up.
+    pub fn insert(&self, stmt_name: &'static str, args: K, ttl: Duration, value: V) {
+        let now = Instant::now();
+        let mut entries = self.lock();
+        entries.retain(|_, results| {
+            results.retain(|_, (expires, _)| now < *expires);
+            !results.is_empty()
+        });
+        entries.entry(stmt_name).or_default().insert(args, (now + ttl, value));
+    }
+
+    /// Returns the cached result of the statement executed with `args`. When the result is
+    /// missing or has expired, `execute` is called to get the result, which is then cached for
+    /// the `ttl` duration. Errors are not cached.
+    ///
+    /// The cache is not locked while the statement is executed, thus concurrent callers might
+    /// execute the same statement.
+    pub fn get_or_try_insert_with<E>(&self, stmt_name: &'static str, args: K, ttl: Duration, execute: impl FnOnce() -> Result<V, E>) -> Result<V, E> {
+        if let Some( value ) = self.get(stmt_name, &args) {
+            return Ok(value);
+        }
+        let value = execute()?;
+        self.insert(stmt_name, args, ttl, value.clone());
+        Ok(value)
+    }
+
+    /// Removes all cached results of the statement.
+    pub fn invalidate(&self, stmt_name: &str) {
+        self.lock().remove(stmt_name);
+    }
+
+    /// Removes all cached results.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries<K, V>> {
+        // cached values remain valid even if another thread panicked while holding the lock
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for StatementCache<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
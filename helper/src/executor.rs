@@ -0,0 +1,105 @@
+/// Database interface that executes statements of the generated executor functions.
+///
+/// `include-sql` generates functions that only depend on this trait when the file is included
+/// with `functions = executor`. Thus supporting another database interface only needs an
+/// implementation of the trait. `P` is the type of the argument trait objects the interface
+/// binds - `dyn ToSql` or `dyn ToSql + Sync`, for example. Arguments borrow values for as long
+/// as the statement is executed, thus implementations cover trait objects with any lifetime.
+///
+/// The trait is implemented by the rusqlite `Connection` with the `rusqlite` feature and by
+/// the postgres `Client` and `Transaction` with the `postgres` feature.
+///
+pub trait Executor<P: ?Sized> {
+    /// Row that queries return
+    type Row;
+
+    /// Error of the database interface
+    type Error;
+
+    /// Prepares the statement without executing it. Interfaces that cache prepared statements
+    /// keep it for the subsequent executions.
+    fn prepare(&mut self, sql: &str) -> Result<(), Self::Error>;
+
+    /// Executes the statement and returns the resulting rows.
+    fn query(&mut self, sql: &str, args: &[&P]) -> Result<Vec<Self::Row>, Self::Error>;
+
+    /// Executes the statement that returns exactly one row.
+    fn query_one(&mut self, sql: &str, args: &[&P]) -> Result<Self::Row, Self::Error>;
+
+    /// Executes the statement and returns the number of modified rows.
+    fn execute(&mut self, sql: &str, args: &[&P]) -> Result<u64, Self::Error>;
+}
+
+#[cfg(feature = "rusqlite")]
+mod rusqlite_executor {
+    use super::Executor;
+    use rusqlite::{Connection, Error, Row};
+    use rusqlite::types::{ToSql, Value};
+
+    /// Returns column values of the row. rusqlite rows borrow the statement, thus they cannot
+    /// be returned as they are.
+    fn values(row: &Row<'_>) -> Result<Vec<Value>, Error> {
+        (0..row.column_count()).map(|idx| row.get(idx)).collect()
+    }
+
+    impl<'t> Executor<dyn ToSql + 't> for Connection {
+        type Row = Vec<Value>;
+        type Error = Error;
+
+        fn prepare(&mut self, sql: &str) -> Result<(), Error> {
+            self.prepare_cached(sql).map(|_| ())
+        }
+
+        fn query(&mut self, sql: &str, args: &[&(dyn ToSql + 't)]) -> Result<Vec<Vec<Value>>, Error> {
+            let mut stmt = self.prepare_cached(sql)?;
+            let mut rows = stmt.query(args)?;
+            let mut res = Vec::new();
+            while let Some( row ) = rows.next()? {
+                res.push(values(row)?);
+            }
+            Ok(res)
+        }
+
+        fn query_one(&mut self, sql: &str, args: &[&(dyn ToSql + 't)]) -> Result<Vec<Value>, Error> {
+            let mut stmt = self.prepare_cached(sql)?;
+            let mut rows = stmt.query(args)?;
+            match rows.next()? {
+                Some( row ) => values(row),
+                None => Err(Error::QueryReturnedNoRows)
+            }
+        }
+
+        fn execute(&mut self, sql: &str, args: &[&(dyn ToSql + 't)]) -> Result<u64, Error> {
+            let mut stmt = self.prepare_cached(sql)?;
+            stmt.execute(args).map(|num_rows| num_rows as u64)
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres_executor {
+    use super::Executor;
+    use postgres::{Error, GenericClient, Row};
+    use postgres::types::ToSql;
+
+    impl<'t, C: GenericClient> Executor<dyn ToSql + Sync + 't> for C {
+        type Row = Row;
+        type Error = Error;
+
+        fn prepare(&mut self, sql: &str) -> Result<(), Error> {
+            GenericClient::prepare(self, sql).map(|_| ())
+        }
+
+        fn query(&mut self, sql: &str, args: &[&(dyn ToSql + Sync + 't)]) -> Result<Vec<Row>, Error> {
+            GenericClient::query(self, sql, args)
+        }
+
+        fn query_one(&mut self, sql: &str, args: &[&(dyn ToSql + Sync + 't)]) -> Result<Row, Error> {
+            GenericClient::query_one(self, sql, args)
+        }
+
+        fn execute(&mut self, sql: &str, args: &[&(dyn ToSql + Sync + 't)]) -> Result<u64, Error> {
+            GenericClient::execute(self, sql, args)
+        }
+    }
+}
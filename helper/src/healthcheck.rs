@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+/// Probe statement annotated with `-- healthcheck`.
+///
+/// `include-sql` collects these into the `HEALTHCHECKS` constant and generates `run_healthchecks`
+/// that executes them via [`run_healthchecks`].
+#[derive(Debug, Clone, Copy)]
+pub struct HealthCheck {
+    /// Name of the statement
+    pub name: &'static str,
+    /// Statement text
+    pub sql: &'static str,
+    /// How long the probe may take - `-- healthcheck: 500ms`
+    pub time_limit: Option<Duration>
+}
+
+/// Outcome of a single health check.
+#[derive(Debug)]
+pub struct HealthCheckReport<E> {
+    /// Name of the probe statement
+    pub name: &'static str,
+    /// How long the probe took
+    pub elapsed: Duration,
+    /// Whether the probe passed
+    pub result: Result<(), HealthCheckError<E>>
+}
+
+impl<E> HealthCheckReport<E> {
+    /// Returns `true` when the probe succeeded within its time limit.
+    pub fn passed(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Reason of a failed health check.
+#[derive(Debug)]
+pub enum HealthCheckError<E> {
+    /// The probe statement returned an error
+    Failed(E),
+    /// The probe succeeded, but took longer than its time limit
+    TimedOut(Duration)
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for HealthCheckError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HealthCheckError::Failed(err) => write!(f, "{}", err),
+            HealthCheckError::TimedOut(limit) => write!(f, "took longer than {:?}", limit)
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for HealthCheckError<E> {}
+
+/// Executes each probe statement and reports whether it passed.
+///
+/// Probes are executed one after another by `execute`, which runs the statement on the database
+/// connection. A probe that completes, but takes longer than its time limit, is reported as
+/// timed out. Probes are not interrupted, thus the database interface should also have its own
+/// statement timeout set.
+///
+/// ```
+/// use include_sql_helper::{HealthCheck, run_healthchecks};
+///
+/// const HEALTHCHECKS : &[HealthCheck] = &[
+///     HealthCheck { name: "ping", sql: "SELECT 1", time_limit: None },
+///     HealthCheck { name: "ships_exist", sql: "SELECT 1 FROM ships WHERE rownum = 1", time_limit: None },
+/// ];
+///
+/// let reports = run_healthchecks(HEALTHCHECKS, |sql| {
+///     if sql.contains("ships") { Err("table or view does not exist") } else { Ok(()) }
+/// });
+/// assert!(reports[0].passed());
+/// assert!(!reports[1].passed());
+/// ```
+pub fn run_healthchecks<E>(checks: &[HealthCheck], mut execute: impl FnMut(&str) -> Result<(), E>) -> Vec<HealthCheckReport<E>> {
+    checks.iter().map(|check| {
+        let start = Instant::now();
+        let result = execute(check.sql);
+        let elapsed = start.elapsed();
+        let result = match (result, check.time_limit) {
+            (Err(err), _) => Err(HealthCheckError::Failed(err)),
+            (Ok(()), Some(limit)) if elapsed > limit => Err(HealthCheckError::TimedOut(limit)),
+            (Ok(()), _) => Ok(())
+        };
+        HealthCheckReport { name: check.name, elapsed, result }
+    }).collect()
+}
@@ -1,38 +1,599 @@
 //! This crate exports items that [include-sql](https://github.com/quietboil/include-sql), being a
 //! proc-macro library, cannot export.
 
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::ops::Range;
+
+mod cache;
+mod executor;
+mod healthcheck;
+mod migrations;
+mod reload;
+mod scan;
+
+pub use cache::StatementCache;
+pub use executor::Executor;
+pub use healthcheck::{HealthCheck, HealthCheckReport, HealthCheckError, run_healthchecks};
+pub use migrations::{Migration, MigrationRunner, MigrationError, migrate, rollback};
+pub use reload::{Placeholders, ReloadedStatement, reload_statement, reloaded_text};
+#[doc(hidden)]
+pub use scan::{verbatim_ranges, placeholder_count};
+
+#[cfg(feature = "inventory")]
+mod statements;
+
+#[cfg(feature = "inventory")]
+pub use statements::{Statement, statements, dump_statements, dump_statements_on_flag};
+
+#[cfg(feature = "inventory")]
+#[doc(hidden)]
+pub use inventory;
+
+#[cfg(feature = "rusqlite")]
+#[doc(hidden)]
+pub use rusqlite;
+
+#[cfg(feature = "postgres")]
+#[doc(hidden)]
+pub use postgres;
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing;
+
+#[cfg(feature = "tokio-postgres")]
+mod async_client;
+
+#[cfg(feature = "tokio-postgres")]
+pub use async_client::{AsyncClient, CachedClient};
+
+#[cfg(feature = "tokio-postgres")]
+#[doc(hidden)]
+pub use tokio_postgres;
+
+/// Kind of the included statement as it is tagged by the suffix of its name.
+///
+/// `include-sql` generates a `<STATEMENT>_KIND` constant for each statement, so wrappers
+/// around database interfaces can decide how the statement needs to be executed.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// Statement returns rows. Its name has no suffix - `-- name: select_ship_crew`
+    Query,
+    /// Statement returns a single row. Its name ends with `<!` - `-- name: insert_ship<!`
+    FetchOne,
+    /// Statement does not return rows. Its name ends with `!` - `-- name: insert_sailor!`
+    Execute
+}
+
+/// Where the included statement comes from and which parameters it binds.
+///
+/// `include-sql` generates a `<STATEMENT>_INFO` constant for each statement, so logging layers
+/// can reference the statement rather than its text. It is displayed as the file location
+/// followed by the statement name:
+///
+/// ```
+/// let info = include_sql_helper::StatementInfo { name: "select_ship_crew", file: "src/crew.sql", line: 12, params: &["ship"] };
+/// assert_eq!("src/crew.sql:12 select_ship_crew", info.to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatementInfo {
+    /// Name of the statement as it is defined in the SQL file
+    pub name: &'static str,
+    /// Path of the SQL file from which the statement was included
+    pub file: &'static str,
+    /// Number of the SQL file line where the statement text starts
+    pub line: u32,
+    /// Names of the statement parameters, each listed once, in the order of their first
+    /// placeholders, followed by the `IN (:list)` parameters. The `<STATEMENT>_PARAMS` constant
+    /// lists instead the parameter of each bound argument in the order the arguments are bound.
+    pub params: &'static [&'static str]
+}
+
+impl std::fmt::Display for StatementInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{} {}", self.file, self.line, self.name)
+    }
+}
+
+/// Conversion of a statement argument struct into the SQL text and the ordered list of arguments.
+///
+/// `include-sql` implements this trait for all generated argument structs - both the ones that
+/// expand `IN (:list)` parameters and the ones that use the statement text as is. This allows
+/// wrappers around database interfaces to execute any statement the same way. For example,
+/// a statement with a list parameter that also returns rows:
+///
+/// ```sql
+/// -- name: promote_sailors<!
+/// UPDATE sailors SET rank = :rank WHERE id IN (:ids) RETURNING id, name
+/// ```
+///
+/// can be executed by the same generic code that runs queries without lists:
+///
+/// ```rust,ignore
+/// fn query<'a, A>(conn: &Connection, args: A) -> Result<Rows>
+/// where A: IntoSqlWithArgs<'a, dyn ToSql + 'a>
+/// {
+///     let (sql, args) = args.into_sql_with_args();
+///     conn.query(&sql, &args)
+/// }
+/// ```
+///
+pub trait IntoSqlWithArgs<'a, T: ?Sized + 'a> {
+    /// Kind of the statement
+    const KIND: StatementKind;
+
+    /// Whether the statement is annotated with `-- idempotent`, i.e. can be safely retried
+    const IDEMPOTENT: bool = false;
+
+    /// Returns the SQL text to execute and the arguments in the order they must be bound.
+    fn into_sql_with_args(self) -> (Cow<'static, str>, Vec<&'a T>);
+}
+
+/// Error of the conversion of a name to value map into an arguments struct, or of arguments
+/// into the statement text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgsError {
+    /// The map or the builder has no value for the named parameter
+    Missing(&'static str),
+    /// The statement has no parameter with this name
+    Unknown(String),
+    /// The list argument has no items and the statement is annotated with `-- empty-in: error`
+    EmptyList(&'static str)
+}
+
+impl std::fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ArgsError::Missing(name) => write!(f, "missing argument `{}`", name),
+            ArgsError::Unknown(name) => write!(f, "unknown argument `{}`", name),
+            ArgsError::EmptyList(name) => write!(f, "list argument `{}` is empty", name)
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
 /// Pushes a list of values into the query argument list.
 /// 
 /// This is a helper function that `include-sql` uses when it generates `into_sql_with_args`.
 /// 
 pub fn push<'a,T: ?Sized>(arg: &[&'a T], param_prefix: &str, sql: &mut String, args: &mut Vec<&'a T>) {
-    let start = args.len() + 1;
-    let end = start + arg.len();
-    let range = start..end;
-    let mut iter = range.into_iter();
-    if let Some( n ) = iter.next() {
-        sql.push_str(param_prefix);
-        sql.push_str(&n.to_string());
-        for n in iter {
+    push_numbered(arg, param_prefix, Some(1), sql, args);
+}
+
+/// Pushes a list of values into the query argument list, like `push` does, for database
+/// interfaces that number parameters differently.
+/// 
+/// `first_param_no` is the number the database interface assigns to the first positional
+/// parameter. It is `None` when the database uses unnumbered placeholders.
+/// 
+pub fn push_numbered<'a,T: ?Sized>(arg: &[&'a T], param_prefix: &str, first_param_no: Option<usize>, sql: &mut String, args: &mut Vec<&'a T>) {
+    push_placeholders(arg.len(), param_prefix, first_param_no, args.len(), sql);
+    args.extend_from_slice(arg);
+}
+
+/// Pushes placeholders of a list of values into the SQL text.
+/// 
+/// This is a helper function that `include-sql` uses when it generates `into_sql_with_args`
+/// for argument structs with generic fields. `num_args` is the number of arguments that have
+/// been bound before the list.
+/// 
+pub fn push_placeholders(num_items: usize, param_prefix: &str, first_param_no: Option<usize>, num_args: usize, sql: &mut String) {
+    match first_param_no {
+        Some( first_param_no ) => {
+            let start = num_args + first_param_no;
+            let end = start + num_items;
+            let range = start..end;
+            let mut iter = range.into_iter();
+            // numbers are written straight into the SQL buffer, which is sized up front
+            if let Some( n ) = iter.next() {
+                sql.push_str(param_prefix);
+                let _ = write!(sql, "{}", n);
+                for n in iter {
+                    sql.push(',');
+                    sql.push_str(param_prefix);
+                    let _ = write!(sql, "{}", n);
+                }
+            }
+        }
+        None => {
+            for i in 0..num_items {
+                if i > 0 {
+                    sql.push(',');
+                }
+                sql.push_str(param_prefix);
+            }
+        }
+    }
+}
+
+/// Pushes placeholders of a list of values split into chunks of at most `chunk_len` items.
+/// 
+/// This is a helper function that `include-sql` uses for statements annotated with
+/// `-- in-chunks: 1000`. Chunks are separated by `chunk_sep`, which repeats the IN condition -
+/// `) OR col IN (`.
+/// 
+/// ```
+/// let mut sql = String::from("(id IN (");
+/// include_sql_helper::push_placeholder_chunks(5, 2, ") OR id IN (", "$", Some(1), 0, &mut sql);
+/// sql.push_str("))");
+/// assert_eq!("(id IN ($1,$2) OR id IN ($3,$4) OR id IN ($5))", sql);
+/// ```
+pub fn push_placeholder_chunks(num_items: usize, chunk_len: usize, chunk_sep: &str, param_prefix: &str, first_param_no: Option<usize>, num_args: usize, sql: &mut String) {
+    let mut start = 0;
+    loop {
+        let end = num_items.min(start + chunk_len);
+        push_placeholders(end - start, param_prefix, first_param_no, num_args + start, sql);
+        if end == num_items {
+            break;
+        }
+        sql.push_str(chunk_sep);
+        start = end;
+    }
+}
+
+/// Pushes items of a list argument into the query argument list and their placeholders, split
+/// into chunks of at most `chunk_len` items, into the SQL text.
+/// 
+/// This is a helper function that `include-sql` uses for statements annotated with
+/// `-- in-chunks: 1000`. Like [`push_iter`] it returns the range of the pushed items.
+/// 
+pub fn push_iter_in_chunks<'a,T: ?Sized>(arg: impl Iterator<Item = &'a T>, chunk_len: usize, chunk_sep: &str, param_prefix: &str, first_param_no: Option<usize>, sql: &mut String, args: &mut Vec<&'a T>) -> Range<usize> {
+    let start = args.len();
+    args.extend(arg);
+    let end = args.len();
+    push_placeholder_chunks(end - start, chunk_len, chunk_sep, param_prefix, first_param_no, start, sql);
+    start..end
+}
+
+/// Pushes an argument into the query argument list and its placeholder into the SQL text.
+/// 
+/// This is a helper function that `include-sql` uses when it generates `into_sql_with_args`
+/// for statements with conditional blocks. `bound_as` is the number of the placeholder of an
+/// argument that has been already bound, in which case the placeholder is reused. Returns the
+/// number of the placeholder, which is `None` when the database uses unnumbered placeholders.
+/// 
+/// ```
+/// let mut sql = String::new();
+/// let mut args : Vec<&str> = Vec::new();
+/// let ship_no = include_sql_helper::push_arg("Enterprise", None, "$", Some(1), &mut sql, &mut args);
+/// sql.push_str(" OR ");
+/// include_sql_helper::push_arg("Enterprise", ship_no, "$", Some(1), &mut sql, &mut args);
+/// assert_eq!("$1 OR $1", sql);
+/// assert_eq!(1, args.len());
+/// ```
+pub fn push_arg<'a,T: ?Sized>(arg: &'a T, bound_as: Option<usize>, param_prefix: &str, first_param_no: Option<usize>, sql: &mut String, args: &mut Vec<&'a T>) -> Option<usize> {
+    sql.push_str(param_prefix);
+    let param_no = match bound_as {
+        Some( _ ) => bound_as,
+        None => {
+            args.push(arg);
+            first_param_no.map(|first_param_no| first_param_no + args.len() - 1)
+        }
+    };
+    if let Some( n ) = param_no {
+        let _ = write!(sql, "{}", n);
+    }
+    param_no
+}
+
+/// Pushes items of a list argument into the query argument list and their placeholders into
+/// the SQL text.
+/// 
+/// This is a helper function that `include-sql` uses when it generates `into_sql_with_args`.
+/// It returns the range of the pushed items in the argument list, so the list can be bound
+/// again for another occurrence of the same parameter.
+/// 
+pub fn push_iter<'a,T: ?Sized>(arg: impl Iterator<Item = &'a T>, param_prefix: &str, first_param_no: Option<usize>, sql: &mut String, args: &mut Vec<&'a T>) -> Range<usize> {
+    let start = args.len();
+    args.extend(arg);
+    let end = args.len();
+    push_placeholders(end - start, param_prefix, first_param_no, start, sql);
+    start..end
+}
+
+/// Pushes tuples of a tuple list argument into the query argument list and their placeholders
+/// into the SQL text - `($1,$2),($3,$4)`.
+/// 
+/// This is a helper function that `include-sql` uses for `(ship_id, rank) IN (:pairs)` lists.
+/// Like [`push_iter`] it returns the range of the pushed items.
+/// 
+pub fn push_tuples<'a,T: ?Sized, A: TupleArg<'a, T, N>, const N: usize>(arg: impl Iterator<Item = A>, param_prefix: &str, first_param_no: Option<usize>, sql: &mut String, args: &mut Vec<&'a T>) -> Range<usize> {
+    let start = args.len();
+    for tuple in arg {
+        tuple.push_args(args);
+    }
+    let end = args.len();
+    push_tuple_placeholders((end - start) / N, N, param_prefix, first_param_no, start, sql);
+    start..end
+}
+
+/// Pushes placeholders of a list of tuples into the SQL text.
+/// 
+/// ```
+/// let mut sql = String::from("(ship_id, rank) IN (");
+/// include_sql_helper::push_tuple_placeholders(2, 2, "$", Some(1), 1, &mut sql);
+/// sql.push(')');
+/// assert_eq!("(ship_id, rank) IN (($2,$3),($4,$5))", sql);
+/// ```
+pub fn push_tuple_placeholders(num_tuples: usize, tuple_len: usize, param_prefix: &str, first_param_no: Option<usize>, num_args: usize, sql: &mut String) {
+    for i in 0..num_tuples {
+        if i > 0 {
             sql.push(',');
-            sql.push_str(param_prefix);
-            sql.push_str(&n.to_string());
         }
+        sql.push('(');
+        push_placeholders(tuple_len, param_prefix, first_param_no, num_args + i * tuple_len, sql);
+        sql.push(')');
     }
-    args.extend_from_slice(arg);
+}
+
+/// Value of a `(ship_id, rank) IN (:pairs)` parameter.
+/// 
+/// Argument structs accept any collection or iterator of tuples - or of arrays - of `N`
+/// argument references, or of references to them, as a tuple list argument:
+/// 
+/// ```rust,ignore
+/// pairs: &[ (&1 as &dyn ToSql, &"captain" as &dyn ToSql), (&2, &"ensign") ]
+/// pairs: crew.iter().map(|sailor| [&sailor.ship_id as &dyn ToSql, &sailor.rank])
+/// ```
+/// 
+pub trait TupleListArg<'a, T: ?Sized + 'a, const N: usize> {
+    /// Tuple of the list
+    type Item: TupleArg<'a, T, N>;
+    /// Iterator over the list tuples
+    type Iter: Iterator<Item = Self::Item>;
+
+    /// Returns an iterator over the list tuples.
+    fn into_tuple_iter(self) -> Self::Iter;
+}
+
+impl<'a, T: ?Sized + 'a, L, const N: usize> TupleListArg<'a, T, N> for L
+where L: IntoIterator, L::Item: TupleArg<'a, T, N>
+{
+    type Item = L::Item;
+    type Iter = L::IntoIter;
+
+    fn into_tuple_iter(self) -> Self::Iter {
+        self.into_iter()
+    }
+}
+
+/// Item of a tuple list argument - a tuple or an array of `N` argument references.
+pub trait TupleArg<'a, T: ?Sized, const N: usize> {
+    /// Pushes elements of the tuple into the query argument list.
+    fn push_args(self, args: &mut Vec<&'a T>);
+}
+
+impl<'a, T: ?Sized, A: ArgRef<'a, T>, const N: usize> TupleArg<'a, T, N> for [A; N] {
+    fn push_args(self, args: &mut Vec<&'a T>) {
+        args.extend(IntoIterator::into_iter(self).map(ArgRef::arg_ref));
+    }
+}
+
+impl<'a, 'b, T: ?Sized, A, const N: usize> TupleArg<'a, T, N> for &'b [A; N]
+where &'b A: ArgRef<'a, T>
+{
+    fn push_args(self, args: &mut Vec<&'a T>) {
+        args.extend(self.iter().map(ArgRef::arg_ref));
+    }
+}
+
+macro_rules! impl_tuple_arg {
+    ($len:literal => $($elem:ident . $idx:tt),+) => {
+        impl<'a, T: ?Sized, $($elem: ArgRef<'a, T>),+> TupleArg<'a, T, $len> for ($($elem,)+) {
+            fn push_args(self, args: &mut Vec<&'a T>) {
+                $( args.push(self.$idx.arg_ref()); )+
+            }
+        }
+
+        impl<'a, 'b, T: ?Sized, $($elem),+> TupleArg<'a, T, $len> for &'b ($($elem,)+)
+        where $(&'b $elem: ArgRef<'a, T>),+
+        {
+            fn push_args(self, args: &mut Vec<&'a T>) {
+                $( args.push((&self.$idx).arg_ref()); )+
+            }
+        }
+    };
+}
+
+impl_tuple_arg!(2 => A.0, B.1);
+impl_tuple_arg!(3 => A.0, B.1, C.2);
+impl_tuple_arg!(4 => A.0, B.1, C.2, D.3);
+impl_tuple_arg!(5 => A.0, B.1, C.2, D.3, E.4);
+impl_tuple_arg!(6 => A.0, B.1, C.2, D.3, E.4, F.5);
+
+/// Value of an `IN (:list)` parameter.
+/// 
+/// Argument structs accept any collection or iterator of `&dyn ToSql` - or of references to
+/// them - as a list argument. For example, all of these can be used as `ranks`:
+/// 
+/// ```rust,ignore
+/// ranks: &[ &"captain" as &dyn ToSql, &"midshipman" ]
+/// ranks: vec![ &"captain" as &dyn ToSql, &"midshipman" ]
+/// ranks: ids.iter().map(|id| id as &dyn ToSql)
+/// ```
+/// 
+pub trait ListArg<'a, T: ?Sized + 'a> {
+    /// Iterator over the list items
+    type Iter: Iterator<Item = &'a T>;
+
+    /// Returns an iterator over the list items.
+    fn into_arg_iter(self) -> Self::Iter;
+}
+
+impl<'a, T: ?Sized + 'a, L> ListArg<'a, T> for L
+where L: IntoIterator, L::Item: ArgRef<'a, T>
+{
+    type Iter = std::iter::Map<L::IntoIter, fn(L::Item) -> &'a T>;
+
+    fn into_arg_iter(self) -> Self::Iter {
+        self.into_iter().map(ArgRef::arg_ref)
+    }
+}
+
+/// Item of a list argument - either an argument reference or a reference to it.
+pub trait ArgRef<'a, T: ?Sized> {
+    /// Returns the argument reference.
+    fn arg_ref(self) -> &'a T;
+}
+
+impl<'a, T: ?Sized> ArgRef<'a, T> for &'a T {
+    fn arg_ref(self) -> &'a T {
+        self
+    }
+}
+
+impl<'a, T: ?Sized> ArgRef<'a, T> for &&'a T {
+    fn arg_ref(self) -> &'a T {
+        self
+    }
+}
+
+/// Returns the length of the text that placeholders of list arguments will take.
+/// 
+/// This is a helper function that `include-sql` uses when it generates `into_sql_with_args`
+/// to size the SQL buffer up front. `num_items` is the total number of list items and
+/// `last_param_no` is the largest placeholder number, which is `None` when the database uses
+/// unnumbered placeholders.
+/// 
+pub fn list_text_len(num_items: usize, param_prefix: &str, last_param_no: Option<usize>) -> usize {
+    let mut num_len = 0;
+    if let Some( mut n ) = last_param_no {
+        loop {
+            num_len += 1;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+    }
+    // each placeholder is followed by a comma, but the last one
+    num_items * (param_prefix.len() + num_len + 1)
 }
 
 /// Generates a macro that convers an argument struct into a slice that can be passed to
 /// database interfaces that require the latter.
 /// 
+/// Optional fields are followed by the type of their NULL value - `rank | &str`. Fields of
+/// parameter groups follow the field of their group - `context.tenant`.
+/// 
+/// Exported macros - `#[macro_export] select_ship_crew_args : [crate::queries] SelectShipCrew` -
+/// refer to the argument struct by the path of its module from the crate root, so other crates
+/// can use them.
+/// 
 #[macro_export]
 macro_rules! def_args {
-    ($s:tt => $macro_name:ident : $args_struct:ident = $($field:ident),+) => {
+    ($s:tt => $macro_name:ident : $args_struct:ident = $($field:ident $(. $group_field:ident)? $(| $null:ty)?),+) => {
+        #[allow(unused_macros)]
+        macro_rules! $macro_name {
+            ($s($s name:ident : $s value:expr),+) => {{
+                let args = $args_struct { $s( $s name : $s value ),+ };
+                &[ $(args.$field $(.$group_field)? $(.unwrap_or(&None::<$null>))?),+ ]
+            }};
+        }
+    };
+    ($s:tt => #[macro_export] $macro_name:ident : [$krate:tt $(:: $module:ident)*] $args_struct:ident = $($field:ident $(. $group_field:ident)? $(| $null:ty)?),+) => {
+        #[macro_export]
+        macro_rules! $macro_name {
+            ($s($s name:ident : $s value:expr),+) => {{
+                let args = $s $krate $(:: $module)* :: $args_struct { $s( $s name : $s value ),+ };
+                &[ $(args.$field $(.$group_field)? $(.unwrap_or(&None::<$null>))?),+ ]
+            }};
+        }
+    };
+}
+
+/// Generates a macro that fills the argument struct from the fields of another struct that have
+/// the names of the statement parameters - `select_ship_crew_args_from!(sailor)`. Fields of
+/// optional parameters are `Option`s and the ones of list parameters are collections.
+/// 
+/// This is a helper macro that `include-sql` uses when it generates argument structs. The fields
+/// of the argument struct are listed in three groups - required, optional and lists.
+/// 
+#[macro_export]
+macro_rules! def_args_from {
+    ($s:tt => $macro_name:ident : $args_struct:ident = [$($field:ident),*] [$($opt:ident),*] [$($lst:ident),*]) => {
+        #[allow(unused_macros)]
+        macro_rules! $macro_name {
+            ($s value:expr) => {{
+                let value = &$s value;
+                $args_struct {
+                    $( $field : &value.$field, )*
+                    $( $opt : match &value.$opt { Some( arg ) => Some(arg), None => None }, )*
+                    $( $lst : value.$lst.iter().map(|item| item as &dyn ToSql), )*
+                }
+            }};
+        }
+    };
+    ($s:tt => #[macro_export] $macro_name:ident : [$krate:tt $(:: $module:ident)*] $args_struct:ident = [$($field:ident),*] [$($opt:ident),*] [$($lst:ident),*]) => {
+        #[macro_export]
+        macro_rules! $macro_name {
+            ($s value:expr) => {{
+                use $s $krate $(:: $module)* :: ToSql;
+                let value = &$s value;
+                $s $krate $(:: $module)* :: $args_struct {
+                    $( $field : &value.$field, )*
+                    $( $opt : match &value.$opt { Some( arg ) => Some(arg), None => None }, )*
+                    $( $lst : value.$lst.iter().map(|item| item as &dyn ToSql), )*
+                }
+            }};
+        }
+    };
+}
+
+/// Generates a macro that converts an argument struct into a slice of `(name, value)` pairs
+/// for database interfaces that bind arguments by name - rusqlite `execute_named`, for example.
+/// 
+/// This is a helper macro that `include-sql` uses for the `bind = named` mode.
+/// 
+#[macro_export]
+macro_rules! def_named_args {
+    ($s:tt => $macro_name:ident : $args_struct:ident) => {
+        #[allow(unused_macros)]
+        macro_rules! $macro_name {
+            ($s($s name:ident : $s value:expr),+) => {
+                &$args_struct { $s( $s name : $s value ),+ }.named_args()
+            };
+        }
+    };
+    ($s:tt => #[macro_export] $macro_name:ident : [$krate:tt $(:: $module:ident)*] $args_struct:ident) => {
+        #[macro_export]
+        macro_rules! $macro_name {
+            ($s($s name:ident : $s value:expr),+) => {
+                &$s $krate $(:: $module)* :: $args_struct { $s( $s name : $s value ),+ }.named_args()
+            };
+        }
+    };
+}
+
+/// Generates a macro that builds diesel's `sql_query` of the statement with the arguments bound
+/// in the order of their placeholders.
+/// 
+/// This is a helper macro that `include-sql` uses for statements annotated with `-- diesel-bind:`
+/// or `-- diesel-row:`. Each field of the argument struct is followed by its SQL type.
+/// 
+#[macro_export]
+macro_rules! def_sql_query {
+    ($s:tt => $macro_name:ident : $sql:ident) => {
+        #[allow(unused_macros)]
+        macro_rules! $macro_name {
+            () => {
+                diesel::sql_query($sql)
+            };
+        }
+    };
+    ($s:tt => $macro_name:ident : $sql:ident, $args_struct:ident = $($field:ident $sql_type:ty),+) => {
         #[allow(unused_macros)]
         macro_rules! $macro_name {
             ($s($s name:ident : $s value:expr),+) => {{
                 let args = $args_struct { $s( $s name : $s value ),+ };
-                &[ $(args.$field),+ ]
+                diesel::sql_query($sql) $( .bind::<$sql_type, _>(args.$field) )+
             }};
         }
     };
@@ -0,0 +1,112 @@
+/// Schema migration annotated with `-- migration: 003 add_sailors_rank`.
+///
+/// `include-sql` collects migrations of the SQL file into the `MIGRATIONS` constant, ordered by
+/// their versions, and generates `migrate` that applies them via [`migrate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// Version of the migration
+    pub version: u32,
+    /// Name of the migration
+    pub name: &'static str,
+    /// SQL that applies the migration
+    pub up: &'static str,
+    /// SQL after the `-- down` line that reverts the migration
+    pub down: Option<&'static str>
+}
+
+/// Database interface that keeps track of the applied migrations.
+///
+/// Implementations usually record versions of the applied migrations in a table of their own,
+/// and execute the migration SQL and update the record in one transaction.
+pub trait MigrationRunner {
+    /// Error of the database interface
+    type Error;
+
+    /// Returns versions of the migrations that have been applied to the database.
+    fn applied_versions(&mut self) -> Result<Vec<u32>, Self::Error>;
+
+    /// Executes the `up` SQL of the migration and records the migration as applied.
+    fn apply(&mut self, migration: &Migration) -> Result<(), Self::Error>;
+
+    /// Executes the `down` SQL of the migration and removes its record.
+    fn revert(&mut self, migration: &Migration) -> Result<(), Self::Error>;
+}
+
+/// Reason of a failed migration.
+#[derive(Debug)]
+pub enum MigrationError<E> {
+    /// The database interface returned an error
+    Failed(E),
+    /// The migration with this version has no `-- down` SQL, thus it cannot be reverted
+    Irreversible(u32)
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for MigrationError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MigrationError::Failed(err) => write!(f, "{}", err),
+            MigrationError::Irreversible(version) => write!(f, "migration {} cannot be reverted", version)
+        }
+    }
+}
+
+impl<E: std::error::Error> std::error::Error for MigrationError<E> {}
+
+/// Applies migrations that have not been applied yet in the order of their versions and returns
+/// versions of the applied ones.
+///
+/// ```
+/// use include_sql_helper::{Migration, MigrationRunner, migrate};
+///
+/// const MIGRATIONS : &[Migration] = &[
+///     Migration { version: 1, name: "create_ships", up: "CREATE TABLE ships (id INTEGER)", down: None },
+///     Migration { version: 2, name: "add_ship_name", up: "ALTER TABLE ships ADD name TEXT", down: None },
+/// ];
+///
+/// struct Log(Vec<u32>);
+///
+/// impl MigrationRunner for Log {
+///     type Error = String;
+///     fn applied_versions(&mut self) -> Result<Vec<u32>, String> { Ok(self.0.clone()) }
+///     fn apply(&mut self, migration: &Migration) -> Result<(), String> {
+///         self.0.push(migration.version);
+///         Ok(())
+///     }
+///     fn revert(&mut self, migration: &Migration) -> Result<(), String> {
+///         self.0.retain(|&version| version != migration.version);
+///         Ok(())
+///     }
+/// }
+///
+/// let mut log = Log(vec![1]);
+/// assert_eq!(vec![2], migrate(&mut log, MIGRATIONS).unwrap());
+/// assert!(migrate(&mut log, MIGRATIONS).unwrap().is_empty());
+/// ```
+pub fn migrate<R: MigrationRunner + ?Sized>(runner: &mut R, migrations: &[Migration]) -> Result<Vec<u32>, MigrationError<R::Error>> {
+    let applied = runner.applied_versions().map_err(MigrationError::Failed)?;
+    let mut pending : Vec<&Migration> = migrations.iter().filter(|migration| !applied.contains(&migration.version)).collect();
+    pending.sort_by_key(|migration| migration.version);
+    let mut versions = Vec::with_capacity(pending.len());
+    for migration in pending {
+        runner.apply(migration).map_err(MigrationError::Failed)?;
+        versions.push(migration.version);
+    }
+    Ok(versions)
+}
+
+/// Reverts applied migrations with versions above `target`, the latest first, and returns
+/// versions of the reverted ones. Nothing is reverted when one of them has no `-- down` SQL.
+pub fn rollback<R: MigrationRunner + ?Sized>(runner: &mut R, migrations: &[Migration], target: u32) -> Result<Vec<u32>, MigrationError<R::Error>> {
+    let applied = runner.applied_versions().map_err(MigrationError::Failed)?;
+    let mut reverted : Vec<&Migration> = migrations.iter().filter(|migration| migration.version > target && applied.contains(&migration.version)).collect();
+    reverted.sort_by_key(|migration| std::cmp::Reverse(migration.version));
+    if let Some( migration ) = reverted.iter().find(|migration| migration.down.is_none()) {
+        return Err(MigrationError::Irreversible(migration.version));
+    }
+    let mut versions = Vec::with_capacity(reverted.len());
+    for migration in reverted {
+        runner.revert(migration).map_err(MigrationError::Failed)?;
+        versions.push(migration.version);
+    }
+    Ok(versions)
+}
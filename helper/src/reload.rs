@@ -0,0 +1,181 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use crate::scan::verbatim_ranges;
+
+/// Placeholders of the included statements, which the reloaded ones get too.
+///
+/// `include-sql` generates it for the `hot_reload` option from the placeholder prefix or the
+/// dialect of the include.
+#[derive(Debug, Clone, Copy)]
+pub struct Placeholders<'a> {
+    /// Text that each placeholder starts with - `$`, `?` or `:`
+    pub prefix: &'a str,
+    /// Number of the first placeholder, or `None` when placeholders are not numbered
+    pub first: Option<usize>,
+    /// Whether a repeated parameter reuses the number of its first placeholder
+    pub dedup: bool
+}
+
+/// Text of the reloaded statement and the names of the parameters it binds in the order of
+/// their arguments.
+#[doc(hidden)]
+pub type ReloadedStatement = (String, Vec<String>);
+
+/// Finds the statement in the SQL file text and replaces its parameters by placeholders.
+///
+/// This is what the `<statement>_sql()` functions of the `hot_reload` option do at run time.
+/// `include-sql` does it too when it expands the include, and only the statements, whose text
+/// it gets back, can be reloaded. Annotations, comment lines and the terminating semicolon are
+/// skipped.
+///
+/// ```
+/// use include_sql_helper::{Placeholders, reload_statement};
+///
+/// let sql = "-- name: select_ship_crew\nSELECT name FROM sailors WHERE ship_id = :ship AND rank <> :rank;\n";
+/// let placeholders = Placeholders { prefix: "$", first: Some(1), dedup: true };
+/// let (text, bound) = reload_statement(sql, "select_ship_crew", &placeholders).unwrap();
+/// assert_eq!("SELECT name FROM sailors WHERE ship_id = $1 AND rank <> $2", text);
+/// assert_eq!(vec!["ship", "rank"], bound);
+/// ```
+#[doc(hidden)]
+pub fn reload_statement(sql: &str, name: &str, placeholders: &Placeholders<'_>) -> Option<ReloadedStatement> {
+    let mut lines = Vec::new();
+    let mut found = false;
+    for line in sql.lines() {
+        if let Some( stmt_name ) = statement_name(line) {
+            if found {
+                break;
+            }
+            found = stmt_name == name;
+        } else if found && !line.trim_start().starts_with("--") {
+            lines.push(line);
+        }
+    }
+    if !found {
+        return None;
+    }
+    let text = lines.join("\n");
+    let text = text.trim().trim_end_matches(';').trim_end();
+    Some(replace_params(text, placeholders))
+}
+
+/// Returns the name of the statement without the kind suffix when the line starts it.
+fn statement_name(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix("--")?.trim_start().strip_prefix("name:")?;
+    let name = rest.split_whitespace().next()?;
+    Some(name.trim_end_matches('!').trim_end_matches('<'))
+}
+
+/// Replaces named parameters outside of comments and quoted text - the ones that `include-sql`
+/// finds too - by placeholders. `::` casts are not parameters.
+fn replace_params(text: &str, placeholders: &Placeholders<'_>) -> ReloadedStatement {
+    let verbatim = verbatim_ranges(text);
+    let mut sql = String::with_capacity(text.len());
+    let mut bound : Vec<String> = Vec::new();
+    let mut numbered : Vec<&str> = Vec::new();
+    let mut copied = 0;
+    let mut pos = 0;
+    while let Some( offset ) = text[pos..].find(':') {
+        let start = pos + offset;
+        pos = start + 1;
+        let name_len = text[pos..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(text.len() - pos);
+        let is_param = name_len > 0
+            && !text.as_bytes()[pos].is_ascii_digit()
+            && !text[..start].ends_with(':')
+            && !verbatim.iter().any(|range| range.contains(&start));
+        if !is_param {
+            continue;
+        }
+        let name = &text[pos..pos + name_len];
+        sql.push_str(&text[copied..start]);
+        sql.push_str(placeholders.prefix);
+        match placeholders.first {
+            Some( first ) => {
+                let index = match numbered.iter().position(|&numbered_name| numbered_name == name) {
+                    Some( index ) if placeholders.dedup => index,
+                    _ => {
+                        numbered.push(name);
+                        bound.push(name.to_string());
+                        numbered.len() - 1
+                    }
+                };
+                sql.push_str(&(first + index).to_string());
+            }
+            None => bound.push(name.to_string())
+        }
+        pos += name_len;
+        copied = pos;
+    }
+    sql.push_str(&text[copied..]);
+    (sql, bound)
+}
+
+/// Reloaded texts and the modification times of the files they were read from keyed by the
+/// file path and the statement name, and the texts that were ever reloaded.
+#[derive(Default)]
+struct Reloaded {
+    statements: HashMap<(&'static str, &'static str), (SystemTime, &'static str)>,
+    // texts are interned, so reverting an edit, or saving the file without changing the
+    // statement, reuses the text that was kept before instead of keeping another copy
+    texts: HashSet<&'static str>
+}
+
+static RELOADED : Mutex<Option<Reloaded>> = Mutex::new(None);
+
+/// Returns the current text of the statement in the SQL file, or the compiled text when the
+/// file cannot be read or the statement binds other arguments than the compiled one.
+///
+/// This is a helper function that the `<statement>_sql()` functions of the `hot_reload` option
+/// call in debug builds. The statement is read again only when the file was modified. Each
+/// distinct reloaded text is kept until the process exits, once no matter how many times it is
+/// reloaded. With the `tracing` feature the statements that fall back to the compiled text are
+/// reported as warning events.
+#[doc(hidden)]
+pub fn reloaded_text(file: &'static str, name: &'static str, placeholders: &Placeholders<'_>, bound: &[&str], compiled: &'static str) -> &'static str {
+    let modified = match fs::metadata(file).and_then(|meta| meta.modified()) {
+        Ok( modified ) => modified,
+        Err( _ ) => return compiled
+    };
+    let mut reloaded = RELOADED.lock().unwrap_or_else(|err| err.into_inner());
+    let reloaded = reloaded.get_or_insert_with(Reloaded::default);
+    if let Some( &(text_modified, text) ) = reloaded.statements.get(&(file, name)) {
+        if text_modified == modified {
+            return text;
+        }
+    }
+    let text = match fs::read_to_string(file).ok().and_then(|sql| reload_statement(&sql, name, placeholders)) {
+        Some( (text, text_bound) ) if text_bound == bound => {
+            if compiled.ends_with(';') { format!("{};", text) } else { text }
+        }
+        Some( _ ) => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(file, statement = name, "the statement binds other arguments now, the compiled text is used until it is rebuilt");
+            compiled.to_string()
+        }
+        None => {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(file, statement = name, "the statement is not found, the compiled text is used");
+            compiled.to_string()
+        }
+    };
+    let text = if text == compiled { compiled } else { reloaded.intern(text) };
+    reloaded.statements.insert((file, name), (modified, text));
+    text
+}
+
+impl Reloaded {
+    /// Returns the kept copy of the text, or keeps the text until the process exits when it
+    /// is new.
+    fn intern(&mut self, text: String) -> &'static str {
+        match self.texts.get(text.as_str()) {
+            Some( &interned ) => interned,
+            None => {
+                let interned : &'static str = Box::leak(text.into_boxed_str());
+                self.texts.insert(interned);
+                interned
+            }
+        }
+    }
+}
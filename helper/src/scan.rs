@@ -0,0 +1,142 @@
+use std::ops::Range;
+
+/// Returns byte ranges of the statement text that is not parsed - comments and quoted text,
+/// i.e. string literals, quoted identifiers and dollar quoted bodies. The database reads quoted
+/// text as is, thus `'12:30'` and the body of `DO $$ ... $$` have no parameters. Quotes in the
+/// text are escaped by doubling them - `'it''s'`.
+///
+/// This is the scanner that `include-sql` finds parameters with when it expands the include
+/// and that the `hot_reload` option uses when it reloads statements at run time.
+///
+/// ```
+/// use include_sql_helper::verbatim_ranges;
+///
+/// let text = "SELECT ':id' /* :id */, :id";
+/// assert_eq!(vec![7..12, 13..22], verbatim_ranges(text));
+/// ```
+#[doc(hidden)]
+pub fn verbatim_ranges(stmt_text: &str) -> Vec<Range<usize>> {
+    VerbatimRanges { text: stmt_text, pos: 0 }.collect()
+}
+
+/// Iterator over the verbatim ranges of the statement text in the text order. Scanning does not
+/// allocate, thus the debug assertions of the generated code can use it on the hot path.
+struct VerbatimRanges<'a> {
+    text: &'a str,
+    pos: usize
+}
+
+impl Iterator for VerbatimRanges<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        let stmt_text = self.text;
+        let bytes = stmt_text.as_bytes();
+        let mut pos = self.pos;
+        while pos < bytes.len() {
+            match bytes[pos] {
+                quote @ (b'\'' | b'"' | b'`') => {
+                    let start = pos;
+                    // Postgres escape strings - `E'it\'s'` - escape quotes with backslashes too
+                    let escapes = quote == b'\'' && is_escape_string_prefix(&bytes[..pos]);
+                    pos += 1;
+                    loop {
+                        match bytes[pos..].iter().position(|&b| b == quote || (escapes && b == b'\\')) {
+                            Some( end ) if bytes[pos + end] == b'\\' => pos = (pos + end + 2).min(bytes.len()),
+                            Some( end ) if bytes.get(pos + end + 1) == Some(&quote) => pos += end + 2,
+                            Some( end ) => {
+                                pos += end + 1;
+                                break;
+                            }
+                            None => {
+                                pos = bytes.len();
+                                break;
+                            }
+                        }
+                    }
+                    self.pos = pos;
+                    return Some(start..pos);
+                }
+                b'$' => {
+                    // `$$` or `$body$` opens a dollar quoted text that ends with the same tag
+                    let tag_len = stmt_text[pos + 1..].find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(0);
+                    if stmt_text[pos + 1 + tag_len..].starts_with('$') {
+                        let tag = &stmt_text[pos..pos + tag_len + 2];
+                        let body_start = pos + tag.len();
+                        let end = stmt_text[body_start..].find(tag).map_or(bytes.len(), |end| body_start + end + tag.len());
+                        self.pos = end;
+                        return Some(pos..end);
+                    }
+                    pos += 1;
+                }
+                b'-' if bytes.get(pos + 1) == Some(&b'-') => {
+                    let end = stmt_text[pos..].find('\n').map_or(bytes.len(), |end| pos + end);
+                    self.pos = end;
+                    return Some(pos..end);
+                }
+                b'/' if bytes.get(pos + 1) == Some(&b'*') => {
+                    let end = stmt_text[pos + 2..].find("*/").map_or(bytes.len(), |end| pos + 2 + end + 2);
+                    self.pos = end;
+                    return Some(pos..end);
+                }
+                _ => pos += 1
+            }
+        }
+        self.pos = pos;
+        None
+    }
+}
+
+/// Returns whether the text before a quote ends with the `E` prefix of an escape string - a
+/// standalone `E`, not the last letter of a word.
+fn is_escape_string_prefix(before: &[u8]) -> bool {
+    match before {
+        [.., prev, b'E' | b'e'] => !(prev.is_ascii_alphanumeric() || *prev == b'_'),
+        [b'E' | b'e'] => true,
+        _ => false
+    }
+}
+
+/// Returns the number of arguments that the placeholders of the statement text bind - the
+/// numbers from the lowest placeholder number to the highest one, or the number of placeholders
+/// when they are not numbered. Placeholders in comments and quoted text are not counted.
+///
+/// This is a helper function for the debug assertions of the generated code, which check that
+/// statements bind as many arguments as their text assembled at run time has placeholders. It
+/// does not allocate, so the assertions do not change how much argument conversion allocates.
+///
+/// ```
+/// use include_sql_helper::placeholder_count;
+///
+/// assert_eq!(2, placeholder_count("SELECT $1, $2, $1, '$3'", "$", true));
+/// assert_eq!(3, placeholder_count("SELECT ?, ?, ? -- ?", "?", false));
+/// ```
+#[doc(hidden)]
+pub fn placeholder_count(sql: &str, param_prefix: &str, numbered: bool) -> usize {
+    let mut verbatim = VerbatimRanges { text: sql, pos: 0 }.peekable();
+    let mut count = 0;
+    let mut numbers : Option<(usize, usize)> = None;
+    for (pos, _) in sql.match_indices(param_prefix) {
+        // ranges follow in the text order, thus the ones that end before the placeholder are done
+        while verbatim.peek().is_some_and(|range| range.end <= pos) {
+            verbatim.next();
+        }
+        if verbatim.peek().is_some_and(|range| range.contains(&pos)) {
+            continue;
+        }
+        if !numbered {
+            count += 1;
+            continue;
+        }
+        let digits = &sql[pos + param_prefix.len()..];
+        let digits = &digits[..digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len())];
+        if let Ok( number ) = digits.parse::<usize>() {
+            numbers = Some(numbers.map_or((number, number), |(lowest, highest)| (lowest.min(number), highest.max(number))));
+        }
+    }
+    match numbers {
+        _ if !numbered => count,
+        Some( (lowest, highest) ) => highest - lowest + 1,
+        None => 0
+    }
+}
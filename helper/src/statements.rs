@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+use crate::StatementKind;
+
+/// Statement included by `include-sql` that is registered in the binary's statement inventory.
+///
+/// This is only available with the `inventory` feature.
+///
+#[derive(Debug)]
+pub struct Statement {
+    /// Name of the statement as it is defined in the SQL file
+    pub name: &'static str,
+    /// Kind of the statement
+    pub kind: StatementKind,
+    /// Whether the statement is annotated with `-- idempotent`, i.e. can be safely retried
+    pub idempotent: bool,
+    /// Path of the SQL file from which the statement was included
+    pub file: &'static str,
+    /// Preprocessed text of the statement
+    pub text: &'static str,
+    /// FNV-1a hash of the statement text
+    pub hash: u64
+}
+
+inventory::collect!(Statement);
+
+/// Returns an iterator over all statements that were included into the binary.
+///
+pub fn statements() -> impl Iterator<Item = &'static Statement> {
+    inventory::iter::<Statement>.into_iter()
+}
+
+/// Writes all statements that were included into the binary.
+///
+pub fn dump_statements(out: &mut impl Write) -> io::Result<()> {
+    for stmt in statements() {
+        writeln!(out, "-- name: {}", stmt.name)?;
+        writeln!(out, "-- kind: {:?}", stmt.kind)?;
+        if stmt.idempotent {
+            writeln!(out, "-- idempotent")?;
+        }
+        writeln!(out, "-- file: {}", stmt.file)?;
+        writeln!(out, "-- hash: {:016x}", stmt.hash)?;
+        writeln!(out, "{}", stmt.text)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Prints all included statements and exits the process when the `flag` is one of
+/// the command line arguments.
+///
+/// ```rust,no_run
+/// include_sql_helper::dump_statements_on_flag("--dump-sql");
+/// ```
+///
+pub fn dump_statements_on_flag(flag: &str) {
+    if std::env::args().skip(1).any(|arg| arg == flag) {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        let code = match dump_statements(&mut out) {
+            Ok(_) => 0,
+            Err(_) => 1
+        };
+        std::process::exit(code);
+    }
+}
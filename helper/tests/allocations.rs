@@ -0,0 +1,145 @@
+//! Allocations that `into_sql_with_args` makes for representative statements.
+//!
+//! Argument conversion is on the hot path of every executed statement. These tests keep the
+//! number of allocations it makes from growing silently - with the number of list items in
+//! particular.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::cell::Cell;
+use include_sql::inline_sql;
+use include_sql_helper::IntoSqlWithArgs;
+
+struct CountingAllocator;
+
+thread_local! {
+    // tests run in parallel, thus each thread counts its own allocations
+    static ALLOCATIONS : Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR : CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations, including reallocations, that `f` has made.
+fn allocations<R>(f: impl FnOnce() -> R) -> (usize, R) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let res = f();
+    let after = ALLOCATIONS.with(Cell::get);
+    (after - before, res)
+}
+
+/// Converts arguments the way generic wrappers around database interfaces do.
+fn sql_with_args<'a, A: IntoSqlWithArgs<'a, dyn ToSql + 'a>>(args: A) -> (Cow<'static, str>, Vec<&'a (dyn ToSql + 'a)>) {
+    args.into_sql_with_args()
+}
+
+pub trait ToSql {}
+
+impl ToSql for &str {}
+impl ToSql for i32 {}
+
+inline_sql!("
+    -- name: select_sailor
+    SELECT name FROM sailors WHERE ship_id = :ship AND name = :name AND rank = :rank AND age > :age AND active = :active
+
+    -- name: select_sailors_by_rank
+    SELECT name FROM sailors WHERE ship_id = :ship AND rank IN (:ranks)
+
+    -- name: select_sailors_by_ranks_twice
+    SELECT name FROM sailors WHERE ship_id = :ship AND (rank IN (:ranks) OR prev_rank IN (:ranks))
+", "$");
+
+fn list_items(values: &[i32]) -> Vec<&dyn ToSql> {
+    values.iter().map(|value| value as &dyn ToSql).collect()
+}
+
+#[test]
+fn statement_without_lists() {
+    let (num_allocs, (sql, args)) = allocations(|| {
+        sql_with_args(SelectSailor { ship: &1, name: &"Riker", rank: &"commander", age: &30, active: &1 })
+    });
+    assert_eq!(SELECT_SAILOR, sql);
+    assert_eq!(5, args.len());
+    assert!(num_allocs <= 1, "{} allocations", num_allocs);
+}
+
+#[test]
+fn statement_with_list() {
+    let values : Vec<i32> = (0..100).collect();
+    let ranks = list_items(&values);
+    let (num_allocs, (sql, args)) = allocations(|| {
+        sql_with_args(SelectSailorsByRank { ship: &1, ranks: ranks.iter() })
+    });
+    assert!(sql.ends_with("$100,$101)"), "{}", sql);
+    assert_eq!(101, args.len());
+    assert!(num_allocs <= 2, "{} allocations", num_allocs);
+}
+
+#[test]
+fn statement_with_repeated_list() {
+    let values : Vec<i32> = (0..100).collect();
+    let ranks = list_items(&values);
+    let (num_allocs, (sql, args)) = allocations(|| {
+        sql_with_args(SelectSailorsByRanksTwice { ship: &1, ranks: &ranks })
+    });
+    assert!(sql.ends_with("$100,$101))"), "{}", sql);
+    assert_eq!(101, args.len());
+    // placeholders of the first occurrence are saved for the second one
+    assert!(num_allocs <= 3, "{} allocations", num_allocs);
+}
+
+mod unnumbered {
+    use super::*;
+
+    inline_sql!("
+        -- name: select_sailors_by_ranks_unnumbered
+        SELECT name FROM sailors WHERE (rank IN (:ranks) OR prev_rank IN (:ranks)) AND age > :age
+    ", "?", numbering = none);
+
+    #[test]
+    fn statement_with_unnumbered_placeholders() {
+        let values : Vec<i32> = (0..100).collect();
+        let ranks = list_items(&values);
+        let (num_allocs, (sql, args)) = allocations(|| {
+            sql_with_args(SelectSailorsByRanksUnnumbered { ranks: ranks.iter(), age: &30 })
+        });
+        assert!(sql.ends_with("?,?)) AND age > ?"), "{}", sql);
+        assert_eq!(201, args.len());
+        assert!(num_allocs <= 2, "{} allocations", num_allocs);
+    }
+}
+
+mod generic {
+    use super::*;
+
+    inline_sql!("
+        -- name: select_sailors_by_rank_generic
+        SELECT name FROM sailors WHERE ship_id = :ship AND rank IN (:ranks)
+    ", "$", args = generic);
+
+    #[test]
+    fn statement_with_generic_args() {
+        let values : Vec<i32> = (0..100).collect();
+        let stmt_args = SelectSailorsByRankGeneric { ship: 1, ranks: &values };
+        let (num_allocs, (sql, args)) = allocations(|| stmt_args.into_sql_with_args());
+        assert!(sql.ends_with("$100,$101)"), "{}", sql);
+        assert_eq!(101, args.len());
+        assert!(num_allocs <= 2, "{} allocations", num_allocs);
+    }
+}
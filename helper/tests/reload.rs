@@ -0,0 +1,62 @@
+//! Statements that the `<statement>_sql()` functions of the `hot_reload` option read from the
+//! SQL file when it changes.
+
+use std::fs::{self, File};
+use std::time::{Duration, SystemTime};
+use include_sql_helper::{Placeholders, reload_statement, reloaded_text};
+
+const COMPILED : &str = "SELECT name FROM sailors WHERE ship_id = $1";
+const PLACEHOLDERS : Placeholders<'static> = Placeholders { prefix: "$", first: Some(1), dedup: true };
+
+fn write_sql(file: &str, sql: &str, modified: SystemTime) {
+    fs::write(file, sql).unwrap();
+    File::options().write(true).open(file).unwrap().set_modified(modified).unwrap();
+}
+
+#[test]
+fn changed_statements_are_reloaded() {
+    let file = std::env::temp_dir().join(format!("include-sql-reload-{}.sql", std::process::id()));
+    let file : &'static str = Box::leak(file.to_str().unwrap().to_string().into_boxed_str());
+    let modified = SystemTime::now();
+
+    write_sql(file, "-- name: select_ship_crew\nSELECT name FROM sailors WHERE ship_id = :ship\n", modified);
+    assert_eq!(COMPILED, reloaded_text(file, "select_ship_crew", &PLACEHOLDERS, &["ship"], COMPILED));
+
+    write_sql(file, "-- name: select_ship_crew\nSELECT name, rank FROM sailors WHERE ship_id = :ship;\n", modified + Duration::from_secs(1));
+    assert_eq!("SELECT name, rank FROM sailors WHERE ship_id = $1", reloaded_text(file, "select_ship_crew", &PLACEHOLDERS, &["ship"], COMPILED));
+
+    // the statement binds other arguments than the compiled code passes
+    write_sql(file, "-- name: select_ship_crew\nSELECT name FROM sailors WHERE rank = :rank\n", modified + Duration::from_secs(2));
+    assert_eq!(COMPILED, reloaded_text(file, "select_ship_crew", &PLACEHOLDERS, &["ship"], COMPILED));
+
+    fs::remove_file(file).unwrap();
+    assert_eq!(COMPILED, reloaded_text(file, "select_ship_crew", &PLACEHOLDERS, &["ship"], COMPILED));
+}
+
+#[test]
+fn quoted_text_and_comments_are_not_reloaded_as_parameters() {
+    let sql = "-- name: update_ship_note\nUPDATE ships /* :ship */ SET note = E'it\\'s :old' || $$:body$$, name = :name::text WHERE id = :ship\n";
+    let placeholders = Placeholders { prefix: "$", first: Some(1), dedup: true };
+    let (text, bound) = reload_statement(sql, "update_ship_note", &placeholders).unwrap();
+    assert_eq!("UPDATE ships /* :ship */ SET note = E'it\\'s :old' || $$:body$$, name = $1::text WHERE id = $2", text);
+    assert_eq!(vec!["name", "ship"], bound);
+}
+
+#[test]
+fn reverted_statements_reuse_their_reloaded_text() {
+    let file = std::env::temp_dir().join(format!("include-sql-revert-{}.sql", std::process::id()));
+    let file : &'static str = Box::leak(file.to_str().unwrap().to_string().into_boxed_str());
+    let modified = SystemTime::now();
+
+    write_sql(file, "-- name: select_ship_crew\nSELECT name, rank FROM sailors WHERE ship_id = :ship\n", modified);
+    let first = reloaded_text(file, "select_ship_crew", &PLACEHOLDERS, &["ship"], COMPILED);
+
+    write_sql(file, "-- name: select_ship_crew\nSELECT rank, name FROM sailors WHERE ship_id = :ship\n", modified + Duration::from_secs(1));
+    assert_eq!("SELECT rank, name FROM sailors WHERE ship_id = $1", reloaded_text(file, "select_ship_crew", &PLACEHOLDERS, &["ship"], COMPILED));
+
+    write_sql(file, "-- name: select_ship_crew\nSELECT name, rank FROM sailors WHERE ship_id = :ship\n", modified + Duration::from_secs(2));
+    let reverted = reloaded_text(file, "select_ship_crew", &PLACEHOLDERS, &["ship"], COMPILED);
+    assert_eq!(first.as_ptr(), reverted.as_ptr());
+
+    fs::remove_file(file).unwrap();
+}
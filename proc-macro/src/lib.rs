@@ -47,21 +47,191 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use proc_macro2::Span;
-use syn::{parse_macro_input, Token, Lit, LitStr, Ident, Expr, Error};
-use syn::parse::{Parse, ParseStream, Result};
-use syn::spanned::Spanned;
-use quote::quote;
+use syn::{parse_macro_input, ItemMod};
 
-mod sql;
+use include_sql_codegen::{IncludeSql, InlineSql};
 
 /// Includes SQL from the provided file.
 ///
 /// This macro needs 2 arguments:
-/// - Path to the SQL file. The path should be defined relative to the package root.
+/// - Path to the SQL file. The path should be defined relative to the package root, or to the
+///   directory that the `root` option names.
 /// - Prefix that will the database interface uses to tag positional SQL parameters.
-///   For example, it would be `"?"` for SQLite, `"$"` for Postgresql or `":"` for
-///   Oracle.
+///   For example, it would be `"?"` for SQLite, `"$"` for Postgresql, `":"` for
+///   Oracle or `"@p"` for MSSQL.
+///
+/// Both can be `concat!` and `env!` of literals instead of literal strings -
+/// `concat!(env!("CARGO_MANIFEST_DIR"), "/sql/crew.sql")`. The environment variables are read
+/// when the macro expands, and changing them does not make cargo rebuild the crate.
+///
+/// Several files can be included at once - `["src/crew.sql", "src/ships.sql"]`. They share the
+/// prefix and the options, and statement names must be unique across all of them. Such an
+/// include cannot have `dialects` or the `module` option, which need a single file.
+///
+/// Instead of the prefix the second argument can name a database preset - `dialect = postgres`.
+/// Presets know which placeholder style the backend expects:
+///
+/// | Dialect    | Placeholders     |
+/// |------------|------------------|
+/// | `postgres` | `$1`, `$2`, ...  |
+/// | `sqlite`   | `?1`, `?2`, ...  |
+/// | `mysql`    | `?`, `?`, ...    |
+/// | `oracle`   | `:1`, `:2`, ...  |
+/// | `mssql`    | `@p1`, `@p2`, ...|
+///
+/// Backends with numbered placeholders bind each named parameter once, even when the statement
+/// uses it several times. MySQL placeholders are not numbered, thus the argument of a named
+/// parameter is bound for each occurrence of that parameter. The `mysql` preset also includes
+/// `LOAD DATA` and `LOAD XML` statements as they are, since MySQL does not prepare them. Session
+/// variables - `@row_no` - and the `:=` assignment are not parameters in any dialect.
+///
+/// The SQL that several databases execute can name all their presets - `dialects = ["sqlite",
+/// "postgres"]`. Each dialect then gets the statements of the file, where the statements of its
+/// variant of the file - `src/crew.postgres.sql` - replace the ones with the same names. The
+/// cargo feature of the package named after the dialect selects it at compile time, and the first
+/// dialect is used when none of the features of the others is enabled.
+///
+/// A single statement can be gated too - `-- cfg: feature = "admin"` puts the `cfg` attribute on
+/// all items generated for it. A statement with several `-- cfg:` lines needs all of them.
+///
+/// These arguments might be followed by the optional `name = value` ones:
+/// - `macro_prefix = "crew_"` - the prefix is inserted before the statement name in the names of
+///   the generated argument macros - `using_crew_insert_row_args!` and `crew_insert_row_args!`.
+///   Macros are not scoped by modules, thus this avoids collisions when several SQL files contain
+///   statements with the same name.
+/// - `schema = "sql/schema.json"` - path, relative to the package root, to the schema snapshot.
+///   Statement tables, columns and parameter counts are checked against the snapshot. This needs
+///   the `schema-snapshot` feature.
+/// - `dedup_params = false` - with numbered placeholders each occurrence of a repeated parameter
+///   gets its own number and the argument is bound for each of them. By default repeated
+///   parameters reuse the number of their first occurrence and are bound once.
+/// - `numbering = 0` - number of the first placeholder. Placeholders are numbered from 1 unless
+///   the database interface counts them from another number.
+/// - `numbering = none` - placeholders are not numbered - `?`, `?`, ... - and the argument of
+///   a repeated parameter is bound for each of its occurrences. This is the mode of the `mysql`
+///   preset for interfaces that need a different prefix or are not covered by the presets.
+/// - `args = generic` - fields of the argument structs get their own generic types bounded by
+///   `ToSql` - `struct SelectShipCrew<Ship: ToSql> { ship: Ship }` - instead of being `&dyn ToSql`
+///   references. Structs without list parameters implement `args()` that returns an array of
+///   arguments. Structs with list parameters implement `into_sql_with_args(&self)`. Neither
+///   argument macros nor `IntoIterator` and `IntoSqlWithArgs` are generated in this mode.
+/// - `arg_bounds = Sync + Send` - additional bounds of the generic field types and of the
+///   argument trait objects - `&(dyn ToSql + Sync + Send)` - for the `args = generic` mode.
+/// - `functions = rusqlite` or `functions = postgres` - for each statement a function, named
+///   after the statement, that takes the connection and the statement arguments, and executes
+///   the statement - `select_ship_crew(&conn, &ship_id, |row| row.get(0))`. rusqlite functions
+///   take a rusqlite `Connection` and, for queries and `<!` statements, a closure that maps rows.
+///   postgres functions take any postgres `GenericClient` - a `Client` or a `Transaction` - and
+///   return rows, like the tokio-postgres ones do. Functions for postgres imply
+///   `args = generic` with the `Sync` bound. These need the `rusqlite` or `postgres` feature.
+/// - `functions = tokio_postgres` - for each statement an async function, named after the
+///   statement, prepares and executes it via `include_sql_helper::AsyncClient` - a tokio-postgres
+///   `Client` or `include_sql_helper::CachedClient` that prepares each statement once. Queries
+///   return rows, `<!` statements - one row, and `!` ones - the number of modified rows. This
+///   implies `args = generic` with the `Sync` bound. This needs the `tokio-postgres` feature.
+/// - `functions = executor` - for each statement a function, named after the statement, that
+///   takes any `include_sql_helper::Executor` and the argument struct, and executes the statement
+///   via the executor - `select_ship_crew(&mut conn, SelectShipCrew { ship: &ship_id })`. The
+///   functions only depend on the trait, which the helper implements for rusqlite and postgres
+///   behind the features of the same names. With `args = generic` the struct is passed by
+///   reference and the executor binds `dyn ToSql` with the `arg_bounds`.
+/// - `bind = named` - the statement text is left as is and arguments are bound by parameter
+///   names. Argument structs implement `named_args()` that returns an array of `(name, value)`
+///   pairs, which can be passed to Oracle `query_named` or rusqlite `query_named`. Argument
+///   macros return slices of these pairs. Names are the
+///   parameter names prefixed by the second argument - `""` for Oracle and `":"` for SQLite - or
+///   by the one that `dialect = oracle` or `dialect = sqlite` selects. `IN (:list)` parameters
+///   cannot be bound by name.
+/// - `vars = { "schema" = "analytics", "suffix" = "_v2" }` - template variables. Each
+///   `{{schema}}` in the statements is replaced by the value of the variable before the SQL is
+///   parsed. Variables that statements use must be declared and each declared variable must be
+///   used. Without `vars` the SQL is included as is. `${SCHEMA}` is replaced by the value of the
+///   `SCHEMA` variable too, or, when `vars` does not declare it, by the value of the `SCHEMA`
+///   environment variable at the time the macro is expanded.
+/// - `module = true` - the generated items are placed into a module named after the SQL file -
+///   `crew::SELECT_SHIP_CREW` for `src/crew.sql` - and are visible to its parent, like the items
+///   of [`include_sql_mod`](attr.include_sql_mod.html). Statement names then only need to be
+///   unique in their file.
+/// - `const_names = verbatim` - statement constants keep the statement names as they are -
+///   `select_ship_crew` and `select_ship_crew_kind` - instead of `SELECT_SHIP_CREW`. Functions
+///   are named after the statements too, thus with `functions` this needs a `const_prefix` or
+///   a `macro_prefix`.
+/// - `const_prefix = "SQL_"` - the prefix is inserted before the names of the statement
+///   constants and of the constants that accompany them - `SQL_SELECT_SHIP_CREW_KIND`.
+/// - `sql_items = static` - statement texts are `static` items instead of constants, so each
+///   text has a single address in the binary.
+/// - `row_trait = db::RowColumns` - path to the trait of the database rows, which row structs use
+///   to implement `from_row<R: RowColumns + ?Sized>(row: &R)`. The trait declares `type Error` and
+///   `fn get_column<T: ...>(&self, index: usize) -> Result<T, Self::Error>`, where `T` is bound
+///   by whatever trait the database interface converts column values with.
+/// - `registry = true` - the `SQL_STATEMENTS` constant, a `&[(&str, &str)]` of the names and texts
+///   of all statements in the file, so they can be prepared at startup or listed at runtime. With
+///   the `macro_prefix` its name gets the prefix too - `CREW_SQL_STATEMENTS`.
+/// - `namespace = "crew"` - prefixes every generated name, so several files that have statements
+///   with the same names can be included into one module. Constants get `CREW_` before the
+///   `const_prefix`, macros and functions get `crew_` before the `macro_prefix`, and structs
+///   get `Crew` - `CREW_SELECT_SHIP_CREW`, `crew_select_ship_crew_args!` and `CrewSelectShipCrew`.
+/// - `comments = keep` - whole-line comments inside the statement text stay in the constant, so
+///   they are visible where the database shows the statement. By default (`inline`) only the
+///   comments that follow SQL on the same line stay. `comments = strip` removes all `--` and
+///   `/* */` comments. Comments in string literals are not comments and always stay.
+/// - `semicolons = strip` - the semicolon that terminates a statement is removed, as some
+///   drivers reject it. Scripts, migrations and PL/SQL blocks keep it. `dialect = oracle` strips
+///   semicolons unless `semicolons = keep` says otherwise.
+/// - `minify = true` - statement constants are collapsed into a single line - comments are
+///   removed and each run of whitespace becomes a single space. String literals stay as they are.
+/// - `tag = "service=crew"` - query tags that are appended to each statement text as a comment
+///   together with the statement name - `/* service=crew,stmt=select_ship_crew */` - so queries in
+///   server-side logs can be attributed to the application. Statements add their own tags with
+///   `-- tag: team=fleet`, and only statements with tags are tagged.
+/// - `attrs = [allow(dead_code)]` - attributes of the constants, structs, macros and functions
+///   that are generated for each statement. Statements add their own with `-- attr: deprecated`.
+///   Items that refer to deprecated statements allow it, so only the code that uses them warns.
+/// - `export = crate::queries` - the generated items are public and the argument macros are
+///   `#[macro_export]`ed, so a library crate can provide the statements to other crates. The path
+///   is the one of the module with the generated items from the crate root, which the exported
+///   macros use to refer to the argument structs - `$crate::queries::SelectShipCrew`.
+/// - `debug_args = true` - argument structs implement `Debug`, so statement arguments can be
+///   logged. `&dyn ToSql` values cannot be printed, thus only whether they are provided is -
+///   `SelectShipCrew { ship: .., rank: None }`. Generic arguments are printed when their types
+///   implement `Debug`.
+/// - `serialize_args = true` - argument structs implement `serde::Serialize`, so arguments can be
+///   saved with the executed statement. Fields are serialized under the parameter names when
+///   their types implement `Serialize`. This needs `args = generic` and the `serde` feature.
+/// - `builder = true` - argument structs get builders - `SelectShipCrew::builder().ship(&id)
+///   .ranks(&ranks).build()` - with a method for each parameter. Arguments of optional parameters
+///   and conditional blocks can be left out, and `build` returns `ArgsError::Missing` when one of
+///   the other arguments is. This needs `args = refs`.
+/// - `args_trait = true` - each statement gets the trait of its arguments - `SelectShipCrewArgs` -
+///   with a method that returns the argument of each parameter and `bind_args`, which returns
+///   them in the order they are bound. The argument struct implements it, and so can the types
+///   of the application, which are then bound without the struct. Statements with lists,
+///   conditional blocks or identifier parameters do not get one. This needs `args = refs` and
+///   positional binding.
+/// - `generate = explain` - the EXPLAIN (or, with `explain_analyze`, EXPLAIN ANALYZE) constant
+///   of every query and data modifying statement, as if each of them was annotated with
+///   `-- generate:`. Statements with IN lists or conditional blocks, whose text is only known at
+///   run time, are skipped.
+/// - `instrument = true` - generated functions execute statements inside the tracing span of the
+///   statement. This needs the `functions` option and the `tracing` feature, which generates the
+///   span function of each statement - `select_ship_crew_span()`.
+/// - `unused_in = "src"` - path, relative to the package root, to the directory with the code
+///   that uses the statements. Statements whose constants, structs, macros and functions none of
+///   the `.rs` files in the directory and its subdirectories mentions are reported as errors.
+/// - `manifest = "target/sql/crew.json"` - path to the JSON manifest of the statements, which the
+///   macro writes for the tools that review the SQL the application runs. Each statement is
+///   listed with its name, file and line, kind, parameters in the order of their placeholders,
+///   the first placeholder - `$1`, `?` or `:name` - its fingerprint as a hex string and its text.
+/// - `root = "file"` - directory that relative paths - of the SQL file, `schema`, `unused_in` and
+///   `manifest` - are resolved against. It is `"crate"` for the package root, `"workspace"` for
+///   the root of the workspace, or `"file"` for the directory of the source file with the macro
+///   call, like `include_str!` does. Without it paths are relative to the directory where the
+///   compiler runs, which cargo sets to the workspace root for workspace members.
+/// - `hot_reload = true` - each statement gets the `select_ship_crew_sql()` function that returns
+///   its text. In debug builds the function reads the statement from the SQL file again when the
+///   file changes, so the SQL can be tuned without rebuilding. Only statements whose parameters
+///   are bound positionally, without IN lists, conditional blocks and template variables, are
+///   reloaded. Constants, argument structs and generated functions keep the compiled text.
 ///
 /// There is an additional requirement. The code generated by the `include-sql` assumes that
 /// the database interface has defined and implemented some trait to convert argument values
@@ -86,12 +256,166 @@ mod sql;
 /// use oci_rs::types::ToSqlValue as ToSql;
 /// ```
 ///
+/// `{{build.version}}` placeholders in the statement text are replaced with the package version,
+/// and `{{build.<name>}}` ones with the values of the `BUILD_<NAME>` environment variables.
+///
+/// The file can define its own template variables before the first statement -
+/// `-- define: table_prefix = app_` - which `{{table_prefix}}` in the statements is replaced by.
+/// A variable of the `vars` option with the same name takes precedence over the constant.
+///
+/// Text that several statements share can be defined once as a fragment - `-- fragment: crew_columns`
+/// followed by the text. Statements include it either in place - `SELECT :{crew_columns} FROM ...` -
+/// or as whole lines - `-- use: crew_columns`. Fragments are expanded before parameters are parsed,
+/// thus they can have parameters too. They must be defined above the statements that use them.
+///
+/// Numbered placeholders are assigned in the order of the first occurrence of each parameter,
+/// thus parameters added after the existing ones never renumber the latter. The order can also
+/// be set explicitly - `-- param-order: ship, rank` - in which case it must list every parameter
+/// but the `IN (:list)` ones, which are always numbered after all other parameters.
+///
+/// Files that must only contain static prepared statements can say so before their first
+/// statement - `-- strict: static-only`. Statements with `IN (:list)` parameters, which text is
+/// built at run time, fail the compilation in these files.
+///
+/// Lines between `-- if :rank` and `-- end` are a conditional block, which is only included when
+/// the argument of its parameter is provided. The parameter becomes an `Option<&dyn ToSql>` field,
+/// and the text of the statement is assembled by `into_sql_with_args`, which numbers placeholders
+/// of the included blocks. The statement constant has the text with all blocks included.
+/// Blocks cannot be nested or used with `IN (:list)` parameters, and their parameters can only
+/// be used inside them.
+///
+/// Parameters that many statements share can be declared as a group before the first statement -
+/// `-- group: context = tenant, user`. The group gets a struct - `Context { tenant, user }` - and
+/// the argument structs of the statements with all of its parameters embed it in one field -
+/// `SelectTenantSailors { context, ship }`. Statements with lists, conditional blocks or
+/// identifier parameters, and the ones where a grouped parameter is optional, keep separate
+/// fields. Groups need `args = refs` and cannot be used with `bind = named`.
+///
+/// Identifiers that cannot be bound, like the ORDER BY column, can be parameters too -
+/// `ORDER BY :#sort_column` - when the statement declares which identifiers are allowed -
+/// `-- allow sort_column: name, rank`. The parameter becomes a field of a generated enum type -
+/// `SelectShipCrewSortColumn::Rank` - and the identifier is spliced into the text by
+/// `into_sql_with_args`. The statement constant has the first allowed identifier.
+///
+/// Statements named with the `(script)` suffix - `-- name: init_schema (script)` - are scripts
+/// of `;` separated statements. They also get `INIT_SCHEMA_STATEMENTS`, a `&[&str]` of the
+/// individual statements, for drivers that execute one statement at a time.
+///
+/// Migrations are statements named by `-- migration: 003 add_sailors_rank` with the version and the
+/// name of the migration. Their text is the up SQL. The optional down SQL follows the `-- down`
+/// line. Migrations cannot have parameters, and each of them needs its own version.
+///
+/// Lists compared with a tuple of columns - `(ship_id, rank) IN (:pairs)` - are tuple lists.
+/// Their arguments are collections or iterators of tuples, like `(&ship_id as &dyn ToSql, &rank)`,
+/// and each tuple is expanded into its own group of placeholders - `(($1,$2),($3,$4))`.
+///
+/// IN lists of statements annotated with `-- in-chunks: 1000` are split into chunks of at most
+/// that many items, as Oracle only accepts 1000. The condition - `col IN (:list)` - is repeated
+/// for each chunk, and the chunks are joined by OR, or by AND for `NOT IN`.
+///
+/// Empty IN lists are expanded into `IN ()`, which most databases reject, unless the statement
+/// says what they turn into - `-- empty-in: null` for `IN (NULL)`, `-- empty-in: false` for the
+/// false IN condition or the true NOT IN one, or `-- empty-in: error` for `into_sql_with_args`
+/// that returns `Err(ArgsError::EmptyList)`.
+///
+/// Queries and `<!` statements annotated with the Rust types of their columns - `-- returns: id i32, name String` -
+/// get a `<Statement>Row` struct with these fields. With the `rusqlite` feature it implements
+/// `from_rusqlite_row`, and with the `postgres` or `tokio-postgres` feature - `from_postgres_row`,
+/// which read the columns by their position.
+///
+/// Statements annotated with the SQL types of their parameters - `-- diesel-bind: ship Integer, rank Text` -
+/// also get a `<name>_sql_query!` macro that builds diesel's `sql_query` with the arguments bound
+/// in the placeholders order. `-- diesel-row: id Integer i32, name Text String` adds a
+/// `QueryableByName` struct for the rows. The SQL types are resolved where the file is included.
+///
+/// Parameters annotated as optional - `-- param: rank? &str` - become `Option<&dyn ToSql>` fields.
+/// When their values are `None` the NULL of the annotated type - `&None::<&str>` - is bound.
+///
+/// Errors in the SQL file do not stop the parsing. Each of them is reported separately, so a
+/// single build shows all of them.
+///
+/// When the `INCLUDE_SQL_VERBOSE` environment variable names a directory the macro writes notes
+/// about the SQL file it has parsed into it - statements, their kinds and parameters, and whether
+/// each parameter is a scalar, optional or `IN (:list)` one, and which arguments it is bound as.
+/// The notes are plain text in `crew.notes` for `src/crew.sql`, not compiler diagnostics.
+///
+/// When the `INCLUDE_SQL_DUMP` environment variable names a directory the macro writes the code
+/// it has generated into it - `src/crew.sql` into `crew.rs`, and inline SQL into the file named
+/// after its first statement - `inline_select_ship_name.rs`. A dump that cannot be written is
+/// reported as a compiler warning.
+///
 /// For each of the statements found in the SQL file `include-sql` will generate:
 /// - `&str` constant with the text of the preprocessed SQL - named parameters will be replaced
-///   by numbered positional ones
+///   by numbered positional ones. Statements with the same text share the text literal - the
+///   constant of a repeated statement is defined as the constant of the first one. Set the
+///   `INCLUDE_SQL_VERBOSE` environment variable to get a compiler warning about each of them.
 /// - `struct` that will be used to convert query arguments from a named into a positional form
 /// - a macro to transparently convert the argument struct into an argument slice when the struct
 ///   cannot be used directly
+/// - `TryFrom<&HashMap<&str, &dyn ToSql>>` implementation for arguments structs without list
+///   parameters. The conversion fails with `include_sql_helper::ArgsError` when the map misses a
+///   value of a parameter or has a value for an unknown one.
+/// - `as_array()` method of the arguments struct without list parameters that returns the
+///   arguments as an array, which can be passed as a slice without allocation
+/// - `with_extra_args(&[...])` method of the arguments struct that appends extra arguments
+///   after the generated ones
+/// - owned variant of the arguments struct - `<Statement>Owned` - with `Box<dyn ToSql>` fields,
+///   and `Vec<Box<dyn ToSql>>` ones for lists. It can be built in one place and executed in
+///   another. Its `as_args()` method borrows it as the arguments struct.
+/// - `<statement>_args_from!(value)` macro that fills the arguments struct from the fields of
+///   another struct that are named after the parameters. Fields of optional parameters are
+///   `Option`s, and the ones of lists are collections, whose items are bound. Statements with
+///   identifier parameters or tuple lists do not get it.
+/// - `include_sql_helper::StatementKind` constant - `<STATEMENT>_KIND` - with the kind of the
+///   statement. Like in Yesql the kind is defined by the suffix of the statement name:
+///   `-- name: insert_sailor!` is executed, `-- name: insert_ship<!` fetches one row, and
+///   statements without a suffix are queries.
+/// - `include_sql_helper::StatementInfo` constant - `<STATEMENT>_INFO` - with the name of the
+///   statement, the SQL file and the line where the statement starts, and the names of its
+///   parameters. Each parameter is listed once, in the order of its first placeholder - the order
+///   parameters first appear in the text unless `-- param-order` sets another - and `IN (:list)`
+///   parameters follow the others. It is displayed as `src/crew.sql:12 select_ship_crew`.
+/// - `u64` constant - `<STATEMENT>_FINGERPRINT` - with the FNV-1a hash of the statement text
+///   without comments and with collapsed whitespace. It stays the same when only the formatting
+///   of the statement changes.
+/// - `usize` constant - `<STATEMENT>_PARAM_COUNT` - with the number of arguments that the
+///   statement text binds. `into_sql_with_args` asserts, in debug builds, that it binds as many
+///   arguments as the text it returns has placeholders, which is also checked for statements with
+///   lists and conditional blocks that do not get the constant.
+/// - `&[&str]` constant - `<STATEMENT>_PARAMS` - with the names of the parameters of the bound
+///   arguments in the order they are bound. A parameter that is bound several times - with
+///   unnumbered placeholders or `dedup_params = false` - is listed each time, so unlike the
+///   `params` of `_INFO` the names line up with the arguments. Statements with `IN (:list)`
+///   parameters or conditional blocks, whose text is assembled at run time, get neither this
+///   nor the count.
+/// - `bool` constant - `<STATEMENT>_IDEMPOTENT` - for statements annotated with `-- idempotent`,
+///   which thus can be safely retried after a connection drop. Other statements do not get it.
+/// - `usize` constant - `<STATEMENT>_COLUMNS` - with the number of columns in the rows of queries
+///   and `<!` statements when the projection - the SELECT or RETURNING list without `*` - tells it.
+///   Generated functions assert, in debug builds, that the database returns as many columns.
+/// - `usize` constants - `<STATEMENT>_COL_<COLUMN>` - with the indexes of the projection columns
+///   that are named by their alias - `AS ship_name` - or by the column they select - `s.name`.
+///   Names that several columns share get no constant.
+/// - `&str` constants - `<STATEMENT>_EXPLAIN` and `<STATEMENT>_EXPLAIN_ANALYZE` - for statements
+///   annotated with `-- generate: explain, explain_analyze`. They prefix the statement text with
+///   the dialect's EXPLAIN, so the execution plan can be requested with the same arguments.
+/// - `HEALTHCHECKS` constant and `run_healthchecks(execute)` function when some statements are
+///   annotated with `-- healthcheck` or `-- healthcheck: 500ms`. The function executes each probe
+///   statement with `execute` and reports whether it failed or exceeded its time limit. With the
+///   `macro_prefix` their names get the prefix too - `CREW_HEALTHCHECKS`.
+/// - `MIGRATIONS` constant, ordered by version, and `migrate(runner)` function when the file has
+///   migrations - `-- migration: 003 add_sailors_rank`. The function applies the migrations that
+///   the `include_sql_helper::MigrationRunner` has not applied yet. With the `macro_prefix` their
+///   names get the prefix too - `CREW_MIGRATIONS`.
+/// - `std::time::Duration` constant - `<STATEMENT>_CACHE_TTL` - for statements annotated with
+///   `-- cache: 30s`. It tells how long the results of the statement can be kept in the
+///   `include_sql_helper::StatementCache`. Durations are numbers followed by `ms`, `s`, `m` or `h`.
+///   With `functions` these statements also get `<statement>_cached` functions that take the
+///   cache as their first argument and return the cached result, shared via `Arc`, until it
+///   expires. The cache is keyed on the arguments, thus statements with parameters need
+///   `args = generic` arguments without lists.
+/// - `fn() -> &'static str` - `<statement>_sql()` - with the `hot_reload` option. It returns the
+///   current text of the statement in debug builds and the compiled one in release builds.
 ///
 /// # Examples
 ///
@@ -130,221 +454,53 @@ mod sql;
 /// ```
 #[proc_macro]
 pub fn include_sql(input: TokenStream) -> TokenStream {
-    let IncludeSql { statements, param_prefix } = parse_macro_input!(input as IncludeSql);
-    let mut code = Vec::new();
-
-    for stmt in statements {
-        let sql::Stmt { name, const_name, text, params } = stmt;
-        code.push(quote! {
-            const #const_name : &str = #text;
-        });
-        if let Some( params ) = params {
-            if params.lst_params.is_empty() {
-                add_pos_params(&params, &name, &mut code);
-            } else {
-                add_lst_params(&params, &param_prefix, &const_name, &mut code);
-            }
-        }
-    }
-    let code = quote! {
-        #( #code )*
-    };
-    TokenStream::from(code)
+    let include = parse_macro_input!(input as IncludeSql);
+    TokenStream::from(include_sql_codegen::include_sql(include))
 }
 
-struct IncludeSql {
-    statements: Vec<sql::Stmt>,
-    param_prefix: String
-}
-
-impl Parse for IncludeSql {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let path: Expr = input.parse()?;
-        input.parse::<Token![,]>()?;
-        let param_prefix: Expr = input.parse()?;
-
-        let path = to_litstr(path, "SQL file path")?;
-        let path = path.value();
-        let param_prefix = to_litstr(param_prefix, "parameter prefix")?;
-        let param_prefix = param_prefix.value();
-        match sql::parse_sql_file(&path, &param_prefix) {
-            Ok(statements) => {
-                Ok( IncludeSql { statements, param_prefix } )
-            }
-            Err(err) => {
-                Err(Error::new(path.span(), format!("{}", err)))
-            }
-        }
-    }
-}
-
-fn to_litstr(expr: Expr, kind: &str) -> Result<LitStr> {
-    let span = expr.span();
-    if let Expr::Lit( lit_expr ) = expr {
-        if let Lit::Str( lit ) = lit_expr.lit {
-            return Ok(lit);
-        }
-    }
-    Err(Error::new(span, format!("{} must be a literal string", kind)))
-}
-
-macro_rules! len {
-    ($s:expr) => {
-        $s.len()
-    };
-    ($s:expr, $($t:expr),+) => {
-        $s.len() + len!($($t),+)
-    };
-}
-
-macro_rules! ident {
-    ($s:expr) => {
-        Ident::new($s, Span::call_site())
-    };
-    ($($s:expr),+) => {{
-        let cap = len!($($s),+);
-        let mut name = String::with_capacity(cap);
-        $(
-            name.push_str($s);
-        )+
-        ident!(&name)
-    }};
-}
-
-fn add_pos_params(params: &sql::StmtParams, stmt_name: &str, code: &mut Vec<proc_macro2::TokenStream>) {
-    let sql::StmtParams { struct_name, pos_params, lst_params: _ } = params;
-    code.push(quote! {
-        struct #struct_name<'a> {
-            #( #pos_params : &'a dyn ToSql ),*
-        }
-    });
-    let using_args_macro = ident!("using_", stmt_name, "_args");
-    let args_macro = ident!(stmt_name, "_args");
-    code.push(quote! {
-        include_sql_helper::def_args!($ => #using_args_macro : #struct_name = #( #pos_params ),*);
-        include_sql_helper::def_args!($ => #args_macro : #struct_name = #( #pos_params ),*);
-    });
-    let iter = ident!(&struct_name.to_string(), "ArgsIter");
-    code.push(quote! {
-        pub(crate) struct #iter<'a> {
-            item: #struct_name<'a>,
-            index: usize
-        }
-    });
-    code.push(quote! {
-        impl<'a> std::iter::IntoIterator for #struct_name<'a> {
-            type Item = &'a dyn ToSql;
-            type IntoIter = #iter<'a>;
-
-            fn into_iter(self) -> Self::IntoIter {
-                #iter { item: self, index: 0 }
-            }
-        }
-    });
-    let param_nums = 0..pos_params.len();
-    let fn_next = quote! {
-        fn next(&mut self) -> std::option::Option<Self::Item> {
-            let next = match self.index {
-                #( #param_nums => Some( self.item.#pos_params ), )*
-                _ => None,
-            };
-            self.index += 1;
-            next
-        }
-    };
-    code.push(quote! {
-        impl<'a> std::iter::Iterator for #iter<'a> {
-            type Item = &'a dyn ToSql;
-            #fn_next
-        }
-    });
+/// Includes SQL from the provided string literal.
+///
+/// This is [`include_sql!`](macro.include_sql.html) for one-off statements that do not deserve
+/// their own file. The SQL text takes the place of the file path and the other arguments are the
+/// same. The text is parsed like the SQL file, except that every statement must be named:
+///
+/// ```rust,ignore
+/// inline_sql!("
+///     -- name: select_ship_name
+///     SELECT name FROM ships WHERE id = :id
+/// ", "$");
+///
+/// let rows = conn.query(SELECT_SHIP_NAME, using_select_ship_name_args! { id: &ship_id })?;
+/// ```
+#[proc_macro]
+pub fn inline_sql(input: TokenStream) -> TokenStream {
+    let InlineSql(include) = parse_macro_input!(input as InlineSql);
+    TokenStream::from(include_sql_codegen::inline_sql(include))
 }
 
-fn add_lst_params(params: &sql::StmtParams, param_prefix: &str, sql_text_const: &Ident, code: &mut Vec<proc_macro2::TokenStream>) {
-    let sql::StmtParams { struct_name, pos_params, lst_params } = params;
-
-    struct ExtLstParam<'a> {
-        param: &'a sql::LstParam,
-        usage: ParamUsage
-    }
-
-    enum ParamUsage {
-        Unique,
-        HasDups,
-        IsADup
-    }
-
-    let mut ext_lst_params = Vec::new();
-    let mut lst_fields = Vec::new();
-    let mut iter = lst_params.iter();
-    if let Some( param ) = iter.next() {
-        ext_lst_params.push(ExtLstParam { param, usage: ParamUsage::Unique });
-        lst_fields.push(&param.name);
-        for param in iter {
-            if let Some( idx ) = ext_lst_params.iter().position(|ext| ext.param.name == param.name) {
-                let ext = &mut ext_lst_params[idx];
-                ext.usage = ParamUsage::HasDups;
-                ext_lst_params.push(ExtLstParam { param, usage: ParamUsage::IsADup });
-            } else {
-                lst_fields.push(&param.name);
-                ext_lst_params.push(ExtLstParam { param, usage: ParamUsage::Unique });
-            }
-        }
-    }
-
-    let mut push_lst_args_code = Vec::new();
-    let mut from = 0;
-    for ext in ext_lst_params {
-        let param_name = &ext.param.name;
-        let text_end = ext.param.position;
-        push_lst_args_code.push(quote! {
-            sql.push_str(&#sql_text_const[#from..#text_end]);
-        });
-        if let ParamUsage::HasDups = ext.usage {
-            push_lst_args_code.push(quote! {
-                let start = sql.len();
-            });
-        }
-        match ext.usage {
-            ParamUsage::Unique | ParamUsage::HasDups => {
-                push_lst_args_code.push(quote! {
-                    include_sql_helper::push(self.#param_name, #param_prefix, &mut sql, &mut args);
-                });
-            }
-            ParamUsage::IsADup => {
-            let param_list = ident!(&param_name.to_string(), "_list");
-                push_lst_args_code.push(quote! {
-                    sql.push_str(&#param_list);
-                });
-            }
-        }
-        if let ParamUsage::HasDups = ext.usage {
-            let param_list = ident!(&param_name.to_string(), "_list");
-            push_lst_args_code.push(quote! {
-                let #param_list = sql[start .. sql.len()].to_string();
-            });
-        }
-        from = text_end;
-    }
-    push_lst_args_code.push(quote! {
-        sql.push_str(&#sql_text_const[#from..]);
-    });
-
-    code.push(quote! {
-        struct #struct_name<'a> {
-            #( #pos_params : &'a dyn ToSql, )*
-            #( #lst_fields : &'a[&'a dyn ToSql] ),*
-        }
-    });
-    code.push(quote! {
-        impl<'a> #struct_name<'a>{
-            fn into_sql_with_args(self) -> (String, Vec<&'a dyn ToSql>) {
-                let mut args = Vec::new();
-                #( args.push(self.#pos_params); )*
-                let mut sql = String::with_capacity(#sql_text_const.len() + 16);
-                #( #push_lst_args_code )*
-                (sql, args)
-            }
-        }
-    });
+/// Includes SQL from the provided file into the annotated module.
+///
+/// This is the attribute form of [`include_sql!`](macro.include_sql.html) that takes the same
+/// arguments. Items that `include_sql!` generates are placed into the module and are visible to
+/// its parent - `crew::SELECT_SHIP_CREW`, `crew::SelectShipCrew`. Thus several SQL files can be
+/// included into the same scope even when they have statements with the same names:
+///
+/// ```rust,ignore
+/// #[include_sql_mod("src/crew.sql", "$")]
+/// mod crew {}
+///
+/// #[include_sql_mod("src/archive/crew.sql", "$")]
+/// mod archived_crew {}
+/// ```
+///
+/// The module must be inline - `mod crew {}` - as attributes on modules in their own files are
+/// not stable yet. Its items are kept and all items of the parent module are imported into it,
+/// so `ToSql` of the parent is used by the generated code. Argument macros are defined inside
+/// the module before its own items. They can be used after the module when it is also annotated
+/// with `#[macro_use]`.
+#[proc_macro_attribute]
+pub fn include_sql_mod(args: TokenStream, item: TokenStream) -> TokenStream {
+    let include = parse_macro_input!(args as IncludeSql);
+    let module = parse_macro_input!(item as ItemMod);
+    TokenStream::from(include_sql_codegen::include_sql_mod(include, module))
 }
@@ -0,0 +1,22 @@
+-- Statements that the MySQL integration test executes
+
+-- name: create_table_sailors!
+CREATE TEMPORARY TABLE sailors (
+  name      VARCHAR(100) CHARACTER SET utf8mb4 NOT NULL,
+  station   VARCHAR(20) NOT NULL
+)
+
+-- name: insert_sailor!
+INSERT INTO sailors (name, station) VALUES (:name, :station)
+
+-- name: select_sailor_station
+SELECT station FROM sailors WHERE name = :name
+
+-- name: count_sailors_at_stations
+SELECT count(*) FROM sailors WHERE station IN (:stations)
+
+-- name: count_sailors_named_or_stationed
+SELECT count(*) FROM sailors WHERE name = :value OR station = :value
+
+-- name: count_sailors_in_list
+SELECT count(*) FROM sailors WHERE station IN (:values) OR name IN (:values)
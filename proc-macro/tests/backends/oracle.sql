@@ -0,0 +1,38 @@
+-- Statements that the Oracle integration test executes
+
+-- name: create_table_sailors!
+-- Oracle temporary tables are permanent, thus the table is created once and emptied by every test
+DECLARE
+  found INTEGER;
+BEGIN
+  SELECT count(*)
+    INTO found
+    FROM user_tables
+   WHERE table_name = 'SAILORS';
+  IF found = 0 THEN
+    EXECUTE IMMEDIATE '
+      CREATE GLOBAL TEMPORARY TABLE sailors (
+        name      VARCHAR2(100 CHAR) NOT NULL,
+        station   VARCHAR2(20) NOT NULL
+      ) ON COMMIT PRESERVE ROWS
+    ';
+  END IF;
+END;
+
+-- name: delete_sailors!
+DELETE FROM sailors
+
+-- name: insert_sailor!
+INSERT INTO sailors (name, station) VALUES (:name, :station)
+
+-- name: select_sailor_station
+SELECT station FROM sailors WHERE name = :name
+
+-- name: count_sailors_at_stations
+SELECT count(*) FROM sailors WHERE station IN (:stations)
+
+-- name: count_sailors_named_or_stationed
+SELECT count(*) FROM sailors WHERE name = :value OR station = :value
+
+-- name: count_sailors_in_list
+SELECT count(*) FROM sailors WHERE station IN (:values) OR name IN (:values)
@@ -0,0 +1,23 @@
+-- Statements that the SQLite integration test executes
+
+-- name: create_table_sailors!
+CREATE TABLE sailors (
+  name      TEXT NOT NULL,
+  station   TEXT NOT NULL
+)
+
+-- name: insert_sailor!
+INSERT INTO sailors (name, station) VALUES (:name, :station)
+
+-- name: select_sailor_station
+-- returns: station String
+SELECT station FROM sailors WHERE name = :name
+
+-- name: count_sailors_at_stations
+SELECT count(*) FROM sailors WHERE station IN (:stations)
+
+-- name: count_sailors_named_or_stationed
+SELECT count(*) FROM sailors WHERE name = :value OR station = :value
+
+-- name: count_sailors_in_list
+SELECT count(*) FROM sailors WHERE station IN (:values) OR name IN (:values)
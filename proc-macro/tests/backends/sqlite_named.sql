@@ -0,0 +1,16 @@
+-- Statements that the SQLite integration test executes with arguments bound by name
+
+-- name: create_table_sailors!
+CREATE TABLE sailors (
+  name      TEXT NOT NULL,
+  station   TEXT NOT NULL
+)
+
+-- name: insert_sailor!
+INSERT INTO sailors (name, station) VALUES (:name, :station)
+
+-- name: select_sailor_station
+SELECT station FROM sailors WHERE name = :name
+
+-- name: count_sailors_named_or_stationed
+SELECT count(*) FROM sailors WHERE name = :value OR station = :value
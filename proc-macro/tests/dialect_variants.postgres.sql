@@ -0,0 +1,4 @@
+-- Statements that Postgres needs to be written differently
+
+-- name: select_recent_sailors
+SELECT name FROM sailors WHERE ship_id = :ship AND joined_at > now() - interval '1 year'
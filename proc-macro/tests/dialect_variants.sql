@@ -0,0 +1,7 @@
+-- Statements that are the same in every dialect, unless the dialect variant of the file overrides them
+
+-- name: select_ship_crew
+SELECT name, rank FROM sailors WHERE ship_id = :ship
+
+-- name: select_recent_sailors
+SELECT name FROM sailors WHERE ship_id = :ship AND joined_at > datetime('now', '-1 year')
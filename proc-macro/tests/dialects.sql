@@ -0,0 +1,12 @@
+-- Test case for database presets
+
+-- name: select_dialect_objects
+-- Uses both scalar and list parameters twice to check that the arguments
+-- are bound once for numbered placeholders and repeated for unnumbered ones.
+select object_name
+  from user_objects
+ where object_type in ( :object_types ) and owner = :owner
+    or object_type in ( :object_types ) and owner = :owner
+
+-- name: select_owner_objects
+select object_name from user_objects where owner = :owner or created_by = :owner and status = :status
@@ -0,0 +1,36 @@
+//! Checks the diesel `sql_query` adapters. The queries are not executed, thus this does not need a database.
+
+use diesel::pg::Pg;
+use diesel::sql_types::*;
+use include_sql::include_sql;
+
+// argument structs still expect the database interface trait
+pub trait ToSql {}
+
+include_sql!("proc-macro/tests/stmt_for_diesel.sql", dialect = postgres);
+
+fn is_queryable_by_name<T: diesel::QueryableByName<Pg>>() {}
+
+#[test]
+fn arguments_are_bound_in_placeholder_order() {
+    let query = select_ship_crew_sql_query! { rank: "captain", ship: &1 };
+    assert_eq!(
+        format!("{} -- binds: [1, \"captain\"]", SELECT_SHIP_CREW),
+        diesel::debug_query::<Pg, _>(&query).to_string()
+    );
+}
+
+#[test]
+fn statement_without_parameters() {
+    let query = count_ships_sql_query!();
+    assert_eq!(
+        format!("{} -- binds: []", COUNT_SHIPS),
+        diesel::debug_query::<Pg, _>(&query).to_string()
+    );
+}
+
+#[test]
+fn row_structs() {
+    is_queryable_by_name::<SelectShipCrewRow>();
+    is_queryable_by_name::<CountShipsRow>();
+}
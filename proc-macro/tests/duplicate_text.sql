@@ -0,0 +1,10 @@
+-- Statements with the same text share the text constant
+
+-- name: select_ship_sailors
+SELECT id, name FROM sailors WHERE ship_id = :ship
+
+-- name: select_ship_crew_members
+SELECT id, name FROM sailors WHERE ship_id = :ship
+
+-- name: count_ships
+SELECT count(*) FROM ships
@@ -24,6 +24,14 @@ fn multiple_named_statements_per_file() {
     );
 }
 
+include_sql!("proc-macro/tests/named_statements.sql", "$", module = true);
+
+#[test]
+fn statements_in_file_module() {
+    assert_eq!(DUAL_OUTPUT, named_statements::DUAL_OUTPUT);
+    assert_eq!(include_sql_helper::StatementKind::Query, named_statements::USER_TABLES_COUNT_KIND);
+}
+
 include_sql!("proc-macro/tests/default_stmt.sql", ":");
 
 #[test]
@@ -38,6 +46,445 @@ fn include_with_default_unnamed_statement() {
     );
 }
 
+include_sql!("proc-macro/tests/stmt_kinds.sql", ":");
+
+#[test]
+fn statement_kinds_from_name_suffixes() {
+    use include_sql_helper::StatementKind;
+
+    assert_eq!(StatementKind::Query, SELECT_TMP_OBJECTS_KIND);
+    assert_eq!(StatementKind::FetchOne, INSERT_TMP_OBJECT_KIND);
+    assert_eq!(StatementKind::Execute, DELETE_TMP_OBJECTS_KIND);
+    assert_eq!(
+        "insert into tmp_objects (object_name) values ('T') returning id",
+        collapce_whitespace(INSERT_TMP_OBJECT)
+    );
+}
+
+include_sql!("proc-macro/tests/stmt_with_cache.sql", "$");
+
+#[test]
+fn statement_cache_ttl() {
+    use std::time::Duration;
+
+    assert_eq!(Duration::from_secs(30), SELECT_SHIP_NAMES_CACHE_TTL);
+    assert_eq!(Duration::from_millis(500), SELECT_SHIP_CREW_CACHE_TTL);
+
+    let cache = include_sql_helper::StatementCache::new();
+    let mut executed = 0;
+    for _ in 0..2 {
+        let names = cache.get_or_try_insert_with("select_ship_names", (), SELECT_SHIP_NAMES_CACHE_TTL, || {
+            executed += 1;
+            Ok::<_, ()>(vec!["Enterprise"])
+        });
+        assert_eq!(Ok(vec!["Enterprise"]), names);
+    }
+    assert_eq!(1, executed);
+
+    let cache = include_sql_helper::StatementCache::new();
+    for ship in [1, 2, 1].iter() {
+        let crew = cache.get_or_try_insert_with("select_ship_crew", *ship, SELECT_SHIP_CREW_CACHE_TTL, || {
+            executed += 1;
+            Ok::<_, ()>(vec![*ship * 10])
+        });
+        assert_eq!(Ok(vec![*ship * 10]), crew);
+    }
+    assert_eq!(3, executed);
+    cache.invalidate("select_ship_crew");
+    assert_eq!(None, cache.get("select_ship_crew", &1));
+}
+
+include_sql!("proc-macro/tests/stmt_with_build_vars.sql", "$");
+
+#[test]
+fn build_metadata_interpolation() {
+    assert_eq!(
+        concat!("SELECT /* app ", env!("CARGO_PKG_VERSION"), " */ id, name FROM ships WHERE id = $1"),
+        SELECT_TAGGED_SHIPS
+    );
+}
+
+include_sql!("proc-macro/tests/stmt_with_template_vars.sql", "$", vars = { "schema" = "analytics", "suffix" = "_v2" });
+
+#[test]
+fn template_variables() {
+    assert_eq!(
+        "SELECT day, visits FROM analytics.visits_v2 WHERE day >= $1",
+        collapce_whitespace(SELECT_DAILY_VISITS)
+    );
+    assert_eq!("DELETE FROM analytics.visits_v2 WHERE day < $1", PURGE_VISITS);
+}
+
+include_sql!("proc-macro/tests/stmt_with_constants.sql", "$", vars = { "schema" = "fleet" });
+
+#[test]
+fn constants_of_the_file() {
+    // the `vars` of the macro take precedence over the constants
+    assert_eq!("SELECT id, name FROM fleet.app_sailors WHERE ship_id = $1", SELECT_PREFIXED_SAILORS);
+}
+
+include_sql!("proc-macro/tests/stmt_with_env_vars.sql", "$", vars = { "SCHEMA" = "config" });
+
+#[test]
+fn environment_variables() {
+    assert_eq!("SELECT value FROM config.settings WHERE package = 'include-sql' AND name = $1", SELECT_PACKAGE_SETTINGS);
+}
+
+include_sql!("proc-macro/tests/stmt_with_fragments.sql", "$");
+
+#[test]
+fn statements_with_fragments() {
+    assert_eq!(
+        "SELECT id, name, rank, ship_id, enlisted FROM sailors WHERE discharged IS NULL",
+        collapce_whitespace(SELECT_ACTIVE_SAILORS)
+    );
+    assert_eq!(
+        "SELECT id, name, rank, ship_id, enlisted FROM sailors WHERE discharged IS NULL AND ship_id = $1",
+        collapce_whitespace(SELECT_ACTIVE_SHIP_SAILORS)
+    );
+    let args = SelectActiveShipSailors { ship: &"Enterprise" }.as_array();
+    assert_eq!("Enterprise", args[0].to_sql());
+}
+
+include_sql!("proc-macro/tests/stmt_with_cond_blocks.sql", "$");
+
+#[test]
+fn conditional_blocks() {
+    assert_eq!(
+        "SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND rank = $2 AND (name LIKE $3 OR nickname LIKE $3) AND ship_id = $1 ORDER BY name",
+        collapce_whitespace(SEARCH_SAILORS)
+    );
+
+    let (sql, args) = SearchSailors { ship: &"Enterprise", rank: None, name: None }.into_sql_with_args();
+    assert_eq!("SELECT id, name, rank FROM sailors WHERE ship_id = $1 ORDER BY name", collapce_whitespace(&sql));
+    assert_eq!(1, args.len());
+
+    let (sql, args) = SearchSailors { ship: &"Enterprise", rank: None, name: Some(&"W%") }.into_sql_with_args();
+    assert_eq!(
+        "SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND (name LIKE $2 OR nickname LIKE $2) AND ship_id = $1 ORDER BY name",
+        collapce_whitespace(&sql)
+    );
+    let args : Vec<&str> = args.into_iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["Enterprise", "W%"], args);
+
+    let (kind, sql, args) = sql_with_args(SearchSailors { ship: &"Enterprise", rank: Some(&"captain"), name: None });
+    assert_eq!(include_sql_helper::StatementKind::Query, kind);
+    assert_eq!("SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND rank = $2 ORDER BY name", sql);
+    assert_eq!(vec!["Enterprise", "captain"], args);
+}
+
+include_sql!("proc-macro/tests/stmt_with_ident_params.sql", "$");
+
+#[test]
+fn identifier_parameters() {
+    assert_eq!(
+        "SELECT s.id, s.name, s.rank FROM sailors s WHERE s.ship_id = $1 ORDER BY name asc, s.id",
+        collapce_whitespace(SELECT_CREW_SORTED)
+    );
+
+    let (kind, sql, args) = sql_with_args(SelectCrewSorted {
+        ship: &"Enterprise",
+        sort_column: SelectCrewSortedSortColumn::SEnlistedAt,
+        sort_order: SelectCrewSortedSortOrder::Desc
+    });
+    assert_eq!(include_sql_helper::StatementKind::Query, kind);
+    assert_eq!("SELECT s.id, s.name, s.rank FROM sailors s WHERE s.ship_id = $1 ORDER BY s.enlisted_at desc, s.id", sql);
+    assert_eq!(vec!["Enterprise"], args);
+
+    assert_eq!("sailors_2020", CountArchivedArchive::Sailors2020.as_str());
+    let (sql, args) = CountArchived { archive: CountArchivedArchive::Sailors2020 }.into_sql_with_args();
+    assert_eq!("SELECT count(*) FROM sailors_2020", sql);
+    assert!(args.is_empty());
+}
+
+include_sql!("proc-macro/tests/stmt_with_chunked_lists.sql", "$");
+
+#[test]
+fn chunked_in_lists() {
+    assert_eq!(
+        "SELECT id FROM sailors s WHERE s.ship_id = $1 AND (s.id IN ()) AND (s.rank NOT IN ())",
+        collapce_whitespace(SELECT_SAILORS_IN_CHUNKS)
+    );
+
+    let ids = ["1", "2", "3"];
+    let ranks = ["ensign", "cadet", "civilian"];
+    let (kind, sql, args) = sql_with_args(SelectSailorsInChunks {
+        ship: &"Enterprise",
+        ids: ids.iter().map(|id| id as &dyn ToSql),
+        ranks: ranks.iter().map(|rank| rank as &dyn ToSql)
+    });
+    assert_eq!(include_sql_helper::StatementKind::Query, kind);
+    assert_eq!(
+        "SELECT id FROM sailors s WHERE s.ship_id = $1 AND (s.id IN ($2,$3) OR s.id IN ($4)) AND (s.rank NOT IN ($5,$6) AND s.rank NOT IN ($7))",
+        sql
+    );
+    assert_eq!(vec!["Enterprise", "1", "2", "3", "ensign", "cadet", "civilian"], args);
+
+    let (_, sql, args) = sql_with_args(SelectSailorsByRanksInChunks { ranks: ranks.iter().map(|rank| rank as &dyn ToSql) });
+    assert_eq!(
+        "SELECT id FROM sailors WHERE (rank IN ($1,$2) OR rank IN ($3)) OR (prev_rank IN ($1,$2) OR prev_rank IN ($3))",
+        sql
+    );
+    assert_eq!(vec!["ensign", "cadet", "civilian"], args);
+}
+
+include_sql!("proc-macro/tests/stmt_with_empty_lists.sql", "$");
+
+#[test]
+fn empty_in_lists() {
+    let no_items : [&dyn ToSql; 0] = [];
+
+    let (_, sql, args) = sql_with_args(SelectSailorsOfRanks { ranks: &no_items });
+    assert_eq!("SELECT id FROM sailors WHERE rank IN (NULL)", sql);
+    assert!(args.is_empty());
+
+    let (_, sql, args) = sql_with_args(SelectSailorsExcept { ship: &"Enterprise", ranks: &no_items, excluded: &no_items });
+    assert_eq!("SELECT id FROM sailors WHERE ship_id = $1 AND (rank IN (NULL) AND (1=0)) AND (id NOT IN (NULL) OR (1=1))", sql);
+    assert_eq!(vec!["Enterprise"], args);
+
+    let (_, sql, _) = sql_with_args(SelectSailorsExcept { ship: &"Enterprise", ranks: &[&"captain" as &dyn ToSql], excluded: &[&"1" as &dyn ToSql, &"2"] });
+    assert_eq!("SELECT id FROM sailors WHERE ship_id = $1 AND (rank IN ($2)) AND (id NOT IN ($3,$4))", sql);
+
+    let err = SelectSailorsByIds { ids: &no_items }.into_sql_with_args().err();
+    assert_eq!(Some(include_sql_helper::ArgsError::EmptyList("ids")), err);
+    assert_eq!("list argument `ids` is empty", err.unwrap().to_string());
+
+    let (sql, args) = SelectSailorsByIds { ids: &[&"1" as &dyn ToSql] }.into_sql_with_args().unwrap();
+    assert_eq!("SELECT id FROM sailors WHERE id IN ($1)", sql);
+    assert_eq!(1, args.len());
+}
+
+include_sql!("proc-macro/tests/stmt_with_tuple_lists.sql", "$");
+
+#[test]
+fn tuple_list_parameters() {
+    let (kind, sql, args) = sql_with_args(SelectSailorsByShipAndRank {
+        age: &"30",
+        ships_ranks: &[ (&"Enterprise" as &dyn ToSql, &"captain" as &dyn ToSql), (&"Voyager", &"ensign") ]
+    });
+    assert_eq!(include_sql_helper::StatementKind::Query, kind);
+    assert_eq!("SELECT id, name FROM sailors WHERE (ship_id, rank) IN (($2,$3),($4,$5)) AND age > $1", sql);
+    assert_eq!(vec!["30", "Enterprise", "captain", "Voyager", "ensign"], args);
+
+    let excluded = [ [&"Enterprise" as &dyn ToSql, &"captain", &"Picard"] ];
+    let (_, sql, args) = sql_with_args(SelectSailorsByShipRankAndName {
+        names: &[&"Riker" as &dyn ToSql],
+        excluded: excluded.iter()
+    });
+    assert_eq!("SELECT id FROM sailors WHERE coalesce(nickname, name) IN ($1) AND (ship_id, rank, name) NOT IN (($2,$3,$4))", sql);
+    assert_eq!(vec!["Riker", "Enterprise", "captain", "Picard"], args);
+}
+
+include_sql!("proc-macro/tests/stmt_scripts.sql", "$");
+
+#[test]
+fn script_statements() {
+    assert_eq!(include_sql_helper::StatementKind::Execute, INIT_CREW_SCHEMA_KIND);
+    assert_eq!(3, INIT_CREW_SCHEMA_STATEMENTS.len());
+    assert_eq!(
+        "CREATE TABLE ships ( id INTEGER PRIMARY KEY, name TEXT NOT NULL -- the name; is unique )",
+        collapce_whitespace(INIT_CREW_SCHEMA_STATEMENTS[0])
+    );
+    assert_eq!("CREATE TABLE sailors (id INTEGER PRIMARY KEY, ship_id INTEGER, name TEXT, motto TEXT DEFAULT 'Engage; make it so')", INIT_CREW_SCHEMA_STATEMENTS[1]);
+    assert_eq!("/* seed data; not needed in production */\nINSERT INTO ships (id, name) VALUES (1, 'Enterprise')", INIT_CREW_SCHEMA_STATEMENTS[2]);
+}
+
+include_sql!("proc-macro/tests/stmt_result_columns.sql", "$");
+
+#[test]
+fn result_column_counts() {
+    assert_eq!(3, SELECT_CREW_SUMMARY_COLUMNS);
+    assert_eq!(2, SELECT_RANKED_CREW_COLUMNS);
+    assert_eq!(3, INSERT_SHIP_COLUMNS);
+}
+
+#[test]
+fn result_column_indexes() {
+    assert_eq!([0, 1], [SELECT_CREW_SUMMARY_COL_NAME, SELECT_CREW_SUMMARY_COL_RANK_IF_ANY]);
+    assert_eq!([0, 1], [SELECT_RANKED_CREW_COL_NAME, SELECT_RANKED_CREW_COL_POS]);
+    assert_eq!([0, 1, 2], [INSERT_SHIP_COL_ID, INSERT_SHIP_COL_NAME, INSERT_SHIP_COL_CREATED_AT]);
+}
+
+include_sql!("proc-macro/tests/stmt_with_row_types.sql", "$");
+
+#[test]
+fn row_structs() {
+    let row = SelectCrewRosterRow { id: 1, name: "Riker".to_string(), rank: None };
+    assert_eq!((1, "Riker", None), (row.id, row.name.as_str(), row.rank));
+    let row = InsertLocatedShipRow { id: 7, name: "Enterprise".to_string() };
+    assert_eq!((7, "Enterprise"), (row.id, row.name.as_str()));
+}
+
+include_sql!("proc-macro/tests/stmt_with_optional_params.sql", "$");
+
+#[test]
+fn optional_parameters() {
+    let args = select_sailors_args! { ship: &"Enterprise", rank: None };
+    assert_eq!(2, args.len());
+    assert_eq!("NULL", args[1].to_sql());
+    let args : Vec<&dyn ToSql> = SelectSailors { ship: &"Enterprise", rank: Some(&"captain") }.into_iter().collect();
+    assert_eq!("captain", args[1].to_sql());
+
+    let (sql, args) = SelectFleetSailors { ships: &[ &"Enterprise" as &dyn ToSql ], rank: None }.into_sql_with_args();
+    assert_eq!("SELECT id, name FROM sailors WHERE ship_id IN ($2) AND ($1 IS NULL OR rank = $1)", sql);
+    let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["NULL", "Enterprise"], args);
+
+    let args = SelectSailors { ship: &"Enterprise", rank: None }.as_array();
+    assert_eq!(["Enterprise", "NULL"], [args[0].to_sql(), args[1].to_sql()]);
+
+    let owned_args = SelectSailorsOwned { ship: Box::new("Enterprise"), rank: None };
+    let args : Vec<&dyn ToSql> = owned_args.as_args().into_iter().collect();
+    assert_eq!("NULL", args[1].to_sql());
+}
+
+#[test]
+fn parameter_counts() {
+    use include_sql_helper::IntoSqlWithArgs;
+
+    assert_eq!(0, DUAL_OUTPUT_PARAM_COUNT);
+    assert_eq!(2, SELECT_SAILORS_PARAM_COUNT);
+    let (_, args) = SelectSailors { ship: &"Enterprise", rank: None }.into_sql_with_args();
+    assert_eq!(SELECT_SAILORS_PARAM_COUNT, args.len());
+}
+
+mod bound_param_names {
+    use crate::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_ranked_sailors
+        SELECT name FROM sailors WHERE ship_id = :ship AND (rank = :rank OR :rank IS NULL)
+    ", "?", dedup_params = false);
+
+    #[test]
+    fn parameters_are_named_in_the_order_they_are_bound() {
+        assert_eq!(&["ship", "rank", "rank"], SELECT_RANKED_SAILORS_PARAMS);
+        assert_eq!(SELECT_RANKED_SAILORS_PARAM_COUNT, SELECT_RANKED_SAILORS_PARAMS.len());
+        assert_eq!(&["ship", "rank"], crate::SELECT_SAILORS_PARAMS);
+        assert!(crate::DUAL_OUTPUT_PARAMS.is_empty());
+        let args = SelectRankedSailors { ship: &"Enterprise", rank: &"captain" };
+        let bound : Vec<(&str, &str)> = SELECT_RANKED_SAILORS_PARAMS.iter().copied().zip(args.into_iter().map(|arg| arg.to_sql())).collect();
+        assert_eq!(vec![("ship", "Enterprise"), ("rank", "captain"), ("rank", "captain")], bound);
+    }
+}
+
+include_sql!("proc-macro/tests/stmt_with_param_order.sql", "$");
+
+#[test]
+fn explicit_parameter_order() {
+    assert_eq!("SELECT id FROM sailors WHERE name LIKE $3 AND rank = $2 AND ship_id = $1 OR escort_of = $1", SELECT_ORDERED_SAILORS);
+    let args : Vec<&str> = SelectOrderedSailors { name: &"H%", rank: &"captain", ship: &"Indefatigable" }.into_iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["Indefatigable", "captain", "H%"], args);
+
+    let (sql, args) = SelectRankedFleetSailors { ship: &"Indefatigable", ids: &[ &"1" as &dyn ToSql, &"2" ], rank: &"seaman" }.into_sql_with_args();
+    assert_eq!("SELECT id FROM sailors WHERE ship_id = $2 AND id IN ($3,$4) AND rank = $1", sql);
+    let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["seaman", "Indefatigable", "1", "2"], args);
+}
+
+include_sql!("proc-macro/tests/stmt_healthchecks.sql", "$");
+
+#[test]
+fn health_checks() {
+    assert_eq!(2, HEALTHCHECKS.len());
+    assert_eq!(["ping", "check_ships_table"], [HEALTHCHECKS[0].name, HEALTHCHECKS[1].name]);
+    assert_eq!(CHECK_SHIPS_TABLE, HEALTHCHECKS[1].sql);
+    assert_eq!(Some(std::time::Duration::from_millis(250)), HEALTHCHECKS[1].time_limit);
+
+    let reports = run_healthchecks(|sql| if sql.contains("ships") { Err("relation \"ships\" does not exist") } else { Ok(()) });
+    let passed : Vec<_> = reports.iter().map(|report| (report.name, report.passed())).collect();
+    assert_eq!(vec![("ping", true), ("check_ships_table", false)], passed);
+    assert_eq!("relation \"ships\" does not exist", reports[1].result.as_ref().unwrap_err().to_string());
+
+    let reports = run_healthchecks(|sql| {
+        if sql.contains("ships") {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+        }
+        Ok::<_, String>(())
+    });
+    assert!(reports[0].passed());
+    assert!(matches!(reports[1].result, Err(include_sql_helper::HealthCheckError::TimedOut(_))));
+}
+
+include_sql!("proc-macro/tests/stmt_migrations.sql", "$");
+
+/// Migration runner that keeps the applied versions and the executed SQL in memory.
+#[derive(Default)]
+struct MigrationLog {
+    applied: Vec<u32>,
+    executed: Vec<&'static str>
+}
+
+impl include_sql_helper::MigrationRunner for MigrationLog {
+    type Error = String;
+
+    fn applied_versions(&mut self) -> Result<Vec<u32>, String> {
+        Ok(self.applied.clone())
+    }
+
+    fn apply(&mut self, migration: &include_sql_helper::Migration) -> Result<(), String> {
+        self.applied.push(migration.version);
+        self.executed.push(migration.up);
+        Ok(())
+    }
+
+    fn revert(&mut self, migration: &include_sql_helper::Migration) -> Result<(), String> {
+        self.applied.retain(|&version| version != migration.version);
+        self.executed.extend(migration.down);
+        Ok(())
+    }
+}
+
+#[test]
+fn migrations() {
+    let versions : Vec<_> = MIGRATIONS.iter().map(|migration| (migration.version, migration.name)).collect();
+    assert_eq!(vec![(1, "create_sailors"), (2, "add_sailors_rank"), (3, "index_sailors_rank")], versions);
+    assert_eq!("ALTER TABLE sailors ADD COLUMN rank TEXT", ADD_SAILORS_RANK);
+    assert_eq!(ADD_SAILORS_RANK, MIGRATIONS[1].up);
+    assert_eq!(Some("ALTER TABLE sailors DROP COLUMN rank"), MIGRATIONS[1].down);
+    assert_eq!(None, MIGRATIONS[0].down);
+
+    let mut log = MigrationLog { applied: vec![1], executed: Vec::new() };
+    assert_eq!(vec![2, 3], migrate(&mut log).unwrap());
+    assert_eq!(vec![ADD_SAILORS_RANK, INDEX_SAILORS_RANK], log.executed);
+    assert!(migrate(&mut log).unwrap().is_empty());
+
+    assert_eq!(vec![3, 2], include_sql_helper::rollback(&mut log, MIGRATIONS, 1).unwrap());
+    assert_eq!(vec![1], log.applied);
+    assert!(matches!(include_sql_helper::rollback(&mut log, MIGRATIONS, 0), Err(include_sql_helper::MigrationError::Irreversible(1))));
+
+    let mut log = MigrationLog::default();
+    assert_eq!(vec![1, 2, 3], migrate(&mut log).unwrap());
+}
+
+include_sql!("proc-macro/tests/stmt_static_only.sql", "$");
+
+#[test]
+fn static_only_file() {
+    assert_eq!(
+        "SELECT id, amount, status FROM payments WHERE id = $1",
+        collapce_whitespace(SELECT_PAYMENT)
+    );
+    assert_eq!(
+        "UPDATE payments SET status = 'SETTLED' WHERE id = $1 AND status = $2",
+        collapce_whitespace(SETTLE_PAYMENT)
+    );
+    let args = SettlePayment { id: &"P-42", status: &"AUTHORIZED" };
+    assert_eq!(2, args.as_array().len());
+}
+
+include_sql!("proc-macro/tests/duplicate_text.sql", "$");
+
+#[test]
+fn statements_with_same_text_share_constant() {
+    assert_eq!(SELECT_SHIP_SAILORS, SELECT_SHIP_CREW_MEMBERS);
+    assert_eq!("SELECT id, name FROM sailors WHERE ship_id = $1", SELECT_SHIP_CREW_MEMBERS);
+    assert_eq!("SELECT count(*) FROM ships", COUNT_SHIPS);
+
+    let args : Vec<&dyn ToSql> = SelectShipCrewMembers { ship: &"Enterprise" }.into_iter().collect();
+    assert_eq!(1, args.len());
+}
+
 // Statements with parameters need ToSql trait
 // The test version of the latter will be used to access argument value(s)
 pub(crate) trait ToSql {
@@ -46,7 +493,13 @@ pub(crate) trait ToSql {
 
 impl ToSql for &str {
     fn to_sql(&self) -> &str {
-        *self
+        self
+    }
+}
+
+impl ToSql for Option<&str> {
+    fn to_sql(&self) -> &str {
+        self.unwrap_or("NULL")
     }
 }
 
@@ -70,6 +523,14 @@ fn statement_with_parameters() {
 
 include_sql!("proc-macro/tests/stmt_with_in_params.sql", ":");
 
+#[test]
+fn statement_info() {
+    assert_eq!("select_objects_by_type", SELECT_OBJECTS_BY_TYPE_INFO.name);
+    assert_eq!("proc-macro/tests/stmt_with_in_params.sql:7 select_objects_by_type", SELECT_OBJECTS_BY_TYPE_INFO.to_string());
+    assert_eq!(["generated", "temporary", "object_types"], SELECT_OBJECTS_BY_TYPE_INFO.params);
+    assert!(DELETE_TMP_OBJECTS_INFO.params.is_empty());
+}
+
 #[test]
 fn statement_with_in_parameters() {
     assert_eq!(
@@ -78,7 +539,7 @@ fn statement_with_in_parameters() {
     );
 
     let (sql, args) = SelectObjectsByType {
-        object_types: &[ &"FUNCTION" as &ToSql, &"TRIGGER" ],
+        object_types: &[ &"FUNCTION" as &dyn ToSql, &"TRIGGER" ],
         generated: &"N",
         temporary: &"N"
     }.into_sql_with_args();
@@ -93,6 +554,1631 @@ fn statement_with_in_parameters() {
     assert_eq!("TRIGGER", args[3].to_sql());
 }
 
+#[test]
+fn in_parameters_from_iterators() {
+    let object_types = ["FUNCTION", "TRIGGER"];
+    let (sql, args) = SelectObjectsByType {
+        object_types: object_types.iter().map(|object_type| object_type as &dyn ToSql),
+        generated: &"N",
+        temporary: &"Y"
+    }.into_sql_with_args();
+    assert!(sql.contains("object_type in ( :3,:4 ) and generated = :1"));
+    let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["N", "Y", "FUNCTION", "TRIGGER"], args);
+
+    let (_sql, args) = SelectObjectsByType {
+        object_types: vec![ &"VIEW" as &dyn ToSql ],
+        generated: &"N",
+        temporary: &"Y"
+    }.into_sql_with_args();
+    assert_eq!(3, args.len());
+    assert_eq!("VIEW", args[2].to_sql());
+}
+
+#[test]
+fn owned_arguments() {
+    fn build_args(object_types: &[&'static str]) -> SelectObjectsByTypeOwned {
+        SelectObjectsByTypeOwned {
+            object_types: object_types.iter().map(|object_type| Box::new(*object_type) as Box<dyn ToSql>).collect(),
+            generated: Box::new("N"),
+            temporary: Box::new("Y")
+        }
+    }
+    let owned_args = build_args(&["FUNCTION", "TRIGGER"]);
+    let (sql, args) = owned_args.as_args().into_sql_with_args();
+    assert!(sql.contains("object_type in ( :3,:4 ) and generated = :1"));
+    let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["N", "Y", "FUNCTION", "TRIGGER"], args);
+
+    let owned_args = SelectShipCrewMembersOwned { ship: Box::new("Enterprise") };
+    let args : Vec<&dyn ToSql> = owned_args.as_args().into_iter().collect();
+    assert_eq!("Enterprise", args[0].to_sql());
+}
+
+#[test]
+fn arguments_from_map() {
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+    use include_sql_helper::ArgsError;
+
+    let mut map : HashMap<&str, &dyn ToSql> = HashMap::new();
+    map.insert("ship", &"Enterprise");
+    let args : Vec<&dyn ToSql> = SelectSailors::try_from(&map).unwrap().into_iter().collect();
+    assert_eq!("Enterprise", args[0].to_sql());
+    assert_eq!("NULL", args[1].to_sql());
+
+    map.insert("rang", &"captain");
+    assert_eq!(Some(ArgsError::Unknown("rang".to_string())), SelectSailors::try_from(&map).err());
+    map.remove("ship");
+    map.remove("rang");
+    assert_eq!(Some(ArgsError::Missing("ship")), SelectSailors::try_from(&map).err());
+}
+
+#[test]
+fn extra_arguments() {
+    let audit_context : &dyn ToSql = &"audit";
+    let args : Vec<&dyn ToSql> = SelectShipCrewMembers { ship: &"Enterprise" }.with_extra_args(&[audit_context]).collect();
+    let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["Enterprise", "audit"], args);
+
+    let (_sql, args) = SelectObjectsByType {
+        object_types: &[ &"VIEW" as &dyn ToSql ],
+        generated: &"N",
+        temporary: &"Y"
+    }.with_extra_args(&[audit_context]);
+    let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+    assert_eq!(vec!["N", "Y", "VIEW", "audit"], args);
+}
+
+#[test]
+fn statement_with_long_in_list_is_not_reallocated() {
+    let object_type : &dyn ToSql = &"FUNCTION";
+    let object_types = vec![object_type; 1000];
+    let (sql, args) = SelectObjectsByType {
+        object_types: &object_types,
+        generated: &"N",
+        temporary: &"N"
+    }.into_sql_with_args();
+    assert_eq!(1002, args.len());
+    assert!(sql.ends_with(",:1002 ) and temporary = :2"));
+    // the SQL buffer was allocated once with the estimated capacity
+    let estimate = SELECT_OBJECTS_BY_TYPE.len() + include_sql_helper::list_text_len(2000, ":", Some(1003));
+    assert!(sql.capacity() <= estimate);
+}
+
+include_sql!("proc-macro/tests/stmt_with_returning.sql", ":");
+
+/// Generic "executor" that works with any statement arguments
+fn sql_with_args<'a, A>(args: A) -> (include_sql_helper::StatementKind, String, Vec<&'a str>)
+where A: include_sql_helper::IntoSqlWithArgs<'a, dyn ToSql + 'a>
+{
+    let (sql, args) = args.into_sql_with_args();
+    (A::KIND, collapce_whitespace(&sql), args.into_iter().map(|arg| arg.to_sql()).collect())
+}
+
+#[test]
+fn list_and_plain_statements_compose() {
+    use include_sql_helper::StatementKind;
+
+    let (kind, sql, args) = sql_with_args(PromoteObjects {
+        status: &"VALID",
+        object_names: &[ &"F1" as &dyn ToSql, &"F2" ]
+    });
+    assert_eq!(StatementKind::FetchOne, kind);
+    assert_eq!("update user_objects set status = :1 where object_name in ( :2,:3 ) returning object_id", sql);
+    assert_eq!(vec!["VALID", "F1", "F2"], args);
+
+    let (kind, sql, args) = sql_with_args(DeleteObject { object_name: &"F1" });
+    assert_eq!(StatementKind::Execute, kind);
+    assert_eq!("delete from user_objects where object_name = :1", sql);
+    assert_eq!(vec!["F1"], args);
+}
+
+mod postgres {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialects.sql", dialect = postgres);
+
+    #[test]
+    fn numbered_dialect_binds_dups_once() {
+        assert_eq!(
+            "select object_name from user_objects where owner = $1 or created_by = $1 and status = $2",
+            collapce_whitespace(SELECT_OWNER_OBJECTS)
+        );
+        let args : Vec<_> = SelectOwnerObjects { owner: &"SYS", status: &"VALID" }.into_iter().collect();
+        assert_eq!(2, args.len());
+        let args : [&dyn ToSql; 2] = SelectOwnerObjects { owner: &"SYS", status: &"VALID" }.as_array();
+        assert_eq!(["SYS", "VALID"], [args[0].to_sql(), args[1].to_sql()]);
+        assert_eq!("SYS", args[0].to_sql());
+        assert_eq!("VALID", args[1].to_sql());
+
+        let (sql, args) = SelectDialectObjects {
+            object_types: &[ &"TABLE" as &dyn ToSql, &"VIEW" ],
+            owner: &"SYS"
+        }.into_sql_with_args();
+        assert_eq!(
+            "select object_name from user_objects where object_type in ( $2,$3 ) and owner = $1 or object_type in ( $2,$3 ) and owner = $1",
+            collapce_whitespace(&sql)
+        );
+        assert_eq!(3, args.len());
+        assert_eq!("SYS", args[0].to_sql());
+        assert_eq!("TABLE", args[1].to_sql());
+        assert_eq!("VIEW", args[2].to_sql());
+    }
+}
+
+mod mssql {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialects.sql", dialect = mssql);
+
+    #[test]
+    fn placeholders_have_multi_character_prefix() {
+        assert_eq!(
+            "select object_name from user_objects where owner = @p1 or created_by = @p1 and status = @p2",
+            collapce_whitespace(SELECT_OWNER_OBJECTS)
+        );
+        let (sql, args) = SelectDialectObjects {
+            object_types: &[ &"TABLE" as &dyn ToSql, &"VIEW" ],
+            owner: &"SYS"
+        }.into_sql_with_args();
+        assert_eq!(
+            "select object_name from user_objects where object_type in ( @p2,@p3 ) and owner = @p1 or object_type in ( @p2,@p3 ) and owner = @p1",
+            collapce_whitespace(&sql)
+        );
+        assert_eq!(3, args.len());
+    }
+
+    mod with_prefix {
+        use super::super::{ToSql, collapce_whitespace};
+        use include_sql::include_sql;
+
+        include_sql!("proc-macro/tests/dialects.sql", "@p");
+
+        #[test]
+        fn prefix_is_the_same_as_preset() {
+            assert_eq!(super::SELECT_OWNER_OBJECTS, SELECT_OWNER_OBJECTS);
+            let (sql, _args) = SelectDialectObjects {
+                object_types: &[ &"TABLE" as &dyn ToSql ],
+                owner: &"SYS"
+            }.into_sql_with_args();
+            assert_eq!(
+                "select object_name from user_objects where object_type in ( @p2 ) and owner = @p1 or object_type in ( @p2 ) and owner = @p1",
+                collapce_whitespace(&sql)
+            );
+        }
+    }
+}
+
+mod mysql {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialects.sql", dialect = mysql);
+
+    #[test]
+    fn unnumbered_dialect_repeats_dups() {
+        assert_eq!(
+            "select object_name from user_objects where owner = ? or created_by = ? and status = ?",
+            collapce_whitespace(SELECT_OWNER_OBJECTS)
+        );
+        let args = select_owner_objects_args! { owner: &"SYS", status: &"VALID" };
+        assert_eq!(3, args.len());
+        assert_eq!(3, SelectOwnerObjects { owner: &"SYS", status: &"VALID" }.as_array().len());
+        assert_eq!("SYS", args[0].to_sql());
+        assert_eq!("SYS", args[1].to_sql());
+        assert_eq!("VALID", args[2].to_sql());
+
+        let (sql, args) = SelectDialectObjects {
+            object_types: &[ &"TABLE" as &dyn ToSql, &"VIEW" ],
+            owner: &"SYS"
+        }.into_sql_with_args();
+        assert_eq!(
+            "select object_name from user_objects where object_type in ( ?,? ) and owner = ? or object_type in ( ?,? ) and owner = ?",
+            collapce_whitespace(&sql)
+        );
+        assert_eq!(6, args.len());
+        assert_eq!("TABLE", args[0].to_sql());
+        assert_eq!("VIEW", args[1].to_sql());
+        assert_eq!("SYS", args[2].to_sql());
+        assert_eq!("TABLE", args[3].to_sql());
+        assert_eq!("VIEW", args[4].to_sql());
+        assert_eq!("SYS", args[5].to_sql());
+
+        // iterators are consumed once, but repeated lists are still bound for each occurrence
+        let object_types = ["TABLE", "VIEW"];
+        let (_sql, args) = SelectDialectObjects {
+            object_types: object_types.iter().map(|object_type| object_type as &dyn ToSql),
+            owner: &"SYS"
+        }.into_sql_with_args();
+        let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["TABLE", "VIEW", "SYS", "TABLE", "VIEW", "SYS"], args);
+    }
+
+    include_sql!("proc-macro/tests/stmt_mysql_admin.sql", dialect = mysql);
+
+    #[test]
+    fn session_variables_and_load_data() {
+        assert_eq!("SET @row_no := 0", RESET_ROW_COUNTER);
+        assert_eq!(
+            "SELECT @row_no := @row_no + 1 AS row_no, name FROM sailors WHERE ship_id = ? ORDER BY name",
+            collapce_whitespace(SELECT_NUMBERED_SAILORS)
+        );
+        assert_eq!("SET @ship:=?, @@session.sql_mode = 'ANSI_QUOTES'", REMEMBER_SHIP);
+        assert_eq!(1, RememberShip { ship: &"HMS Indefatigable" }.as_array().len());
+        // LOAD DATA cannot be prepared, thus it is included as is
+        assert_eq!(
+            "LOAD DATA LOCAL INFILE '/var/lib/mysql-files/fleet:sailors.csv' INTO TABLE sailors FIELDS TERMINATED BY ',' ENCLOSED BY '\"' IGNORE 1 LINES (name, @station) SET station = UPPER(@station)",
+            collapce_whitespace(LOAD_SAILORS)
+        );
+        assert_eq!(include_sql_helper::StatementKind::Execute, LOAD_SAILORS_KIND);
+    }
+
+    mod with_prefix {
+        use super::super::{ToSql, collapce_whitespace};
+        use include_sql::include_sql;
+
+        include_sql!("proc-macro/tests/dialects.sql", "?", numbering = none, macro_prefix = "unnumbered_");
+
+        #[test]
+        fn unnumbered_prefix_is_the_same_as_preset() {
+            assert_eq!(super::SELECT_OWNER_OBJECTS, SELECT_OWNER_OBJECTS);
+            assert_eq!(super::SELECT_DIALECT_OBJECTS, SELECT_DIALECT_OBJECTS);
+            let args : Vec<&str> = SelectOwnerObjects { owner: &"SYS", status: &"VALID" }.into_iter().map(|arg| arg.to_sql()).collect();
+            assert_eq!(vec!["SYS", "SYS", "VALID"], args);
+
+            let (sql, args) = SelectDialectObjects {
+                object_types: &[ &"TABLE" as &dyn ToSql ],
+                owner: &"SYS"
+            }.into_sql_with_args();
+            assert_eq!(
+                "select object_name from user_objects where object_type in ( ? ) and owner = ? or object_type in ( ? ) and owner = ?",
+                collapce_whitespace(&sql)
+            );
+            assert_eq!(4, args.len());
+        }
+    }
+}
+
+#[test]
+fn idempotent_statements() {
+    use include_sql_helper::IntoSqlWithArgs;
+
+    fn is_idempotent<'a, A: IntoSqlWithArgs<'a, dyn ToSql + 'a>>(_args: &A) -> bool {
+        A::IDEMPOTENT
+    }
+
+    assert_eq!([true], [DELETE_OBJECT_IDEMPOTENT]);
+    assert!(is_idempotent(&DeleteObject { object_name: &"F1" }));
+    assert!(!is_idempotent(&PromoteObjects { status: &"VALID", object_names: &[ &"F1" as &dyn ToSql ] }));
+}
+
+#[cfg(feature = "inventory")]
+#[test]
+fn statements_are_registered_in_inventory() {
+    let stmt = include_sql_helper::statements().find(|stmt| stmt.name == "user_tables_count");
+    assert!(stmt.is_some());
+    if let Some( stmt ) = stmt {
+        assert_eq!("proc-macro/tests/named_statements.sql", stmt.file);
+        assert_eq!(USER_TABLES_COUNT, stmt.text);
+        assert!(!stmt.idempotent);
+    }
+    let stmt = include_sql_helper::statements().find(|stmt| stmt.name == "delete_object");
+    assert!(stmt.is_some_and(|stmt| stmt.idempotent));
+}
+
+mod prefixed_macros {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/stmt_with_params.sql", ":", macro_prefix = "prefixed_");
+
+    #[test]
+    fn macro_names_with_prefix() {
+        let args = prefixed_select_invalid_objects_args! { object_type: &"VIEW" };
+        assert_eq!(1, args.len());
+        assert_eq!("VIEW", args[0].to_sql());
+        let args = using_prefixed_select_invalid_objects_args! { object_type: &"TYPE" };
+        assert_eq!("TYPE", args[0].to_sql());
+    }
+}
+
+mod rows_via_trait {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    /// Column value that the test rows can convert their text into.
+    pub trait FromText: Sized {
+        fn from_text(text: &str) -> Result<Self, String>;
+    }
+
+    impl FromText for i32 {
+        fn from_text(text: &str) -> Result<Self, String> { text.parse().map_err(|_| format!("`{}` is not a number", text)) }
+    }
+
+    impl FromText for i64 {
+        fn from_text(text: &str) -> Result<Self, String> { text.parse().map_err(|_| format!("`{}` is not a number", text)) }
+    }
+
+    impl FromText for String {
+        fn from_text(text: &str) -> Result<Self, String> { Ok(text.to_string()) }
+    }
+
+    impl FromText for Option<String> {
+        fn from_text(text: &str) -> Result<Self, String> { Ok(if text == "NULL" { None } else { Some(text.to_string()) }) }
+    }
+
+    pub trait RowColumns {
+        type Error;
+        fn get_column<T: FromText>(&self, index: usize) -> Result<T, Self::Error>;
+    }
+
+    impl RowColumns for [&str] {
+        type Error = String;
+        fn get_column<T: FromText>(&self, index: usize) -> Result<T, String> {
+            T::from_text(self.get(index).ok_or_else(|| format!("no column {}", index))?)
+        }
+    }
+
+    include_sql!("proc-macro/tests/stmt_with_row_types.sql", "$", row_trait = RowColumns);
+
+    #[test]
+    fn row_structs_from_trait_rows() {
+        let row = SelectCrewRosterRow::from_row(&["1", "Riker", "NULL"][..]).unwrap();
+        assert_eq!((1, "Riker", None), (row.id, row.name.as_str(), row.rank));
+        let row = SelectCrewRosterRow::from_row(&["2", "Picard", "captain"][..]).unwrap();
+        assert_eq!(Some("captain".to_string()), row.rank);
+        assert_eq!("`one` is not a number", InsertLocatedShipRow::from_row(&["one", "Enterprise"][..]).err().unwrap());
+    }
+}
+
+mod naming {
+    use include_sql::include_sql;
+
+    mod verbatim {
+        use super::include_sql;
+
+        include_sql!("proc-macro/tests/stmt_kinds.sql", "$", const_names = verbatim);
+
+        #[test]
+        fn verbatim_constant_names() {
+            assert_eq!("select object_name from user_objects where temporary = 'Y'", select_tmp_objects);
+            assert_eq!(include_sql_helper::StatementKind::FetchOne, insert_tmp_object_kind);
+            assert_eq!("delete_tmp_objects", delete_tmp_objects_info.name);
+        }
+    }
+
+    mod prefixed_statics {
+        use super::include_sql;
+
+        include_sql!("proc-macro/tests/stmt_healthchecks.sql", "$", const_prefix = "SQL_", sql_items = static);
+
+        #[test]
+        fn prefixed_static_items() {
+            let ping : &'static &'static str = &SQL_PING;
+            assert_eq!(*ping, HEALTHCHECKS[0].sql);
+            assert_eq!(include_sql_helper::StatementKind::Query, SQL_CHECK_SHIPS_TABLE_KIND);
+        }
+    }
+}
+
+mod namespaces {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/stmt_with_params.sql", ":", namespace = "crew");
+    include_sql!("proc-macro/tests/stmt_with_params.sql", "$", namespace = "fleet", const_prefix = "SQL_");
+
+    #[test]
+    fn same_statements_in_one_module() {
+        assert!(CREW_SELECT_INVALID_OBJECTS.contains("object_type = :1"));
+        assert!(FLEET_SQL_SELECT_INVALID_OBJECTS.contains("object_type = $1"));
+        assert_eq!("select_invalid_objects", FLEET_SQL_SELECT_INVALID_OBJECTS_INFO.name);
+        let args = CrewSelectInvalidObjects { object_type: &"VIEW" };
+        assert_eq!("VIEW", args.into_iter().next().unwrap().to_sql());
+        let args = FleetSelectInvalidObjects { object_type: &"TYPE" };
+        assert_eq!("TYPE", args.into_iter().next().unwrap().to_sql());
+        let args = crew_select_invalid_objects_args! { object_type: &"TABLE" };
+        assert_eq!("TABLE", args[0].to_sql());
+        let args = using_fleet_select_invalid_objects_args! { object_type: &"INDEX" };
+        assert_eq!("INDEX", args[0].to_sql());
+    }
+}
+
+mod query_tags {
+    use super::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_ship_captain
+        -- tag: team=fleet
+        SELECT captain FROM ships WHERE id = :id
+
+        -- name: select_ship_names
+        SELECT name FROM ships; -- all of them
+
+        -- name: select_sailors_by_ranks
+        SELECT name FROM sailors WHERE rank IN (:ranks)
+    ", "$", tag = "service=crew");
+
+    mod blocks {
+        use include_sql::include_sql_mod;
+
+        #[include_sql_mod("proc-macro/tests/stmt_with_cond_blocks.sql", "$", tag = "service=crew")]
+        mod search {
+            use crate::ToSql;
+        }
+
+        #[test]
+        fn assembled_text_is_tagged() {
+            let (sql, _) = search::SearchSailors { ship: &"Enterprise", rank: None, name: None }.into_sql_with_args();
+            assert!(sql.ends_with("ORDER BY name /* service=crew,stmt=search_sailors */"), "{}", sql);
+        }
+    }
+
+    #[test]
+    fn tag_comments() {
+        assert_eq!("SELECT captain FROM ships WHERE id = $1 /* service=crew,team=fleet,stmt=select_ship_captain */", SELECT_SHIP_CAPTAIN);
+        assert_eq!("SELECT name FROM ships /* service=crew,stmt=select_ship_names */; -- all of them", SELECT_SHIP_NAMES);
+        let ranks = [&"captain" as &dyn ToSql, &"ensign"];
+        let (sql, args) = SelectSailorsByRanks { ranks: &ranks }.into_sql_with_args();
+        assert_eq!("SELECT name FROM sailors WHERE rank IN ($1,$2) /* service=crew,stmt=select_sailors_by_ranks */", sql);
+        assert_eq!(2, args.len());
+    }
+}
+
+mod arg_builders {
+    use include_sql::include_sql_mod;
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_optional_params.sql", "$", builder = true)]
+    mod optional {
+        use crate::ToSql;
+        use include_sql_helper::ArgsError;
+
+        #[test]
+        fn optional_arguments_can_be_left_out() {
+            let args : Vec<&str> = SelectSailors::builder().ship(&"Enterprise").build().unwrap().into_iter().map(|arg| arg.to_sql()).collect();
+            assert_eq!(vec!["Enterprise", "NULL"], args);
+            let args : Vec<&str> = SelectSailors::builder().rank(&"captain").ship(&"Enterprise").build().unwrap().into_iter().map(|arg| arg.to_sql()).collect();
+            assert_eq!(vec!["Enterprise", "captain"], args);
+            assert_eq!(Some(ArgsError::Missing("ship")), SelectSailors::builder().rank(&"captain").build().err());
+        }
+
+        #[test]
+        fn lists_are_set_by_their_builder_methods() {
+            let ships = [ &"Enterprise" as &dyn ToSql, &"Voyager" ];
+            let (sql, args) = SelectFleetSailors::builder().ships(&ships).build().unwrap().into_sql_with_args();
+            assert_eq!("SELECT id, name FROM sailors WHERE ship_id IN ($2,$3) AND ($1 IS NULL OR rank = $1)", sql);
+            assert_eq!(3, args.len());
+            assert_eq!(Some(ArgsError::Missing("ships")), SelectFleetSailors::<&[&dyn ToSql]>::builder().build().err());
+        }
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_cond_blocks.sql", "$", builder = true)]
+    mod blocks {
+        use crate::ToSql;
+
+        #[test]
+        fn blocks_are_included_when_their_arguments_are_set() {
+            let (sql, args) = SearchSailors::builder().ship(&"Enterprise").name(&"W%").build().unwrap().into_sql_with_args();
+            assert_eq!(
+                "SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND (name LIKE $2 OR nickname LIKE $2) AND ship_id = $1 ORDER BY name",
+                crate::collapce_whitespace(&sql)
+            );
+            assert_eq!(2, args.len());
+        }
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_ident_params.sql", "$", builder = true)]
+    mod idents {
+        use crate::ToSql;
+        use include_sql_helper::ArgsError;
+
+        #[test]
+        fn identifiers_are_required() {
+            let args = CountArchived::builder().archive(CountArchivedArchive::Sailors2020).build().unwrap();
+            assert_eq!(CountArchivedArchive::Sailors2020, args.archive);
+            assert_eq!(Some(ArgsError::Missing("archive")), CountArchived::builder().build().err());
+        }
+    }
+}
+
+#[include_sql::include_sql_mod("proc-macro/tests/stmt_with_param_groups.sql", "$", builder = true)]
+mod param_groups {
+    use crate::ToSql;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn grouped_arguments_are_passed_together() {
+        let context = Context { tenant: &"acme", user: &"admin" };
+        let args : Vec<&str> = SelectTenantSailors { context, ship: &"Enterprise" }.into_iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["acme", "Enterprise", "admin"], args);
+        let args : Vec<&str> = CountTenantShips { context }.as_array().iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["admin", "acme"], args);
+        let args : Vec<&str> = select_tenant_sailors_args!(context: context, ship: &"Voyager").iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["acme", "Voyager", "admin"], args);
+    }
+
+    #[test]
+    fn statements_without_all_grouped_parameters_keep_their_fields() {
+        let args : Vec<&str> = SelectTenant { tenant: &"acme" }.into_iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["acme"], args);
+    }
+
+    #[test]
+    fn grouped_arguments_are_set_one_by_one() {
+        let args = SelectTenantSailors::builder().tenant(&"acme").user(&"admin").ship(&"Enterprise").build().unwrap();
+        assert_eq!("admin", args.context.user.to_sql());
+        let mut map : HashMap<&str, &dyn ToSql> = HashMap::new();
+        map.insert("tenant", &"acme");
+        map.insert("user", &"admin");
+        let args = CountTenantShips::try_from(&map).unwrap();
+        assert_eq!("acme", args.context.tenant.to_sql());
+    }
+}
+
+#[include_sql::include_sql_mod("proc-macro/tests/stmt_with_optional_params.sql", "$", args_trait = true)]
+mod args_traits {
+    use crate::ToSql;
+
+    struct Sailor {
+        ship: &'static str,
+        rank: Option<&'static str>
+    }
+
+    impl<'a> SelectSailorsArgs<'a> for &'a Sailor {
+        fn ship(&self) -> &'a dyn ToSql {
+            &self.ship
+        }
+
+        fn rank(&self) -> Option<&'a dyn ToSql> {
+            self.rank.as_ref().map(|rank| rank as &dyn ToSql)
+        }
+    }
+
+    fn bound<'a>(args: impl SelectSailorsArgs<'a>) -> Vec<&'a str> {
+        args.bind_args().iter().map(|arg| arg.to_sql()).collect()
+    }
+
+    #[test]
+    fn argument_structs_implement_the_trait() {
+        assert_eq!(vec!["Enterprise", "NULL"], bound(SelectSailors { ship: &"Enterprise", rank: None }));
+    }
+
+    #[test]
+    fn application_types_are_bound_via_the_trait() {
+        let sailor = Sailor { ship: "Voyager", rank: Some("captain") };
+        assert_eq!(vec!["Voyager", "captain"], bound(&sailor));
+    }
+}
+
+mod args_from_fields {
+    use include_sql::include_sql_mod;
+
+    struct Sailor {
+        ship: &'static str,
+        rank: Option<&'static str>,
+        name: Option<&'static str>
+    }
+
+    struct Fleet {
+        ships: Vec<&'static str>,
+        rank: Option<&'static str>
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_optional_params.sql", "$", macro_prefix = "domain_")]
+    mod optional {
+        use crate::ToSql;
+        use super::{Sailor, Fleet};
+
+        #[test]
+        fn arguments_are_taken_from_fields() {
+            let sailor = Sailor { ship: "Enterprise", rank: None, name: Some("Worf") };
+            let args : Vec<&str> = domain_select_sailors_args_from!(sailor).into_iter().map(|arg| arg.to_sql()).collect();
+            assert_eq!(vec!["Enterprise", "NULL"], args);
+            assert_eq!(Some("Worf"), sailor.name);
+
+            let fleet = Fleet { ships: vec!["Enterprise", "Voyager"], rank: Some("captain") };
+            let (sql, args) = domain_select_fleet_sailors_args_from!(fleet).into_sql_with_args();
+            assert_eq!("SELECT id, name FROM sailors WHERE ship_id IN ($2,$3) AND ($1 IS NULL OR rank = $1)", sql);
+            let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+            assert_eq!(vec!["captain", "Enterprise", "Voyager"], args);
+        }
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_cond_blocks.sql", "$", macro_prefix = "domain_")]
+    mod blocks {
+        use crate::ToSql;
+        use super::Sailor;
+
+        #[test]
+        fn fields_with_values_include_their_blocks() {
+            let sailor = Sailor { ship: "Enterprise", rank: Some("captain"), name: None };
+            let (sql, args) = domain_search_sailors_args_from!(sailor).into_sql_with_args();
+            assert_eq!("SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND rank = $2 ORDER BY name", crate::collapce_whitespace(&sql));
+            assert_eq!(2, args.len());
+        }
+    }
+}
+
+mod debug_args {
+    use include_sql::include_sql_mod;
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_cond_blocks.sql", "$", debug_args = true)]
+    mod blocks {
+        use crate::ToSql;
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_ident_params.sql", "$", debug_args = true)]
+    mod idents {
+        use crate::ToSql;
+
+        #[test]
+        fn identifiers_are_shown() {
+            let args = CountArchived { archive: CountArchivedArchive::Sailors2020 };
+            assert_eq!("CountArchived { archive: Sailors2020 }", format!("{:?}", args));
+        }
+    }
+
+    mod refs {
+        use crate::ToSql;
+
+        include_sql::inline_sql!("
+            -- name: select_ship_crew_by_ranks
+            SELECT name FROM sailors WHERE ship_id = :ship AND rank IN (:ranks)
+        ", "$", debug_args = true);
+
+        #[test]
+        fn argument_values_are_hidden() {
+            let ranks = [&"captain" as &dyn ToSql];
+            let args = SelectShipCrewByRanks { ship: &"NCC-1701", ranks: &ranks };
+            assert_eq!("SelectShipCrewByRanks { ship: .., ranks: .. }", format!("{:?}", args));
+        }
+    }
+
+    mod generic {
+        pub(crate) trait ToSql {}
+        impl ToSql for i32 {}
+        impl ToSql for &str {}
+
+        include_sql::inline_sql!("
+            -- name: select_ship_crew_by_ranks
+            SELECT name FROM sailors WHERE ship_id = :ship AND rank IN (:ranks)
+
+            -- name: select_ship_captain
+            SELECT captain FROM ships WHERE id = :id
+        ", "$", args = generic, debug_args = true);
+
+        #[test]
+        fn generic_argument_values_are_printed() {
+            let args = SelectShipCrewByRanks { ship: 1701, ranks: &["captain", "ensign"] };
+            assert_eq!(r#"SelectShipCrewByRanks { ship: 1701, ranks: ["captain", "ensign"] }"#, format!("{:?}", args));
+            assert_eq!("SelectShipCaptain { id: 74656 }", format!("{:?}", SelectShipCaptain { id: 74656 }));
+        }
+    }
+
+    #[test]
+    fn optional_arguments_are_shown() {
+        let args = blocks::SearchSailors { ship: &"Enterprise", rank: None, name: Some(&"W%") };
+        assert_eq!("SearchSailors { ship: .., rank: None, name: Some(..) }", format!("{:?}", args));
+    }
+}
+
+mod documented_fields {
+    use super::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_ship_crew_by_ranks
+        -- Selects sailors of the ship who have one of the ranks
+        -- :ship - id of the ship
+        -- :ranks - ranks of the sailors,
+        -- :ranks - e.g. 'captain' or 'ensign'
+        SELECT name FROM sailors WHERE ship_id = :ship AND rank IN (:ranks)
+    ", "$");
+
+    #[test]
+    fn descriptions_are_not_statement_text() {
+        assert_eq!("SELECT name FROM sailors WHERE ship_id = $1 AND rank IN ()", SELECT_SHIP_CREW_BY_RANKS);
+        let ranks = [&"captain" as &dyn ToSql];
+        let (sql, args) = SelectShipCrewByRanks { ship: &"NCC-1701", ranks: &ranks }.into_sql_with_args();
+        assert_eq!("SELECT name FROM sailors WHERE ship_id = $1 AND rank IN ($2)", sql);
+        assert_eq!(2, args.len());
+    }
+}
+
+mod statements_in_use {
+    use super::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_ship_captain
+        SELECT captain FROM ships WHERE id = :id
+
+        -- name: count_ship_crew
+        SELECT count(*) FROM sailors WHERE ship_id = :ship
+    ", "$", unused_in = "proc-macro/tests");
+
+    #[test]
+    fn each_statement_is_used() {
+        assert_eq!("SELECT captain FROM ships WHERE id = $1", SELECT_SHIP_CAPTAIN);
+        let args = CountShipCrew { ship: &"NCC-1701" };
+        assert_eq!("NCC-1701", args.ship.to_sql());
+    }
+}
+
+mod windows_line_endings {
+    use include_sql::include_sql_mod;
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_crlf.sql", "$")]
+    mod crlf {
+        use crate::ToSql;
+    }
+
+    #[test]
+    fn crlf_and_byte_order_mark() {
+        assert_eq!("SELECT id, name\n  FROM sailors\n WHERE ship_id = $1", crlf::SELECT_SHIP_CREW);
+        assert_eq!("SELECT name\n  FROM ships\n WHERE id = $1", crlf::SELECT_SHIP_NAME);
+        assert_eq!(["ship"], crlf::SELECT_SHIP_CREW_INFO.params);
+    }
+}
+
+mod comments {
+    use super::collapce_whitespace;
+    use include_sql::include_sql_mod;
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_comments.sql", "$")]
+    mod inline {
+        use crate::ToSql;
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_comments.sql", "$", comments = keep)]
+    mod kept {
+        use crate::ToSql;
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_comments.sql", "$", comments = strip)]
+    mod stripped {
+        use crate::ToSql;
+    }
+
+    #[test]
+    fn inline_comments() {
+        assert_eq!(
+            "SELECT entry /* the text */, note || ' -- not a comment' AS note FROM ship_logs WHERE ship_id = $1 -- :author is compared below /* written by the captain */ AND author = 'captain'",
+            collapce_whitespace(inline::SELECT_SHIP_LOGS)
+        );
+        assert_eq!(["ship"], inline::SELECT_SHIP_LOGS_INFO.params);
+    }
+
+    #[test]
+    fn fingerprints_ignore_comments() {
+        assert_eq!(inline::SELECT_SHIP_LOGS_FINGERPRINT, kept::SELECT_SHIP_LOGS_FINGERPRINT);
+        assert_eq!(inline::SELECT_SHIP_LOGS_FINGERPRINT, stripped::SELECT_SHIP_LOGS_FINGERPRINT);
+    }
+
+    #[test]
+    fn kept_comments() {
+        assert!(kept::SELECT_SHIP_LOGS.contains("FROM ship_logs\n-- entries of the ship only\n WHERE ship_id = $1"));
+        assert!(!kept::SELECT_SHIP_LOGS.contains("Selects log entries"));
+    }
+
+    #[test]
+    fn stripped_comments() {
+        assert_eq!(
+            "SELECT entry , note || ' -- not a comment' AS note\n  FROM ship_logs\n WHERE ship_id = $1\n   AND author = 'captain'",
+            stripped::SELECT_SHIP_LOGS
+        );
+    }
+}
+
+mod minified {
+    use include_sql::include_sql_mod;
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_comments.sql", "$", minify = true)]
+    mod logs {
+        use crate::ToSql;
+    }
+
+    #[include_sql_mod("proc-macro/tests/stmt_with_cond_blocks.sql", "$", minify = true)]
+    mod search {
+        use crate::ToSql;
+    }
+
+    #[test]
+    fn minified_text() {
+        assert_eq!(
+            "SELECT entry , note || ' -- not a comment' AS note FROM ship_logs WHERE ship_id = $1 AND author = 'captain'",
+            logs::SELECT_SHIP_LOGS
+        );
+        assert_eq!(
+            "SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND rank = $2 AND (name LIKE $3 OR nickname LIKE $3) AND ship_id = $1 ORDER BY name",
+            search::SEARCH_SAILORS
+        );
+    }
+
+    #[test]
+    fn fingerprints_ignore_formatting() {
+        assert_eq!(crate::SEARCH_SAILORS_FINGERPRINT, search::SEARCH_SAILORS_FINGERPRINT);
+        assert_ne!(logs::SELECT_SHIP_LOGS_FINGERPRINT, search::SEARCH_SAILORS_FINGERPRINT);
+    }
+
+    #[test]
+    fn minified_conditional_blocks() {
+        let (sql, args) = search::SearchSailors { ship: &"Enterprise", rank: None, name: Some(&"W%") }.into_sql_with_args();
+        assert_eq!("SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND (name LIKE $2 OR nickname LIKE $2) AND ship_id = $1 ORDER BY name", sql);
+        assert_eq!(2, args.len());
+    }
+}
+
+mod semicolons {
+    use super::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_ship_name
+        SELECT name FROM ships WHERE id = :id; -- by id
+        -- name: upper_ship_names!
+        BEGIN UPDATE ships SET name = upper(name); END;
+    ", dialect = oracle);
+
+    mod kept {
+        use super::ToSql;
+
+        include_sql::inline_sql!("
+            -- name: select_ship_name
+            SELECT name FROM ships WHERE id = :id;
+        ", dialect = oracle, semicolons = keep);
+
+        #[test]
+        fn kept_semicolons() {
+            assert_eq!("SELECT name FROM ships WHERE id = :1;", SELECT_SHIP_NAME);
+        }
+    }
+
+    mod stripped {
+        use super::ToSql;
+
+        include_sql::inline_sql!("
+            -- name: select_sailor_name
+            SELECT name FROM sailors WHERE id = :id AND rank <> ';';
+        ", "$", semicolons = strip);
+
+        #[test]
+        fn stripped_semicolons() {
+            assert_eq!("SELECT name FROM sailors WHERE id = $1 AND rank <> ';'", SELECT_SAILOR_NAME);
+        }
+    }
+
+    #[test]
+    fn terminating_semicolons() {
+        assert_eq!("SELECT name FROM ships WHERE id = :1 -- by id", SELECT_SHIP_NAME);
+        assert_eq!("BEGIN UPDATE ships SET name = upper(name); END;", UPPER_SHIP_NAMES);
+    }
+}
+
+mod numbered_without_dedup {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialects.sql", "$", dedup_params = false);
+
+    #[test]
+    fn repeated_params_get_own_numbers() {
+        assert_eq!(
+            "select object_name from user_objects where owner = $1 or created_by = $2 and status = $3",
+            collapce_whitespace(SELECT_OWNER_OBJECTS)
+        );
+        let args = select_owner_objects_args! { owner: &"SYS", status: &"VALID" };
+        assert_eq!(3, args.len());
+        assert_eq!("SYS", args[1].to_sql());
+
+        let (sql, args) = SelectDialectObjects {
+            object_types: &[ &"TABLE" as &dyn ToSql, &"VIEW" ],
+            owner: &"SYS"
+        }.into_sql_with_args();
+        assert_eq!(
+            "select object_name from user_objects where object_type in ( $3,$4 ) and owner = $1 or object_type in ( $5,$6 ) and owner = $2",
+            collapce_whitespace(&sql)
+        );
+        let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["SYS", "SYS", "TABLE", "VIEW", "TABLE", "VIEW"], args);
+    }
+}
+
+mod pagination {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/stmt_with_pagination.sql", dialect = postgres);
+
+    #[test]
+    fn queries_get_limit_and_offset() {
+        // the clause starts a new line after the comment at the end of the query
+        assert!(SELECT_SHIP_CREW.ends_with(" ORDER BY name -- pages need a stable order\nLIMIT $2 OFFSET $3"));
+        let args : Vec<&str> = SelectShipCrew { ship: &"Indefatigable", limit: &"20", offset: &"40" }.into_iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["Indefatigable", "20", "40"], args);
+    }
+
+    #[test]
+    fn queries_can_be_wrapped_into_rownum_ranges() {
+        assert_eq!(
+            "SELECT * FROM (SELECT q.*, ROWNUM AS row_num FROM (SELECT name, rank FROM sailors WHERE ship_id = $1 ORDER BY name ) q WHERE ROWNUM <= $2 + $3) WHERE row_num > $2;",
+            collapce_whitespace(SELECT_SHIP_CREW_ROWNUM)
+        );
+        assert_eq!(["ship", "offset", "limit"], SELECT_SHIP_CREW_ROWNUM_INFO.params);
+    }
+
+    mod oracle {
+        use super::super::ToSql;
+
+        include_sql::inline_sql!("
+            -- name: select_ship_crew
+            -- paginate
+            SELECT name FROM sailors WHERE ship_id = :ship ORDER BY name
+        ", dialect = oracle);
+
+        #[test]
+        fn queries_fetch_the_page_rows() {
+            assert_eq!("SELECT name FROM sailors WHERE ship_id = :1 ORDER BY name OFFSET :2 ROWS FETCH NEXT :3 ROWS ONLY", SELECT_SHIP_CREW.trim());
+            let args : Vec<&dyn ToSql> = SelectShipCrew { ship: &"Indefatigable", offset: &"40", limit: &"20" }.into_iter().collect();
+            assert_eq!(3, args.len());
+        }
+    }
+}
+
+mod sorting {
+    use super::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_ship_crew
+        -- order_by: name, rank, s.joined_at
+        -- paginate
+        SELECT name, rank FROM sailors s WHERE ship_id = :ship
+    ", "$");
+
+    #[test]
+    fn order_by_clause_is_spliced_before_the_page() {
+        assert_eq!("SELECT name, rank FROM sailors s WHERE ship_id = $1 ORDER BY name asc LIMIT $2 OFFSET $3", SELECT_SHIP_CREW.trim());
+        let (sql, args) = SelectShipCrew {
+            ship: &"Indefatigable",
+            order_by: SelectShipCrewOrderBy::SJoinedAt,
+            order_dir: SelectShipCrewOrderDir::Desc,
+            limit: &"20",
+            offset: &"0"
+        }.into_sql_with_args();
+        assert_eq!("SELECT name, rank FROM sailors s WHERE ship_id = $1 ORDER BY s.joined_at desc LIMIT $2 OFFSET $3", sql.trim());
+        let args : Vec<&str> = args.into_iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["Indefatigable", "20", "0"], args);
+    }
+}
+
+mod dialect_variants {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialect_variants.sql", dialects = ["sqlite", "postgres"]);
+
+    #[test]
+    #[cfg(not(feature = "postgres"))]
+    fn first_dialect_is_selected_by_default() {
+        assert_eq!("SELECT name, rank FROM sailors WHERE ship_id = ?1", SELECT_SHIP_CREW);
+        assert_eq!("SELECT name FROM sailors WHERE ship_id = ?1 AND joined_at > datetime('now', '-1 year')", SELECT_RECENT_SAILORS);
+        assert_eq!("proc-macro/tests/dialect_variants.sql", SELECT_RECENT_SAILORS_INFO.file);
+    }
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn feature_selects_the_dialect_and_its_overrides() {
+        assert_eq!("SELECT name, rank FROM sailors WHERE ship_id = $1", SELECT_SHIP_CREW);
+        assert_eq!("SELECT name FROM sailors WHERE ship_id = $1 AND joined_at > now() - interval '1 year'", SELECT_RECENT_SAILORS);
+        assert_eq!("proc-macro/tests/dialect_variants.postgres.sql", SELECT_RECENT_SAILORS_INFO.file);
+        assert_eq!(4, SELECT_RECENT_SAILORS_INFO.line);
+    }
+
+    #[test]
+    fn statements_share_argument_structs() {
+        let args : Vec<&dyn ToSql> = SelectRecentSailors { ship: &"Indefatigable" }.into_iter().collect();
+        assert_eq!(1, args.len());
+    }
+}
+
+mod gated_statements {
+    use super::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_sailor_ranks
+        -- cfg: feature = \"postgres\"
+        SELECT name, rank FROM sailors WHERE ship_id = :ship
+
+        -- name: select_ship_crew
+        SELECT name, rank FROM sailors WHERE ship_id = :ship
+
+        -- name: count_ships
+        -- cfg: not(feature = \"postgres\")
+        SELECT count(*) FROM ships
+    ", "$", registry = true);
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn items_of_enabled_statements_are_generated() {
+        assert_eq!("SELECT name, rank FROM sailors WHERE ship_id = $1", super::collapce_whitespace(SELECT_SAILOR_RANKS));
+        let args : Vec<&dyn ToSql> = SelectSailorRanks { ship: &"Indefatigable" }.into_iter().collect();
+        assert_eq!(1, args.len());
+        let names : Vec<&str> = SQL_STATEMENTS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(vec!["select_sailor_ranks", "select_ship_crew"], names);
+    }
+
+    #[test]
+    #[cfg(not(feature = "postgres"))]
+    fn items_of_disabled_statements_are_left_out() {
+        assert_eq!("SELECT count(*) FROM ships", super::collapce_whitespace(COUNT_SHIPS));
+        let names : Vec<&str> = SQL_STATEMENTS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(vec!["select_ship_crew", "count_ships"], names);
+    }
+
+    #[test]
+    fn statements_do_not_share_text_of_gated_ones() {
+        assert_eq!("SELECT name, rank FROM sailors WHERE ship_id = $1", super::collapce_whitespace(SELECT_SHIP_CREW));
+        let args : Vec<&dyn ToSql> = SelectShipCrew { ship: &"Indefatigable" }.into_iter().collect();
+        assert_eq!(1, args.len());
+    }
+}
+
+mod item_attributes {
+    use super::ToSql;
+
+    include_sql::inline_sql!("
+        -- name: select_ship_crew
+        -- attr: deprecated(note = \"use select_sailors\")
+        SELECT name FROM sailors WHERE ship_id = :ship
+
+        -- name: select_sailors
+        SELECT name FROM sailors WHERE ship_id = :ship
+
+        -- name: count_ships
+        -- attr: must_use
+        SELECT count(*) FROM ships
+    ", "$", attrs = [allow(dead_code)], registry = true, functions = executor);
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_statements_can_still_be_used() {
+        assert_eq!(SELECT_SAILORS, SELECT_SHIP_CREW);
+        let args : Vec<&dyn ToSql> = SelectShipCrew { ship: &"Indefatigable" }.into_iter().collect();
+        assert_eq!(1, args.len());
+        assert_eq!(3, SQL_STATEMENTS.len());
+    }
+}
+
+mod exported {
+    // exported items are public, thus so is the trait of their arguments
+    pub trait ToSql {
+        fn to_sql(&self) -> &str;
+    }
+
+    impl ToSql for &str {
+        fn to_sql(&self) -> &str {
+            self
+        }
+    }
+
+    include_sql::inline_sql!("
+        -- name: select_sailors_by_rank
+        SELECT name FROM sailors WHERE ship_id = :ship AND rank = :rank
+    ", "$", export = crate::exported);
+
+    mod users {
+        use super::ToSql;
+
+        #[test]
+        fn exported_macros_refer_to_structs_by_path() {
+            let args : &[&dyn ToSql] = using_select_sailors_by_rank_args! { ship: &"Indefatigable", rank: &"captain" };
+            assert_eq!("captain", args[1].to_sql());
+            let args = super::SelectSailorsByRank { ship: &"Endeavour", rank: &"midshipman" };
+            assert_eq!("Endeavour", args.ship.to_sql());
+        }
+    }
+}
+
+mod explain {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/stmt_with_explain.sql", dialect = postgres);
+
+    #[test]
+    fn explain_variants_share_arguments() {
+        assert_eq!("EXPLAIN SELECT count(*) FROM sailors WHERE ship_id = $1", SELECT_CREW_SIZE_EXPLAIN);
+        assert_eq!("EXPLAIN ANALYZE SELECT count(*) FROM sailors WHERE ship_id = $1", SELECT_CREW_SIZE_EXPLAIN_ANALYZE);
+        assert_eq!("EXPLAIN SELECT DISTINCT rank FROM sailors WHERE ship_id = $1", SELECT_CREW_RANKS_EXPLAIN);
+        let args : Vec<&dyn ToSql> = SelectCrewSize { ship: &"Indefatigable" }.into_iter().collect();
+        assert_eq!(1, args.len());
+    }
+
+    mod sqlite {
+        use super::super::ToSql;
+        use include_sql::include_sql;
+
+        include_sql!("proc-macro/tests/stmt_with_query_plan.sql", dialect = sqlite, macro_prefix = "sqlite_");
+
+        #[test]
+        fn explain_uses_dialect_prefix() {
+            assert_eq!("EXPLAIN QUERY PLAN SELECT DISTINCT rank FROM sailors WHERE ship_id = ?1", SELECT_CREW_RANKS_EXPLAIN);
+        }
+    }
+
+    mod every_statement {
+        use super::super::ToSql;
+
+        include_sql::inline_sql!("
+            -- name: create_sailors!
+            CREATE TABLE sailors (ship_id INTEGER, name TEXT, rank TEXT)
+
+            -- name: select_crew_size
+            -- generate: explain
+            SELECT count(*) FROM sailors WHERE ship_id = :ship
+
+            -- name: insert_sailor!
+            INSERT INTO sailors (ship_id, name, rank) VALUES (:ship, :name, :rank)
+
+            -- name: select_crew_by_ranks
+            SELECT name FROM sailors WHERE rank IN (:ranks)
+        ", dialect = postgres, generate = explain_analyze);
+
+        #[test]
+        fn queries_and_data_changes_get_explain_variants() {
+            assert_eq!("EXPLAIN ANALYZE SELECT count(*) FROM sailors WHERE ship_id = $1", SELECT_CREW_SIZE_EXPLAIN_ANALYZE);
+            assert_eq!("EXPLAIN SELECT count(*) FROM sailors WHERE ship_id = $1", SELECT_CREW_SIZE_EXPLAIN);
+            assert_eq!("EXPLAIN ANALYZE INSERT INTO sailors (ship_id, name, rank) VALUES ($1, $2, $3)", INSERT_SAILOR_EXPLAIN_ANALYZE);
+            let args : Vec<&dyn ToSql> = InsertSailor { ship: &"Indefatigable", name: &"Hornblower", rank: &"midshipman" }.into_iter().collect();
+            assert_eq!(3, args.len());
+        }
+    }
+}
+
+mod zero_based_numbering {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialects.sql", "?", numbering = 0);
+
+    #[test]
+    fn placeholders_are_numbered_from_zero() {
+        assert_eq!(
+            "select object_name from user_objects where owner = ?0 or created_by = ?0 and status = ?1",
+            collapce_whitespace(SELECT_OWNER_OBJECTS)
+        );
+        let (sql, args) = SelectDialectObjects {
+            object_types: &[ &"TABLE" as &dyn ToSql, &"VIEW" ],
+            owner: &"SYS"
+        }.into_sql_with_args();
+        assert_eq!(
+            "select object_name from user_objects where object_type in ( ?1,?2 ) and owner = ?0 or object_type in ( ?1,?2 ) and owner = ?0",
+            collapce_whitespace(&sql)
+        );
+        let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["SYS", "TABLE", "VIEW"], args);
+    }
+}
+
+mod hot_reload {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialects.sql", "$", hot_reload = true);
+
+    #[test]
+    fn statements_are_reloaded_from_the_file() {
+        // the file is unchanged, thus the reloaded text is the compiled one
+        assert_eq!(SELECT_OWNER_OBJECTS, select_owner_objects_sql());
+        assert_eq!(SELECT_OWNER_OBJECTS, select_owner_objects_sql());
+        // IN lists are expanded at run time and the statement is not reloaded
+        assert_eq!(SELECT_DIALECT_OBJECTS, select_dialect_objects_sql());
+    }
+}
+
+mod path_roots {
+    const SELECT_INVALID_OBJECTS_TEXT : &str = "select object_name from user_objects where object_type = $1 and status = 'INVALID'";
+
+    mod crate_root {
+        use crate::ToSql;
+        include_sql::include_sql!("tests/stmt_with_params.sql", "$", root = "crate");
+
+        #[test]
+        fn path_is_relative_to_the_package() {
+            assert_eq!(super::SELECT_INVALID_OBJECTS_TEXT, SELECT_INVALID_OBJECTS);
+        }
+    }
+
+    mod file_root {
+        use crate::ToSql;
+        include_sql::include_sql!("stmt_with_params.sql", "$", root = "file");
+
+        #[test]
+        fn path_is_relative_to_the_file_with_the_macro() {
+            assert_eq!(super::SELECT_INVALID_OBJECTS_TEXT, SELECT_INVALID_OBJECTS);
+            assert_eq!("proc-macro/tests/stmt_with_params.sql", SELECT_INVALID_OBJECTS_INFO.file);
+        }
+    }
+
+    mod workspace_root {
+        use crate::ToSql;
+        include_sql::include_sql!("proc-macro/tests/stmt_with_params.sql", "$", root = "workspace");
+
+        #[test]
+        fn path_is_relative_to_the_workspace() {
+            assert_eq!(super::SELECT_INVALID_OBJECTS_TEXT, SELECT_INVALID_OBJECTS);
+        }
+    }
+}
+
+mod path_macros {
+    use super::ToSql;
+    include_sql::include_sql!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/", "stmt_with_params", ".sql"), concat!("$"));
+
+    #[test]
+    fn path_is_concatenated() {
+        assert_eq!("select object_name from user_objects where object_type = $1 and status = 'INVALID'", SELECT_INVALID_OBJECTS);
+        assert!(SELECT_INVALID_OBJECTS_INFO.file.ends_with("proc-macro/tests/stmt_with_params.sql"));
+    }
+}
+
+mod several_files {
+    use super::ToSql;
+    include_sql::include_sql!(["proc-macro/tests/stmt_with_params.sql", "proc-macro/tests/named_statements.sql"], "$", registry = true);
+
+    #[test]
+    fn statements_of_all_files_are_included() {
+        assert_eq!("select object_name from user_objects where object_type = $1 and status = 'INVALID'", SELECT_INVALID_OBJECTS);
+        assert_eq!("proc-macro/tests/stmt_with_params.sql", SELECT_INVALID_OBJECTS_INFO.file);
+        assert_eq!("proc-macro/tests/named_statements.sql", USER_TABLES_COUNT_INFO.file);
+        let names : Vec<&str> = SQL_STATEMENTS.iter().map(|&(name, _)| name).collect();
+        assert_eq!(["select_invalid_objects", "dual_output", "user_tables_count"], names.as_slice());
+    }
+}
+
+mod generic_args {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/dialects.sql", dialect = postgres, args = generic);
+
+    #[test]
+    fn generic_fields() {
+        let args = SelectOwnerObjects { owner: "SCOTT", status: "VALID" };
+        let args = args.args();
+        assert_eq!(2, args.len());
+        assert_eq!("SCOTT", args[0].to_sql());
+        assert_eq!("VALID", args[1].to_sql());
+    }
+
+    #[test]
+    fn generic_list_fields() {
+        let args = SelectDialectObjects { owner: "SCOTT", object_types: &["TABLE", "VIEW"] };
+        let (sql, args) = args.into_sql_with_args();
+        assert_eq!(
+            "select object_name from user_objects where object_type in ( $2,$3 ) and owner = $1 or object_type in ( $2,$3 ) and owner = $1",
+            collapce_whitespace(&sql)
+        );
+        let args : Vec<&str> = args.iter().map(|arg| arg.to_sql()).collect();
+        assert_eq!(vec!["SCOTT", "TABLE", "VIEW"], args);
+    }
+
+    mod sync_args {
+        use super::super::ToSql;
+        use include_sql::include_sql;
+
+        include_sql!("proc-macro/tests/stmt_with_params.sql", ":", args = generic, arg_bounds = Sync + Send);
+
+        fn bind(args: &[&(dyn ToSql + Sync + Send)]) -> Vec<String> {
+            args.iter().map(|arg| arg.to_sql().to_string()).collect()
+        }
+
+        #[test]
+        fn generic_fields_with_bounds() {
+            let args = SelectInvalidObjects { object_type: "VIEW" };
+            assert_eq!(vec!["VIEW"], bind(&args.args()));
+        }
+    }
+}
+
+mod executor_functions {
+    use super::{ToSql, collapce_whitespace};
+    use include_sql::include_sql;
+    use include_sql_helper::Executor;
+
+    include_sql!("proc-macro/tests/one_unnamed_stmt.sql", ":", functions = executor);
+    include_sql!("proc-macro/tests/stmt_with_returning.sql", ":", functions = executor, macro_prefix = "exec_");
+    include_sql!("proc-macro/tests/stmt_with_cond_blocks.sql", "$", functions = executor, macro_prefix = "exec_");
+
+    /// Executor that records statements instead of executing them
+    #[derive(Default)]
+    struct Recorder {
+        executed: Vec<(&'static str, String, Vec<String>)>
+    }
+
+    impl Recorder {
+        fn record(&mut self, method: &'static str, sql: &str, args: &[&dyn ToSql]) {
+            self.executed.push((method, collapce_whitespace(sql), args.iter().map(|arg| arg.to_sql().to_string()).collect()));
+        }
+    }
+
+    impl<'t> Executor<dyn ToSql + 't> for Recorder {
+        type Row = String;
+        type Error = String;
+
+        fn prepare(&mut self, _sql: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn query(&mut self, sql: &str, args: &[&(dyn ToSql + 't)]) -> Result<Vec<String>, String> {
+            self.record("query", sql, args);
+            Ok(vec!["row".to_string()])
+        }
+
+        fn query_one(&mut self, sql: &str, args: &[&(dyn ToSql + 't)]) -> Result<String, String> {
+            self.record("query_one", sql, args);
+            Ok("row".to_string())
+        }
+
+        fn execute(&mut self, sql: &str, args: &[&(dyn ToSql + 't)]) -> Result<u64, String> {
+            self.record("execute", sql, args);
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn statements_are_executed_via_executor() {
+        let mut executor = Recorder::default();
+        assert_eq!(Ok(vec!["row".to_string()]), one_unnamed_stmt(&mut executor));
+        assert_eq!(Ok("row".to_string()), exec_promote_objects(&mut executor, PromoteObjects { status: &"VALID", object_names: &[ &"F1" as &dyn ToSql, &"F2" ] }));
+        assert_eq!(Ok(1), exec_delete_object(&mut executor, DeleteObject { object_name: &"F1" }));
+        exec_search_sailors(&mut executor, SearchSailors { ship: &"Enterprise", rank: None, name: Some(&"W%") }).unwrap();
+        assert_eq!(
+            vec![
+                ("query", "select * from dual".to_string(), vec![]),
+                ("query_one", "update user_objects set status = :1 where object_name in ( :2,:3 ) returning object_id".to_string(), vec!["VALID".to_string(), "F1".to_string(), "F2".to_string()]),
+                ("execute", "delete from user_objects where object_name = :1".to_string(), vec!["F1".to_string()]),
+                ("query", "SELECT id, name, rank FROM sailors WHERE ship_id = $1 AND (name LIKE $2 OR nickname LIKE $2) AND ship_id = $1 ORDER BY name".to_string(), vec!["Enterprise".to_string(), "W%".to_string()])
+            ],
+            executor.executed
+        );
+    }
+
+    mod generic {
+        use super::{super::ToSql, Recorder};
+        use include_sql::include_sql;
+
+        include_sql!("proc-macro/tests/dialects.sql", dialect = postgres, args = generic, functions = executor);
+
+        #[test]
+        fn generic_arguments_are_executed_via_executor() {
+            let mut executor = Recorder::default();
+            select_owner_objects(&mut executor, &SelectOwnerObjects { owner: "SCOTT", status: "VALID" }).unwrap();
+            select_dialect_objects(&mut executor, &SelectDialectObjects { owner: "SCOTT", object_types: &["TABLE", "VIEW"] }).unwrap();
+            let args : Vec<&Vec<String>> = executor.executed.iter().map(|(_, _, args)| args).collect();
+            assert_eq!(vec![&vec!["SCOTT".to_string(), "VALID".to_string()], &vec!["SCOTT".to_string(), "TABLE".to_string(), "VIEW".to_string()]], args);
+            assert!(executor.executed[1].1.contains("in ( $2,$3 ) and owner = $1"));
+        }
+
+        include_sql!("proc-macro/tests/stmt_with_cache.sql", "$", args = generic, functions = executor);
+
+        #[test]
+        fn cached_results_are_reused_until_they_expire() {
+            let mut executor = Recorder::default();
+            let cache = include_sql_helper::StatementCache::new();
+            let first = select_ship_crew_cached(&cache, &mut executor, &SelectShipCrew { ship: "Enterprise" }).unwrap();
+            let second = select_ship_crew_cached(&cache, &mut executor, &SelectShipCrew { ship: "Enterprise" }).unwrap();
+            assert!(std::sync::Arc::ptr_eq(&first, &second));
+            select_ship_crew_cached(&cache, &mut executor, &SelectShipCrew { ship: "Voyager" }).unwrap();
+            assert_eq!(2, executor.executed.len());
+
+            let cache = include_sql_helper::StatementCache::new();
+            select_ship_names_cached(&cache, &mut executor).unwrap();
+            select_ship_names_cached(&cache, &mut executor).unwrap();
+            assert_eq!(3, executor.executed.len());
+        }
+    }
+}
+
+mod named_binding {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/stmt_with_named_params.sql", dialect = sqlite, bind = named, macro_prefix = "named_");
+
+    fn bind<'a>(args: &[(&'a str, &'a dyn ToSql)]) -> Vec<(&'a str, &'a str)> {
+        args.iter().map(|(name, arg)| (*name, arg.to_sql())).collect()
+    }
+
+    #[test]
+    fn statement_text_is_not_rewritten() {
+        assert_eq!("SELECT id, name FROM sailors WHERE ship_id = :ship AND (:rank IS NULL OR rank = :rank)", SELECT_SAILORS);
+        let args = SelectSailors { ship: &"Enterprise", rank: Some(&"captain") };
+        assert_eq!(vec![(":ship", "Enterprise"), (":rank", "captain")], bind(&args.named_args()));
+        let args = SelectSailors { ship: &"Enterprise", rank: None };
+        assert_eq!(vec![(":ship", "Enterprise"), (":rank", "NULL")], bind(&args.named_args()));
+    }
+
+    #[test]
+    fn argument_macros_return_named_pairs() {
+        let args = using_named_select_sailors_args! { ship: &"Enterprise", rank: Some(&"captain") };
+        assert_eq!(vec![(":ship", "Enterprise"), (":rank", "captain")], bind(args));
+    }
+
+    mod oracle {
+        use super::super::ToSql;
+        use super::bind;
+        use include_sql::include_sql;
+
+        include_sql!("proc-macro/tests/stmt_with_named_params.sql", "", bind = named, macro_prefix = "oracle_");
+
+        #[test]
+        fn argument_names_use_provided_prefix() {
+            let owned_args = SelectSailorsOwned { ship: Box::new("Enterprise"), rank: None };
+            assert_eq!(vec![("ship", "Enterprise"), ("rank", "NULL")], bind(&owned_args.as_args().named_args()));
+        }
+    }
+}
+
+#[cfg(feature = "schema-snapshot")]
+mod schema_snapshot {
+    use super::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/schema_check.sql", dialect = postgres, schema = "proc-macro/tests/schema_check.json");
+
+    #[test]
+    fn statements_match_schema_snapshot() {
+        assert_eq!(3, using_insert_sailor_args! { ship: &"1", name: &"Styles", rank: &"seaman" }.len());
+        let (_sql, args) = UpdateRanks { rank: &"captain", ids: &[ &"1" as &dyn ToSql ] }.into_sql_with_args();
+        assert_eq!(2, args.len());
+    }
+}
+
+#[include_sql::include_sql_mod("proc-macro/tests/stmt_with_params.sql", "$")]
+mod pg_objects {}
+
+#[include_sql::include_sql_mod("proc-macro/tests/stmt_with_params.sql", "?", macro_prefix = "sqlite_")]
+mod sqlite_objects {
+    pub(super) const DIALECT : &str = "sqlite";
+
+    #[test]
+    fn argument_macros_in_module() {
+        let args = sqlite_select_invalid_objects_args! { object_type: &"VIEW" };
+        assert_eq!("VIEW", args[0].to_sql());
+    }
+}
+
+#[test]
+fn statements_included_into_modules() {
+    assert_eq!(
+        "select object_name from user_objects where object_type = $1 and status = 'INVALID'",
+        collapce_whitespace(pg_objects::SELECT_INVALID_OBJECTS)
+    );
+    assert_eq!(
+        "select object_name from user_objects where object_type = ?1 and status = 'INVALID'",
+        collapce_whitespace(sqlite_objects::SELECT_INVALID_OBJECTS)
+    );
+    assert_eq!("sqlite", sqlite_objects::DIALECT);
+    let args = pg_objects::SelectInvalidObjects { object_type: &"PACKAGE" }.as_array();
+    assert_eq!("PACKAGE", args[0].to_sql());
+}
+
+#[include_sql::include_sql_mod("proc-macro/tests/stmt_kinds.sql", "$", registry = true)]
+mod registered {}
+
+#[test]
+fn statement_registry() {
+    let names : Vec<&str> = registered::SQL_STATEMENTS.iter().map(|(name, _)| *name).collect();
+    assert_eq!(vec!["select_tmp_objects", "insert_tmp_object", "delete_tmp_objects"], names);
+    assert_eq!(registered::INSERT_TMP_OBJECT, registered::SQL_STATEMENTS[1].1);
+}
+
+include_sql::inline_sql!("
+    -- name: select_ship_by_name
+    SELECT id, name FROM ships WHERE name = :name
+    -- name: scrap_ship!
+    DELETE FROM ships WHERE id = :id
+", "$");
+
+#[test]
+fn inline_statements() {
+    assert_eq!("SELECT id, name FROM ships WHERE name = $1", collapce_whitespace(SELECT_SHIP_BY_NAME));
+    assert_eq!("DELETE FROM ships WHERE id = $1", collapce_whitespace(SCRAP_SHIP));
+    let args = using_select_ship_by_name_args! { name: &"Endeavour" };
+    assert_eq!("Endeavour", args[0].to_sql());
+    assert_eq!(include_sql_helper::StatementKind::Execute, SCRAP_SHIP_KIND);
+}
+
+include_sql::inline_sql!("
+    -- name: select_cargo_by_type
+    SELECT name FROM cargo WHERE type = :type AND hold = :move OR kind IN (:in)
+    -- name: select_cargo_in_hold
+    SELECT name FROM cargo WHERE type = :type AND hold = :move
+", "$");
+
+#[test]
+fn keyword_parameters() {
+    let args = using_select_cargo_in_hold_args! { r#type: &"crate", r#move: &"aft" };
+    assert_eq!(["crate", "aft"], [args[0].to_sql(), args[1].to_sql()]);
+    let (sql, args) = SelectCargoByType { r#type: &"crate", r#move: &"aft", r#in: [&"ore" as &dyn ToSql].iter() }.into_sql_with_args();
+    assert_eq!("SELECT name FROM cargo WHERE type = $1 AND hold = $2 OR kind IN ($3)", collapce_whitespace(&sql));
+    assert_eq!(3, args.len());
+    assert_eq!(["type", "move", "in"], SELECT_CARGO_BY_TYPE_INFO.params);
+}
+
+include_sql::inline_sql!("
+    -- name: select_watch_by_time
+    SELECT name FROM watches WHERE starts = '12:30' AND note <> 'it''s :late' AND ship = :ship -- captain's watch
+      AND ends > :ends
+", "$");
+
+#[test]
+fn parameters_in_string_literals() {
+    assert_eq!(
+        "SELECT name FROM watches WHERE starts = '12:30' AND note <> 'it''s :late' AND ship = $1 -- captain's watch AND ends > $2",
+        collapce_whitespace(SELECT_WATCH_BY_TIME)
+    );
+    assert_eq!(["ship", "ends"], SELECT_WATCH_BY_TIME_INFO.params);
+}
+
+include_sql::inline_sql!(r"
+    -- name: select_watch_shifts
+    SELECT shifts[1:2] FROM watches WHERE note <> E'it\'s :late' AND ship = :ship
+", "$");
+
+#[test]
+fn array_slices_and_escape_strings() {
+    assert_eq!(
+        r"SELECT shifts[1:2] FROM watches WHERE note <> E'it\'s :late' AND ship = $1",
+        collapce_whitespace(SELECT_WATCH_SHIFTS)
+    );
+    assert_eq!(["ship"], SELECT_WATCH_SHIFTS_INFO.params);
+
+    let placeholders = include_sql_helper::Placeholders { prefix: "$", first: Some(1), dedup: true };
+    let sql = "-- name: select_watch_shifts\nSELECT shifts[1:2] FROM watches WHERE note <> E'it\\'s :late' AND ship = :ship\n";
+    let (text, bound) = include_sql_helper::reload_statement(sql, "select_watch_shifts", &placeholders).unwrap();
+    assert_eq!(collapce_whitespace(SELECT_WATCH_SHIFTS), text);
+    assert_eq!(vec!["ship"], bound);
+}
+
+include_sql::inline_sql!("
+    -- name: create_crew_count_fn!
+    CREATE FUNCTION crew_count(ship INTEGER) RETURNS BIGINT AS $body$
+    DECLARE
+        total BIGINT := 0;
+    BEGIN
+        SELECT count(*) INTO total FROM sailors WHERE ship_id = ship AND rank <> ':cadet';
+        RETURN total;
+    END
+    $body$ LANGUAGE plpgsql
+    -- name: select_ship_with_motto
+    SELECT $$motto: 'none'$$ || name FROM ships WHERE id = :id
+", "$");
+
+#[test]
+fn dollar_quoted_bodies() {
+    assert!(CREATE_CREW_COUNT_FN.contains("total BIGINT := 0;"));
+    assert!(CREATE_CREW_COUNT_FN.contains("rank <> ':cadet';"));
+    assert!(CREATE_CREW_COUNT_FN_INFO.params.is_empty());
+    assert_eq!("SELECT $$motto: 'none'$$ || name FROM ships WHERE id = $1", collapce_whitespace(SELECT_SHIP_WITH_MOTTO));
+}
+
+include_sql::inline_sql!("
+    -- name: select_sailor_ranks_as_text
+    SELECT id::text, :rank::int AS rank FROM sailors WHERE name = :name
+    -- name: count_ship_crew!
+    BEGIN total := :start; SELECT count(*) INTO total FROM sailors WHERE ship_id = :ship; END;
+", "$");
+
+#[test]
+fn casts_and_assignments() {
+    assert_eq!("SELECT id::text, $1::int AS rank FROM sailors WHERE name = $2", collapce_whitespace(SELECT_SAILOR_RANKS_AS_TEXT));
+    assert_eq!(["rank", "name"], SELECT_SAILOR_RANKS_AS_TEXT_INFO.params);
+    assert_eq!("BEGIN total := $1; SELECT count(*) INTO total FROM sailors WHERE ship_id = $2; END;", collapce_whitespace(COUNT_SHIP_CREW));
+}
+
 /// Removes consecutive whitespaces for easy comparison
 fn collapce_whitespace(text: &str) -> String {
     let mut acc = String::with_capacity(text.len());
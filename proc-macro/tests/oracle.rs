@@ -0,0 +1,69 @@
+//! Runs the generated code against the Oracle database started by `docker-compose.yml`.
+//!
+//! `cargo test -p include-sql --features test-oracle --test oracle`
+//!
+//! The connection can be changed via `INCLUDE_SQL_ORACLE_USER`, `INCLUDE_SQL_ORACLE_PASSWORD` and
+//! `INCLUDE_SQL_ORACLE_CONNECT`.
+#![cfg(feature = "test-oracle")]
+
+use oracle::{Connection, ToSql};
+use include_sql::include_sql;
+
+include_sql!("proc-macro/tests/backends/oracle.sql", dialect = oracle);
+
+type Result<T> = std::result::Result<T,Box<dyn std::error::Error>>;
+
+fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| String::from(default))
+}
+
+fn connect() -> Result<Connection> {
+    let user = env_or("INCLUDE_SQL_ORACLE_USER", "include_sql");
+    let pass = env_or("INCLUDE_SQL_ORACLE_PASSWORD", "include_sql");
+    let conn = env_or("INCLUDE_SQL_ORACLE_CONNECT", "//localhost:1521/XEPDB1");
+    let conn = Connection::connect(&user, &pass, &conn, &[])?;
+    conn.execute(CREATE_TABLE_SAILORS, &[])?;
+    conn.execute(DELETE_SAILORS, &[])?;
+    for &(name, station) in &[ ("Pellew", "bridge"), ("Hornblower", "deck"), ("Kennedy", "deck"), ("Matthews", "galley") ] {
+        conn.execute(INSERT_SAILOR, using_insert_sailor_args! { name: &name, station: &station })?;
+    }
+    Ok(conn)
+}
+
+fn count(conn: &Connection, sql: &str, args: &[&dyn ToSql]) -> Result<i64> {
+    Ok(conn.query_row_as::<i64>(sql, args)?)
+}
+
+#[test]
+fn scalar_parameters() -> Result<()> {
+    let conn = connect()?;
+    let station = conn.query_row_as::<String>(SELECT_SAILOR_STATION, using_select_sailor_station_args! { name: &"Hornblower" })?;
+    assert_eq!("deck", station);
+    Ok(())
+}
+
+#[test]
+fn list_parameters() -> Result<()> {
+    let conn = connect()?;
+    let (sql, args) = CountSailorsAtStations { stations: &[ &"bridge" as &dyn ToSql, &"deck" ] }.into_sql_with_args();
+    assert_eq!(3, count(&conn, &sql, &args)?);
+    Ok(())
+}
+
+#[test]
+fn repeated_parameters() -> Result<()> {
+    let conn = connect()?;
+    assert_eq!(2, count(&conn, COUNT_SAILORS_NAMED_OR_STATIONED, using_count_sailors_named_or_stationed_args! { value: &"deck" })?);
+    let (sql, args) = CountSailorsInList { values: &[ &"galley" as &dyn ToSql, &"Pellew" ] }.into_sql_with_args();
+    assert_eq!(2, count(&conn, &sql, &args)?);
+    Ok(())
+}
+
+#[test]
+fn unicode_arguments() -> Result<()> {
+    let conn = connect()?;
+    conn.execute(INSERT_SAILOR, using_insert_sailor_args! { name: &"Jérôme Ærø 東郷", station: &"galley" })?;
+    let station = conn.query_row_as::<String>(SELECT_SAILOR_STATION, using_select_sailor_station_args! { name: &"Jérôme Ærø 東郷" })?;
+    assert_eq!("galley", station);
+    Ok(())
+}
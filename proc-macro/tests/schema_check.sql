@@ -0,0 +1,14 @@
+-- Statements in this test case are checked against the schema snapshot
+
+-- name: select_crew
+select s.id, s.name, rank as sailor_rank
+  from sailors s
+  join ships on ships.id = s.ship_id
+ where ships.name = :ship_name
+ order by sailor_rank
+
+-- name: insert_sailor!
+insert into sailors (ship_id, name, rank) values (:ship, :name, :rank)
+
+-- name: update_ranks!
+update sailors set rank = :rank where id in (:ids)
@@ -0,0 +1,34 @@
+//! Checks the `Serialize` implementations of the argument structs.
+//!
+//! `cargo test -p include-sql --features serde --test serde`
+#![cfg(feature = "serde")]
+
+use include_sql::inline_sql;
+
+pub trait ToSql {}
+
+impl ToSql for i32 {}
+impl ToSql for &str {}
+impl<T: ToSql> ToSql for Option<T> {}
+
+inline_sql!("
+    -- name: select_ship_crew
+    SELECT name FROM sailors WHERE ship_id = :ship AND (:rank IS NULL OR rank = :rank)
+
+    -- name: select_sailors_by_ranks
+    SELECT name FROM sailors WHERE ship_id = :ship AND rank IN (:ranks)
+", "$", args = generic, serialize_args = true);
+
+#[test]
+fn arguments_are_serialized_by_parameter_names() {
+    let args = SelectShipCrew { ship: 1701, rank: Some("captain") };
+    assert_eq!(r#"{"ship":1701,"rank":"captain"}"#, serde_json::to_string(&args).unwrap());
+    let args = SelectShipCrew { ship: 1701, rank: None::<&str> };
+    assert_eq!(r#"{"ship":1701,"rank":null}"#, serde_json::to_string(&args).unwrap());
+}
+
+#[test]
+fn list_arguments_are_serialized_as_arrays() {
+    let args = SelectSailorsByRanks { ship: 1701, ranks: &["captain", "commander"] };
+    assert_eq!(r#"{"ship":1701,"ranks":["captain","commander"]}"#, serde_json::to_string(&args).unwrap());
+}
@@ -0,0 +1,41 @@
+//! Runs the generated rusqlite functions against an in-memory SQLite database.
+//!
+//! `cargo test -p include-sql --features rusqlite,test-sqlite --test sqlite-functions`
+#![cfg(all(feature = "rusqlite", feature = "test-sqlite"))]
+
+use rusqlite::{Connection, Result};
+use rusqlite::types::ToSql;
+use include_sql::include_sql;
+
+include_sql!("proc-macro/tests/backends/sqlite.sql", dialect = sqlite, functions = rusqlite);
+
+fn connect() -> Result<Connection> {
+    let conn = Connection::open_in_memory()?;
+    create_table_sailors(&conn)?;
+    for &(name, station) in &[ ("Pellew", "bridge"), ("Hornblower", "deck"), ("Kennedy", "deck"), ("Matthews", "galley") ] {
+        insert_sailor(&conn, &name, &station)?;
+    }
+    Ok(conn)
+}
+
+#[test]
+fn scalar_parameters() -> Result<()> {
+    let conn = connect()?;
+    let stations : Vec<String> = select_sailor_station(&conn, &"Hornblower", |row| row.get(0))?;
+    assert_eq!(vec!["deck"], stations);
+    let rows = select_sailor_station(&conn, &"Hornblower", SelectSailorStationRow::from_rusqlite_row)?;
+    assert_eq!("deck", rows[0].station);
+    let count : i64 = count_sailors_named_or_stationed(&conn, &"deck", |row| row.get(0))?.remove(0);
+    assert_eq!(2, count);
+    Ok(())
+}
+
+#[test]
+fn list_parameters() -> Result<()> {
+    let conn = connect()?;
+    let count : i64 = count_sailors_at_stations(&conn, [ &"bridge" as &dyn ToSql, &"deck" ], |row| row.get(0))?.remove(0);
+    assert_eq!(3, count);
+    let count : i64 = count_sailors_in_list(&conn, vec![ &"galley" as &dyn ToSql, &"Pellew" ], |row| row.get(0))?.remove(0);
+    assert_eq!(2, count);
+    Ok(())
+}
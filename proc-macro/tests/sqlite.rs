@@ -0,0 +1,75 @@
+//! Runs the generated code against an in-memory SQLite database.
+//!
+//! `cargo test -p include-sql --features test-sqlite --test sqlite`
+#![cfg(feature = "test-sqlite")]
+
+use rusqlite::{Connection, Result, NO_PARAMS};
+use rusqlite::types::ToSql;
+use include_sql::include_sql;
+
+include_sql!("proc-macro/tests/backends/sqlite.sql", dialect = sqlite);
+
+fn connect() -> Result<Connection> {
+    let conn = Connection::open_in_memory()?;
+    conn.execute(CREATE_TABLE_SAILORS, NO_PARAMS)?;
+    for &(name, station) in &[ ("Pellew", "bridge"), ("Hornblower", "deck"), ("Kennedy", "deck"), ("Matthews", "galley") ] {
+        conn.execute(INSERT_SAILOR, InsertSailor { name: &name, station: &station })?;
+    }
+    Ok(conn)
+}
+
+fn count(conn: &Connection, sql: &str, args: &[&dyn ToSql]) -> Result<i64> {
+    conn.query_row(sql, args, |row| row.get(0))
+}
+
+#[test]
+fn scalar_parameters() -> Result<()> {
+    let conn = connect()?;
+    let station : String = conn.query_row(SELECT_SAILOR_STATION, SelectSailorStation { name: &"Hornblower" }, |row| row.get(0))?;
+    assert_eq!("deck", station);
+    Ok(())
+}
+
+#[test]
+fn list_parameters() -> Result<()> {
+    let conn = connect()?;
+    let (sql, args) = CountSailorsAtStations { stations: &[ &"bridge" as &dyn ToSql, &"deck" ] }.into_sql_with_args();
+    assert_eq!(3, count(&conn, &sql, &args)?);
+    Ok(())
+}
+
+#[test]
+fn repeated_parameters() -> Result<()> {
+    let conn = connect()?;
+    assert_eq!(2, count(&conn, COUNT_SAILORS_NAMED_OR_STATIONED, using_count_sailors_named_or_stationed_args! { value: &"deck" })?);
+    let (sql, args) = CountSailorsInList { values: &[ &"galley" as &dyn ToSql, &"Pellew" ] }.into_sql_with_args();
+    assert_eq!(2, count(&conn, &sql, &args)?);
+    Ok(())
+}
+
+#[test]
+fn unicode_arguments() -> Result<()> {
+    let conn = connect()?;
+    conn.execute(INSERT_SAILOR, InsertSailor { name: &"Jérôme Ærø 東郷", station: &"galley" })?;
+    let station : String = conn.query_row(SELECT_SAILOR_STATION, SelectSailorStation { name: &"Jérôme Ærø 東郷" }, |row| row.get(0))?;
+    assert_eq!("galley", station);
+    Ok(())
+}
+
+mod named {
+    use rusqlite::{Connection, Result, NO_PARAMS};
+    use rusqlite::types::ToSql;
+    use include_sql::include_sql;
+
+    include_sql!("proc-macro/tests/backends/sqlite_named.sql", dialect = sqlite, bind = named, macro_prefix = "named_");
+
+    #[test]
+    fn named_arguments() -> Result<()> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute(CREATE_TABLE_SAILORS, NO_PARAMS)?;
+        conn.execute_named(INSERT_SAILOR, using_named_insert_sailor_args! { name: &"Jérôme Ærø 東郷", station: &"galley" })?;
+        let count : i64 = conn.query_row_named(COUNT_SAILORS_NAMED_OR_STATIONED, &CountSailorsNamedOrStationed { value: &"galley" }.named_args(), |row| row.get(0))?;
+        assert_eq!(1, count);
+        Ok(())
+    }
+}
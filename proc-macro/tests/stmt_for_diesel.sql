@@ -0,0 +1,11 @@
+-- name: select_ship_crew
+-- diesel-bind: ship Integer, rank Text
+-- diesel-row: id Integer i32, name Text String, nickname Nullable<Text> Option<String>
+SELECT id, name, nickname
+  FROM sailors
+ WHERE ship_id = :ship
+   AND (rank = :rank OR :ship = 0)
+
+-- name: count_ships
+-- diesel-row: num_ships BigInt i64
+SELECT count(*) AS num_ships FROM ships
@@ -0,0 +1,12 @@
+-- Probe statements that are executed when the application starts
+
+-- name: ping
+-- healthcheck
+SELECT 1
+
+-- name: check_ships_table
+-- healthcheck: 250ms
+SELECT 1 FROM ships WHERE 1 = 0
+
+-- name: count_fleet_ships
+SELECT count(*) FROM ships
@@ -0,0 +1,10 @@
+-- Statement kinds are defined by the name suffixes
+
+-- name: select_tmp_objects
+select object_name from user_objects where temporary = 'Y'
+
+-- name: insert_tmp_object<!
+insert into tmp_objects (object_name) values ('T') returning id
+
+-- name: delete_tmp_objects!
+delete from tmp_objects
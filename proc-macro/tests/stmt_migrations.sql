@@ -0,0 +1,14 @@
+-- Migrations in this test case are listed out of order to show that they are sorted by version
+
+-- migration: 002 add_sailors_rank
+ALTER TABLE sailors ADD COLUMN rank TEXT
+-- down
+ALTER TABLE sailors DROP COLUMN rank
+
+-- migration: 001 create_sailors
+CREATE TABLE sailors (id INTEGER PRIMARY KEY, name TEXT NOT NULL)
+
+-- migration: 003 index_sailors_rank
+CREATE INDEX sailors_rank_idx ON sailors (rank)
+-- down
+DROP INDEX sailors_rank_idx
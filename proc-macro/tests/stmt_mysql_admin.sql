@@ -0,0 +1,21 @@
+-- MySQL administrative statements with session variables
+
+-- name: reset_row_counter!
+SET @row_no := 0
+
+-- name: select_numbered_sailors
+SELECT @row_no := @row_no + 1 AS row_no, name
+  FROM sailors
+ WHERE ship_id = :ship
+ ORDER BY name
+
+-- name: remember_ship!
+SET @ship:=:ship, @@session.sql_mode = 'ANSI_QUOTES'
+
+-- name: load_sailors!
+LOAD DATA LOCAL INFILE '/var/lib/mysql-files/fleet:sailors.csv'
+INTO TABLE sailors
+FIELDS TERMINATED BY ',' ENCLOSED BY '"'
+IGNORE 1 LINES
+(name, @station)
+SET station = UPPER(@station)
@@ -0,0 +1,19 @@
+-- The statements in this test case have projections of various shapes
+
+-- name: select_crew_summary
+SELECT s.name, coalesce(s.rank, 'none, yet') AS "rank, if any", count(*) OVER (PARTITION BY s.ship_id, s.rank)
+  FROM sailors s -- a comment, with a comma
+ WHERE s.ship_id = :ship
+
+-- name: select_ranked_crew
+WITH ranked AS (
+  SELECT id, name, rank() OVER (ORDER BY enlisted_at) AS pos FROM sailors
+)
+SELECT DISTINCT name, pos FROM ranked ORDER BY pos
+
+-- name: insert_ship<!
+INSERT INTO ships (name, class) SELECT :name, class FROM ship_classes WHERE id = :class
+RETURNING id, name, created_at
+
+-- name: select_all_sailors
+SELECT * FROM sailors
@@ -0,0 +1,10 @@
+-- The statement in this test case is a script of several statements
+
+-- name: init_crew_schema (script)
+CREATE TABLE ships (
+    id   INTEGER PRIMARY KEY,
+    name TEXT NOT NULL -- the name; is unique
+);
+CREATE TABLE sailors (id INTEGER PRIMARY KEY, ship_id INTEGER, name TEXT, motto TEXT DEFAULT 'Engage; make it so');
+/* seed data; not needed in production */
+INSERT INTO ships (id, name) VALUES (1, 'Enterprise');
@@ -0,0 +1,8 @@
+-- Payments are only executed as static prepared statements
+-- strict: static-only
+
+-- name: select_payment
+SELECT id, amount, status FROM payments WHERE id = :id
+
+-- name: settle_payment!
+UPDATE payments SET status = 'SETTLED' WHERE id = :id AND status = :status
@@ -0,0 +1,4 @@
+-- Build metadata is interpolated into the statement text
+
+-- name: select_tagged_ships
+SELECT /* app {{build.version}} */ id, name FROM ships WHERE id = :id
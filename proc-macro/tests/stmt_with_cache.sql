@@ -0,0 +1,9 @@
+-- Statements with results that can be cached
+
+-- name: select_ship_names
+-- cache: 30s
+SELECT id, name FROM ships
+
+-- name: select_ship_crew
+-- cache: 500ms
+SELECT id, name FROM sailors WHERE ship_id = :ship
@@ -0,0 +1,13 @@
+-- The statements in this test case split their IN lists into chunks
+
+-- name: select_sailors_in_chunks
+-- in-chunks: 2
+SELECT id
+  FROM sailors s
+ WHERE s.ship_id = :ship
+   AND s.id IN (:ids)
+   AND s.rank NOT IN (:ranks)
+
+-- name: select_sailors_by_ranks_in_chunks
+-- in-chunks: 2
+SELECT id FROM sailors WHERE rank IN (:ranks) OR prev_rank IN (:ranks)
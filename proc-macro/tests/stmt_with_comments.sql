@@ -0,0 +1,9 @@
+-- name: select_ship_logs
+-- Selects log entries of the ship
+SELECT entry /* the text */, note || ' -- not a comment' AS note
+  FROM ship_logs
+-- entries of the ship only
+ WHERE ship_id = :ship -- :author is compared below
+   /* written
+      by the captain */
+   AND author = 'captain'
@@ -0,0 +1,12 @@
+-- name: search_sailors
+SELECT id, name, rank
+  FROM sailors
+ WHERE ship_id = :ship
+-- if :rank
+   AND rank = :rank
+-- end
+-- if :name
+   AND (name LIKE :name OR nickname LIKE :name)
+   AND ship_id = :ship
+-- end
+ ORDER BY name
@@ -0,0 +1,7 @@
+-- Constants of the file are interpolated into the statement text
+
+-- define: table_prefix = app_
+-- define: schema = crew
+
+-- name: select_prefixed_sailors
+SELECT id, name FROM {{schema}}.{{table_prefix}}sailors WHERE ship_id = :ship
@@ -0,0 +1,12 @@
+﻿-- Statements of a file saved on Windows
+
+-- name: select_ship_crew
+-- Selects sailors of the ship
+SELECT id, name
+  FROM sailors
+ WHERE ship_id = :ship
+
+-- name: select_ship_name
+SELECT name
+  FROM ships
+ WHERE id = :id
@@ -0,0 +1,17 @@
+-- The statements in this test case replace IN lists that have no items
+
+-- name: select_sailors_of_ranks
+-- empty-in: null
+SELECT id FROM sailors WHERE rank IN (:ranks)
+
+-- name: select_sailors_except
+-- empty-in: false
+SELECT id
+  FROM sailors
+ WHERE ship_id = :ship
+   AND rank IN (:ranks)
+   AND id NOT IN (:excluded)
+
+-- name: select_sailors_by_ids
+-- empty-in: error
+SELECT id FROM sailors WHERE id IN (:ids)
@@ -0,0 +1,4 @@
+-- Environment variables are interpolated into the statement text
+
+-- name: select_package_settings
+SELECT value FROM ${SCHEMA}.settings WHERE package = '${CARGO_PKG_NAME}' AND name = :name
@@ -0,0 +1,9 @@
+-- Statements that also have EXPLAIN variants
+
+-- name: select_crew_size
+-- generate: explain, explain_analyze
+SELECT count(*) FROM sailors WHERE ship_id = :ship
+
+-- name: select_crew_ranks
+-- generate: explain
+SELECT DISTINCT rank FROM sailors WHERE ship_id = :ship
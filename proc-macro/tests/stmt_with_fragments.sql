@@ -0,0 +1,16 @@
+-- fragment: sailor_columns
+id, name, rank,
+ship_id, enlisted
+
+-- fragment: active_sailors
+FROM sailors
+WHERE discharged IS NULL
+
+-- name: select_active_sailors
+SELECT :{sailor_columns}
+-- use: active_sailors
+
+-- name: select_active_ship_sailors
+SELECT :{ sailor_columns }
+-- use: active_sailors
+AND ship_id = :ship
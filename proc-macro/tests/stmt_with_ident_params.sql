@@ -0,0 +1,13 @@
+-- The statements in this test case splice identifiers from their allowlists
+
+-- name: select_crew_sorted
+-- allow sort_column: name, rank, s.enlisted_at
+-- allow sort_order: asc, desc
+SELECT s.id, s.name, s.rank
+  FROM sailors s
+ WHERE s.ship_id = :ship
+ ORDER BY :#sort_column :#sort_order, s.id
+
+-- name: count_archived
+-- allow archive: sailors_2019, sailors_2020
+SELECT count(*) FROM :#archive
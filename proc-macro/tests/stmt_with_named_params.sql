@@ -0,0 +1,5 @@
+-- Statements in this test case are executed with arguments bound by name
+
+-- name: select_sailors
+-- param: rank? &str
+SELECT id, name FROM sailors WHERE ship_id = :ship AND (:rank IS NULL OR rank = :rank)
@@ -0,0 +1,9 @@
+-- Optional parameters are bound as NULLs when their values are missing
+
+-- name: select_sailors
+-- param: rank? &str
+SELECT id, name FROM sailors WHERE ship_id = :ship AND (:rank IS NULL OR rank = :rank)
+
+-- name: select_fleet_sailors
+-- param: rank? &str
+SELECT id, name FROM sailors WHERE ship_id IN (:ships) AND (:rank IS NULL OR rank = :rank)
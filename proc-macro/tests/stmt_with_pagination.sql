@@ -0,0 +1,12 @@
+-- Queries that return their rows page by page
+
+-- name: select_ship_crew
+-- paginate
+SELECT name, rank
+  FROM sailors
+ WHERE ship_id = :ship
+ ORDER BY name -- pages need a stable order
+
+-- name: select_ship_crew_rownum
+-- paginate: rownum
+SELECT name, rank FROM sailors WHERE ship_id = :ship ORDER BY name;
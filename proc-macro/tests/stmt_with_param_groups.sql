@@ -0,0 +1,12 @@
+-- Statements of the tenant share the parameters of the context group
+
+-- group: context = tenant, user
+
+-- name: select_tenant_sailors
+SELECT id, name FROM sailors WHERE tenant_id = :tenant AND ship_id = :ship AND created_by = :user
+
+-- name: count_tenant_ships
+SELECT count(*) FROM ships WHERE owner = :user AND tenant_id = :tenant
+
+-- name: select_tenant
+SELECT name FROM tenants WHERE id = :tenant
@@ -0,0 +1,9 @@
+-- Parameters of the statement are numbered in the explicitly specified order
+
+-- name: select_ordered_sailors
+-- param-order: ship, rank, name
+SELECT id FROM sailors WHERE name LIKE :name AND rank = :rank AND ship_id = :ship OR escort_of = :ship
+
+-- name: select_ranked_fleet_sailors
+-- param-order: rank, ship
+SELECT id FROM sailors WHERE ship_id = :ship AND id IN (:ids) AND rank = :rank
@@ -0,0 +1,5 @@
+-- Statement that also has an EXPLAIN variant in a dialect that does not support EXPLAIN ANALYZE
+
+-- name: select_crew_ranks
+-- generate: explain
+SELECT DISTINCT rank FROM sailors WHERE ship_id = :ship
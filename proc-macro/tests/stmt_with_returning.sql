@@ -0,0 +1,12 @@
+-- The statements in this test case are executed by the same generic code
+
+-- name: promote_objects<!
+-- Statement with the IN list that also returns rows
+update user_objects
+   set status = :status
+ where object_name in ( :object_names )
+returning object_id
+
+-- name: delete_object!
+-- idempotent
+delete from user_objects where object_name = :object_name
@@ -0,0 +1,9 @@
+-- The statements in this test case declare the types of the columns they return
+
+-- name: select_crew_roster
+-- returns: id i32, name String, rank Option<String>
+SELECT id, name, rank FROM sailors WHERE ship_id = :ship
+
+-- name: insert_located_ship<!
+-- returns: id i64, name String
+INSERT INTO ships (name, lat, lon) VALUES (:name, :lat, :lon) RETURNING id, name
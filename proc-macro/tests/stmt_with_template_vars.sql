@@ -0,0 +1,7 @@
+-- name: select_daily_visits
+SELECT day, visits
+  FROM {{schema}}.visits{{suffix}}
+ WHERE day >= :since
+
+-- name: purge_visits!
+DELETE FROM {{ schema }}.visits{{suffix}} WHERE day < :before
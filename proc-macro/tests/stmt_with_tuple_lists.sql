@@ -0,0 +1,13 @@
+-- The statements in this test case compare tuples of columns with lists of tuples
+
+-- name: select_sailors_by_ship_and_rank
+SELECT id, name
+  FROM sailors
+ WHERE (ship_id, rank) IN (:ships_ranks)
+   AND age > :age
+
+-- name: select_sailors_by_ship_rank_and_name
+SELECT id
+  FROM sailors
+ WHERE coalesce(nickname, name) IN (:names)
+   AND (ship_id, rank, name) NOT IN (:excluded)
@@ -0,0 +1,115 @@
+//! Checks the statement spans and the functions that execute statements inside them.
+//!
+//! `cargo test -p include-sql --features tracing --test tracing`
+#![cfg(feature = "tracing")]
+
+use include_sql::inline_sql;
+use include_sql_helper::tracing::{self, Id, Metadata, Subscriber, Event};
+use include_sql_helper::tracing::span::{Attributes, Record};
+use include_sql_helper::Executor;
+use std::sync::{Arc, Mutex};
+
+pub trait ToSql {}
+
+impl ToSql for i32 {}
+impl ToSql for &str {}
+
+inline_sql!("
+    -- name: select_ship_crew
+    SELECT name FROM sailors WHERE ship_id = :ship AND rank IN (:ranks)
+
+    -- name: count_ships
+    SELECT count(*) FROM ships
+", "$", functions = executor, instrument = true);
+
+/// Subscriber that keeps the names of the created spans and of the spans that are entered now.
+#[derive(Default)]
+struct Spans {
+    names: Mutex<Vec<&'static str>>,
+    entered: Arc<Mutex<Vec<&'static str>>>
+}
+
+impl Subscriber for Spans {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut names = self.names.lock().unwrap();
+        names.push(span.metadata().name());
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        let name = self.names.lock().unwrap()[span.into_u64() as usize - 1];
+        self.entered.lock().unwrap().push(name);
+    }
+
+    fn exit(&self, _: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+}
+
+/// Executor that remembers which spans were entered when it executed a statement.
+struct Recorder {
+    entered: Arc<Mutex<Vec<&'static str>>>,
+    executed_in: Vec<Vec<&'static str>>
+}
+
+impl<'t> Executor<dyn ToSql + 't> for Recorder {
+    type Row = ();
+    type Error = ();
+
+    fn prepare(&mut self, _: &str) -> Result<(), ()> {
+        Ok(())
+    }
+
+    fn query(&mut self, _: &str, _: &[&(dyn ToSql + 't)]) -> Result<Vec<()>, ()> {
+        self.executed_in.push(self.entered.lock().unwrap().clone());
+        Ok(Vec::new())
+    }
+
+    fn query_one(&mut self, _: &str, _: &[&(dyn ToSql + 't)]) -> Result<(), ()> {
+        self.executed_in.push(self.entered.lock().unwrap().clone());
+        Ok(())
+    }
+
+    fn execute(&mut self, _: &str, _: &[&(dyn ToSql + 't)]) -> Result<u64, ()> {
+        self.executed_in.push(self.entered.lock().unwrap().clone());
+        Ok(0)
+    }
+}
+
+#[test]
+fn spans_have_statement_and_parameter_fields() {
+    tracing::subscriber::with_default(Spans::default(), || {
+        let span = select_ship_crew_span();
+        let metadata = span.metadata().unwrap();
+        assert_eq!("select_ship_crew", metadata.name());
+        let fields : Vec<&str> = metadata.fields().iter().map(|field| field.name()).collect();
+        assert_eq!(vec!["sql.statement", "sql.file", "sql.line", "ship", "ranks"], fields);
+
+        let span = count_ships_span();
+        let fields : Vec<&str> = span.metadata().unwrap().fields().iter().map(|field| field.name()).collect();
+        assert_eq!(vec!["sql.statement", "sql.file", "sql.line"], fields);
+    });
+}
+
+#[test]
+fn functions_execute_statements_inside_their_spans() {
+    let spans = Spans::default();
+    let entered = spans.entered.clone();
+    tracing::subscriber::with_default(spans, || {
+        let mut recorder = Recorder { entered: entered.clone(), executed_in: Vec::new() };
+        select_ship_crew(&mut recorder, SelectShipCrew { ship: &1701, ranks: &[&"captain" as &dyn ToSql, &"commander"] }).unwrap();
+        count_ships(&mut recorder).unwrap();
+        assert_eq!(vec![vec!["select_ship_crew"], vec!["count_ships"]], recorder.executed_in);
+        assert!(entered.lock().unwrap().is_empty());
+    });
+}